@@ -0,0 +1,95 @@
+use nanachi::{
+    color_adjust::{Adjustment, ColorAdjust},
+    compositor,
+    context::{Context, FillStyle},
+    fill_color, fill_rule,
+    image::RgbaImage,
+    pixel::Rgba,
+    primitives,
+};
+
+fn main() {
+    let (size, rows) = (120, 2);
+    let adjustments = [
+        vec![],
+        vec![Adjustment::HueRotate(120.0)],
+        vec![Adjustment::Saturate(0.2), Adjustment::Sepia(1.0)],
+        vec![Adjustment::Grayscale(1.0), Adjustment::Threshold(0.5)],
+        vec![Adjustment::Posterize(3)],
+        vec![
+            Adjustment::Levels {
+                input_black: 0.1,
+                input_white: 0.9,
+                gamma: 1.5,
+                output_black: 0.0,
+                output_white: 1.0,
+            },
+            Adjustment::Curves(vec![(0.0, 0.0), (0.3, 0.15), (0.7, 0.85), (1.0, 1.0)]),
+        ],
+        vec![
+            Adjustment::Invert(1.0),
+            Adjustment::BrightnessContrast {
+                brightness: 0.1,
+                contrast: 0.3,
+            },
+            Adjustment::Gamma(0.8),
+        ],
+    ];
+    let width = size * adjustments.len() as u32;
+    let mut context =
+        Context::from_pixel(width, size * rows, Rgba([0.85, 0.85, 0.85, 1.0])).high_quality();
+    let gradient = fill_color::LinearGradient::new(
+        (0.0, 0.0),
+        (width as f32, 0.0),
+        vec![
+            (0.0, Rgba([0.9, 0.2, 0.2, 1.0])),
+            (0.5, Rgba([0.2, 0.8, 0.3, 1.0])),
+            (1.0, Rgba([0.2, 0.3, 0.9, 1.0])),
+        ],
+    );
+
+    // The first row adjusts the colors on the fly with `Adjusted`.
+    for (i, adjustments) in adjustments.iter().enumerate() {
+        let adjust = ColorAdjust::new(adjustments);
+        let color = fill_color::Adjusted::new(&gradient, &adjust);
+        let x = (size as usize * i) as f32;
+        context.fill(
+            &primitives::circle(x + size as f32 / 2.0, size as f32 / 2.0, 50.0),
+            &FillStyle::new(color, compositor::SrcOver, fill_rule::NonZero),
+        );
+    }
+
+    // The second row adjusts rendered images.
+    for (i, adjustments) in adjustments.iter().enumerate() {
+        let mut panel = Context::from_pixel(size, size, Rgba([0.0, 0.0, 0.0, 0.0])).high_quality();
+        panel.fill(
+            &primitives::rounded_rect(10.0, 10.0, 100.0, 100.0, [20.0; 4]),
+            &FillStyle::new(
+                fill_color::RadialGradient::new(
+                    (60.0, 60.0),
+                    70.0,
+                    vec![
+                        (0.0, Rgba([1.0, 0.9, 0.3, 1.0])),
+                        (1.0, Rgba([0.6, 0.1, 0.5, 0.4])),
+                    ],
+                ),
+                compositor::SrcOver,
+                fill_rule::NonZero,
+            ),
+        );
+        ColorAdjust::new(adjustments).apply(&mut panel.image);
+        let x = (size as usize * i) as f32;
+        let s = size as f32;
+        context.draw_image(
+            &panel.image,
+            [0.0, 0.0, s, s],
+            [x, s, x + s, 2.0 * s],
+            nanachi::interpolation::NearestNeighbor,
+            &compositor::SrcOver,
+            1.0,
+        );
+    }
+
+    let img: RgbaImage = (&context.image).into();
+    img.save("./color_adjust.png").unwrap();
+}
@@ -0,0 +1,51 @@
+use nanachi::{
+    buffer::{Buffer, GenericBuffer},
+    compositor,
+    context::Context,
+    interpolation,
+    matrix::Matrix,
+    pixel::{Arithmetic, Rgba},
+};
+
+fn main() {
+    let file = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| "nanachi.png".to_string());
+    let src = image::open(file).unwrap().into_rgba32f();
+    let (width, height) = src.dimensions();
+    let mut img = GenericBuffer::from_pixel(width, height, Rgba::zero());
+    for (x, y, p) in src.enumerate_pixels() {
+        img.put_pixel(x, y, Rgba(p.0));
+    }
+    let src = img;
+
+    let mut context = Context::from_pixel(width, height, Rgba([1.0, 1.0, 1.0, 1.0]));
+    let (w, h) = (width as f32, height as f32);
+    context.draw_image(
+        &src,
+        [0.0, 0.0, w, h],
+        [0.0, 0.0, w / 2.0, h / 2.0],
+        interpolation::Mipmap::new(&src, 0.5),
+        &compositor::SrcOver,
+        1.0,
+    );
+    context
+        .transformed_context(
+            &Matrix::new()
+                .translate(-w / 4.0, -h / 4.0)
+                .rotate(0.4)
+                .skew_x(0.2)
+                .translate(w * 0.65, h * 0.6),
+        )
+        .draw_image(
+            &src,
+            [w * 0.25, h * 0.25, w * 0.75, h * 0.75],
+            [0.0, 0.0, w / 2.0, h / 2.0],
+            interpolation::Bicubic::catmull_rom(),
+            &compositor::SrcOver,
+            0.7,
+        );
+
+    let img: image::RgbaImage = (&context.image).into();
+    img.save("./context_draw_image.png").unwrap();
+}
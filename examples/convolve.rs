@@ -0,0 +1,73 @@
+use nanachi::{
+    buffer::{Buffer, GenericBuffer},
+    compositor,
+    context::{Context, FillStyle},
+    contrib::{
+        convolve::{edge_detect, emboss, motion_blur, unsharp_mask, EdgeOperator},
+        gauss_blur::Extrapolation,
+    },
+    fill_color, fill_rule,
+    image::RgbaImage,
+    interpolation,
+    pixel::Rgba,
+    primitives,
+};
+
+fn main() {
+    let size = 160;
+    let style = |color: Rgba| {
+        FillStyle::new(
+            fill_color::Solid::new(color),
+            compositor::SrcOver,
+            fill_rule::NonZero,
+        )
+    };
+
+    let mut scene = Context::from_pixel(size, size, Rgba([1.0, 1.0, 1.0, 1.0])).high_quality();
+    scene.fill(
+        &primitives::star(80.0, 80.0, 5, 60.0, 26.0),
+        &style(Rgba([0.2, 0.4, 0.8, 1.0])),
+    );
+    scene.fill(
+        &primitives::circle(110.0, 110.0, 30.0),
+        &style(Rgba([0.9, 0.5, 0.2, 0.8])),
+    );
+    let scene = scene.image;
+
+    let edge = Extrapolation::ExtendEdge;
+    let mut tmp = scene.clone();
+    let mut sharpened = scene.clone();
+    unsharp_mask(&mut sharpened, &mut tmp, 3.0, 2.0, &edge);
+    let mut edges = scene.clone();
+    edge_detect(&mut edges, &mut tmp, EdgeOperator::Sobel, &edge);
+    // Show the edges as dark lines on an opaque image.
+    let (w, h) = edges.dimensions();
+    for y in 0..h {
+        for x in 0..w {
+            let [r, g, b, _] = edges.get_pixel(x, y).0;
+            let v = 1.0 - (r + g + b).min(1.0);
+            edges.put_pixel(x, y, Rgba([v, v, v, 1.0]));
+        }
+    }
+    let mut embossed = scene.clone();
+    emboss(&mut embossed, &mut tmp, 0.8, 1.5, &edge);
+    let mut blurred = scene.clone();
+    motion_blur(&mut blurred, &mut tmp, 24.0, 0.5, &edge);
+
+    let mut context = Context::from_pixel(size * 5, size, Rgba([1.0, 1.0, 1.0, 1.0]));
+    let panels: [&GenericBuffer<Rgba>; 5] = [&scene, &sharpened, &edges, &embossed, &blurred];
+    for (i, panel) in panels.iter().enumerate() {
+        let (x, s) = ((size as usize * i) as f32, size as f32);
+        context.draw_image(
+            *panel,
+            [0.0, 0.0, s, s],
+            [x, 0.0, x + s, s],
+            interpolation::NearestNeighbor,
+            &compositor::SrcOver,
+            1.0,
+        );
+    }
+
+    let img: RgbaImage = (&context.image).into();
+    img.save("./convolve.png").unwrap();
+}
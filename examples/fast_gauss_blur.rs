@@ -28,6 +28,18 @@ fn main() {
     );
     // box_blur(&mut img, &mut tmp, 10);
     dbg!(t.elapsed());
+
+    // Blur the center horizontally more, premultiplied and with all threads.
+    let options = gauss_blur::GaussBlurOptions {
+        std_deviation_x: 20.0,
+        std_deviation_y: 1.5,
+        rect: Some([width / 4, height / 4, width * 3 / 4, height * 3 / 4]),
+        threads: 0,
+        ..Default::default()
+    };
+    let t = std::time::Instant::now();
+    gauss_blur::gauss_blur_with(&mut img, &options, &gauss_blur::Extrapolation::ExtendEdge);
+    dbg!(t.elapsed());
     let img: image::RgbaImage = (&img).into();
     img.save("fast_gauss_blur.png").unwrap();
 }
@@ -0,0 +1,98 @@
+use nanachi::{
+    compositor,
+    context::{Context, FillStyle},
+    fill_color, fill_rule,
+    filter::{
+        ColorMatrix, CompositeOperator, Filter, FilterInput, FilterPrimitive, TransferFunction,
+    },
+    image::RgbaImage,
+    pixel::Rgba,
+    primitives,
+};
+
+fn main() {
+    let (width, height) = (600, 240);
+    let mut context =
+        Context::from_pixel(width, height, Rgba([0.95, 0.95, 0.95, 1.0])).high_quality();
+    let style = |color: Rgba| {
+        FillStyle::new(
+            fill_color::Solid::new(color),
+            compositor::SrcOver,
+            fill_rule::NonZero,
+        )
+    };
+
+    // A colored drop shadow built with the filter graph.
+    let mut shadow = Filter::new([0.0, 0.0, 300.0, 240.0]);
+    shadow
+        .push(FilterPrimitive::GaussianBlur {
+            input: FilterInput::SourceAlpha,
+            std_deviation_x: 8.0,
+            std_deviation_y: 3.0,
+        })
+        .push_named(
+            "offset",
+            FilterPrimitive::Offset {
+                input: FilterInput::Previous,
+                dx: 10.0,
+                dy: 10.0,
+            },
+        )
+        .push(FilterPrimitive::Flood {
+            color: Rgba([0.2, 0.0, 0.6, 0.6]),
+        })
+        .push_named(
+            "shadow",
+            FilterPrimitive::Composite {
+                input: FilterInput::Previous,
+                input2: FilterInput::Result("offset".to_string()),
+                operator: CompositeOperator::In,
+            },
+        )
+        .push(FilterPrimitive::Merge {
+            inputs: vec![
+                FilterInput::Result("shadow".to_string()),
+                FilterInput::SourceGraphic,
+            ],
+        });
+    context.filter_layer(&shadow, &compositor::SrcOver, |context| {
+        context.fill(
+            &primitives::rounded_rect(40.0, 40.0, 160.0, 110.0, [20.0; 4]),
+            &style(Rgba([0.3, 0.7, 0.9, 1.0])),
+        );
+        context.fill(
+            &primitives::star(160.0, 170.0, 5, 50.0, 22.0),
+            &style(Rgba([0.9, 0.5, 0.2, 1.0])),
+        );
+    });
+
+    // Hue rotation and posterization of a layer.
+    let mut colors = Filter::new([300.0, 0.0, 600.0, 240.0]);
+    let steps = TransferFunction::Discrete(vec![0.0, 0.33, 0.66, 1.0]);
+    colors
+        .push(FilterPrimitive::ColorMatrix {
+            input: FilterInput::SourceGraphic,
+            matrix: ColorMatrix::HueRotate(120.0),
+        })
+        .push(FilterPrimitive::ComponentTransfer {
+            input: FilterInput::Previous,
+            functions: [
+                steps.clone(),
+                steps.clone(),
+                steps,
+                TransferFunction::Identity,
+            ],
+        });
+    context.filter_layer(&colors, &compositor::SrcOver, |context| {
+        for i in 0..6 {
+            let t = i as f32 / 5.0;
+            context.fill(
+                &primitives::circle(360.0 + 36.0 * i as f32, 120.0, 30.0),
+                &style(Rgba([t, 0.4, 1.0 - t, 0.9])),
+            );
+        }
+    });
+
+    let img: RgbaImage = (&context.image).into();
+    img.save("./filter.png").unwrap();
+}
@@ -0,0 +1,88 @@
+use nanachi::{
+    compositor,
+    context::{Context, FillStyle},
+    contrib::lighting::LightSource,
+    fill_color, fill_rule,
+    filter::{CompositeOperator, Filter, FilterInput, FilterPrimitive},
+    image::RgbaImage,
+    pixel::Rgba,
+    primitives,
+};
+
+fn main() {
+    let (width, height) = (600, 220);
+    let mut context = Context::from_pixel(width, height, Rgba([0.9, 0.9, 0.9, 1.0])).high_quality();
+    let style = |color: Rgba| {
+        FillStyle::new(
+            fill_color::Solid::new(color),
+            compositor::SrcOver,
+            fill_rule::NonZero,
+        )
+    };
+
+    // Bevel with the specular highlight of a blurred alpha, like the classic SVG filter.
+    let lights = [
+        LightSource::Distant {
+            azimuth: 225.0,
+            elevation: 40.0,
+        },
+        LightSource::Point {
+            x: 230.0,
+            y: 40.0,
+            z: 80.0,
+        },
+        LightSource::Spot {
+            x: 520.0,
+            y: 30.0,
+            z: 200.0,
+            points_at: [480.0, 110.0, 0.0],
+            specular_exponent: 4.0,
+            limiting_cone_angle: Some(25.0),
+        },
+    ];
+    for (i, light) in lights.into_iter().enumerate() {
+        let x = 200.0 * i as f32;
+        let mut filter = Filter::new([x, 0.0, x + 200.0, height as f32]);
+        filter
+            .push_named(
+                "blur",
+                FilterPrimitive::GaussianBlur {
+                    input: FilterInput::SourceAlpha,
+                    std_deviation_x: 4.0,
+                    std_deviation_y: 4.0,
+                },
+            )
+            .push(FilterPrimitive::SpecularLighting {
+                input: FilterInput::Previous,
+                light,
+                lighting_color: Rgba([1.0, 1.0, 1.0, 1.0]),
+                surface_scale: 5.0,
+                specular_constant: 0.8,
+                specular_exponent: 20.0,
+            })
+            .push(FilterPrimitive::Composite {
+                input: FilterInput::Previous,
+                input2: FilterInput::SourceAlpha,
+                operator: CompositeOperator::In,
+            })
+            .push(FilterPrimitive::Composite {
+                input: FilterInput::SourceGraphic,
+                input2: FilterInput::Previous,
+                operator: CompositeOperator::Arithmetic {
+                    k1: 0.0,
+                    k2: 1.0,
+                    k3: 1.0,
+                    k4: 0.0,
+                },
+            });
+        context.filter_layer(&filter, &compositor::SrcOver, |context| {
+            context.fill(
+                &primitives::rounded_rect(x + 30.0, 40.0, 140.0, 140.0, [30.0; 4]),
+                &style(Rgba([0.2, 0.5, 0.3 + 0.3 * i as f32, 1.0])),
+            );
+        });
+    }
+
+    let img: RgbaImage = (&context.image).into();
+    img.save("./lighting.png").unwrap();
+}
@@ -0,0 +1,97 @@
+use nanachi::{
+    compositor,
+    context::{Context, FillStyle},
+    fill_color, fill_rule,
+    image::RgbaImage,
+    interpolation,
+    mask::Mask,
+    path_outline::{Cap, Join},
+    pixel::Rgba,
+    primitives,
+};
+use std::sync::Arc;
+
+fn main() {
+    let (width, height) = (512, 256);
+    let mut context = Context::from_pixel(width, height, Rgba([1.0, 1.0, 1.0, 1.0])).high_quality();
+    let style = |color: Rgba| {
+        FillStyle::new(
+            fill_color::Solid::new(color),
+            compositor::SrcOver,
+            fill_rule::NonZero,
+        )
+    };
+
+    // A luminance mask rendered with a radial gradient and stripes.
+    let mut source = Context::from_pixel(width, height, Rgba([0.0, 0.0, 0.0, 1.0]));
+    source.fill(
+        &primitives::rect(0.0, 0.0, 256.0, 256.0),
+        &FillStyle::new(
+            fill_color::RadialGradient::new(
+                (128.0, 128.0),
+                120.0,
+                vec![
+                    (0.0, Rgba([1.0, 1.0, 1.0, 1.0])),
+                    (1.0, Rgba([0.0, 0.0, 0.0, 1.0])),
+                ],
+            ),
+            compositor::SrcOver,
+            fill_rule::NonZero,
+        ),
+    );
+    for i in 0..8 {
+        let y = 20.0 + i as f32 * 28.0;
+        source.fill(
+            &primitives::rounded_rect(280.0, y, 210.0, 14.0, [7.0; 4]),
+            &style(Rgba([1.0, 1.0, 1.0, 1.0])),
+        );
+    }
+    let mask = Mask::from_luminance(&source.image);
+
+    // A path mask which cuts a star out.
+    let mut star = Mask::from_path(
+        width,
+        height,
+        &primitives::star(128.0, 128.0, 5, 120.0, 50.0),
+        fill_rule::NonZero,
+        0.1,
+    );
+    star.invert();
+
+    let mut checker = Context::from_pixel(32, 32, Rgba([0.2, 0.5, 0.9, 1.0]));
+    checker.fill(
+        &primitives::rect(0.0, 0.0, 16.0, 16.0),
+        &style(Rgba([0.9, 0.3, 0.2, 1.0])),
+    );
+    checker.fill(
+        &primitives::rect(16.0, 16.0, 16.0, 16.0),
+        &style(Rgba([0.9, 0.3, 0.2, 1.0])),
+    );
+
+    let mut both = mask.clone();
+    both.intersect(&star);
+    context.mask = Some(Arc::new(both));
+    context.fill(
+        &primitives::rect(0.0, 0.0, 256.0, 256.0),
+        &style(Rgba([0.1, 0.2, 0.5, 1.0])),
+    );
+    context.mask = Some(Arc::new(mask));
+    context.draw_image(
+        &checker.image,
+        [0.0, 0.0, 32.0, 32.0],
+        [270.0, 10.0, 500.0, 246.0],
+        interpolation::NearestNeighbor,
+        &compositor::SrcOver,
+        1.0,
+    );
+    context.stroke_with_style(
+        &primitives::circle(128.0, 128.0, 100.0),
+        &style(Rgba([0.9, 0.5, 0.0, 1.0])),
+        12.0,
+        &Join::Round,
+        &Cap::Round,
+    );
+
+    let img: RgbaImage = (&context.image).into();
+    img.save("./mask.png").unwrap();
+}
@@ -0,0 +1,109 @@
+use nanachi::{
+    compositor,
+    context::{Context, FillStyle},
+    contrib::{
+        gauss_blur::Extrapolation,
+        morphology::{close, dilate, erode, open, StructuringElement},
+    },
+    fill_color, fill_rule,
+    image::RgbaImage,
+    interpolation,
+    pixel::Rgba,
+    primitives,
+};
+
+fn main() {
+    let size = 160;
+    let style = |color: Rgba| {
+        FillStyle::new(
+            fill_color::Solid::new(color),
+            compositor::SrcOver,
+            fill_rule::NonZero,
+        )
+    };
+
+    // A star with a thin gap and small dots.
+    let mut shape = Context::from_pixel(size, size, Rgba([0.0; 4])).high_quality();
+    let color = Rgba([0.2, 0.4, 0.8, 1.0]);
+    shape.fill(&primitives::star(80.0, 85.0, 5, 60.0, 26.0), &style(color));
+    shape.fill(
+        &primitives::rect(20.0, 80.0, 120.0, 3.0),
+        &FillStyle::new(
+            fill_color::Solid::new(Rgba([0.0, 0.0, 0.0, 1.0])),
+            compositor::DstOut,
+            fill_rule::NonZero,
+        ),
+    );
+    for i in 0..6 {
+        let x = 15.0 + 26.0 * i as f32;
+        shape.fill(&primitives::circle(x, 150.0, 2.5), &style(color));
+    }
+    let shape = shape.image;
+
+    let transparent = Extrapolation::Constant(Rgba([0.0; 4]));
+    let mut tmp = shape.clone();
+    let mut outlined = shape.clone();
+    dilate(
+        &mut outlined,
+        &mut tmp,
+        6,
+        6,
+        StructuringElement::Ellipse,
+        &transparent,
+    );
+    let mut eroded = shape.clone();
+    erode(
+        &mut eroded,
+        &mut tmp,
+        3,
+        3,
+        StructuringElement::Rectangle,
+        &transparent,
+    );
+    let mut opened = shape.clone();
+    open(
+        &mut opened,
+        &mut tmp,
+        4,
+        4,
+        StructuringElement::Ellipse,
+        &transparent,
+    );
+    let mut closed = shape.clone();
+    close(
+        &mut closed,
+        &mut tmp,
+        3,
+        3,
+        StructuringElement::Ellipse,
+        &transparent,
+    );
+
+    let mut context =
+        Context::from_pixel(size * 5, size, Rgba([1.0, 1.0, 1.0, 1.0])).high_quality();
+    let panels = [&shape, &outlined, &eroded, &opened, &closed];
+    for (i, panel) in panels.iter().enumerate() {
+        let x = (size as usize * i) as f32;
+        let s = size as f32;
+        context.draw_image(
+            *panel,
+            [0.0, 0.0, s, s],
+            [x, 0.0, x + s, s],
+            interpolation::NearestNeighbor,
+            &compositor::SrcOver,
+            1.0,
+        );
+    }
+    // Cut the shape out of the dilated one to make an outline.
+    context.draw_image(
+        &shape,
+        [0.0, 0.0, size as f32, size as f32],
+        [size as f32, 0.0, 2.0 * size as f32, size as f32],
+        interpolation::NearestNeighbor,
+        &compositor::Xor,
+        1.0,
+    );
+
+    let img: RgbaImage = (&context.image).into();
+    img.save("./morphology.png").unwrap();
+}
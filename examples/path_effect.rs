@@ -0,0 +1,59 @@
+use nanachi::{
+    compositor,
+    context::{Context, FillStyle},
+    fill_color, fill_rule,
+    image::RgbaImage,
+    path::Path,
+    path_effect::{Corner, Discrete, PathEffect, Stamp},
+    pixel::Rgba,
+    point::Point,
+    primitives,
+};
+
+fn main() {
+    let (width, height) = (512, 256);
+    let mut context = Context::from_pixel(width, height, Rgba([1.0, 1.0, 1.0, 1.0])).high_quality();
+    let style = |color: Rgba| {
+        FillStyle::new(
+            fill_color::Solid::new(color),
+            compositor::SrcOver,
+            fill_rule::NonZero,
+        )
+    };
+
+    let star = primitives::star(90.0, 128.0, 5, 80.0, 35.0);
+    context.fill(
+        &Corner::new(10.0).apply(&star),
+        &style(Rgba([1.0, 0.7, 0.1, 1.0])),
+    );
+
+    let square = primitives::rect(190.0, 58.0, 140.0, 140.0);
+    let sketchy = Corner::new(20.0).then(Discrete::new(6.0, 2.0, 42));
+    for seed in 0..3 {
+        let effect = Corner::new(20.0).then(Discrete::new(6.0, 2.0, seed));
+        context.stroke(
+            &effect.apply(&square),
+            &style(Rgba([0.1, 0.1, 0.1, 0.8])),
+            1.5,
+        );
+    }
+    context.fill(&sketchy.apply(&square), &style(Rgba([0.3, 0.6, 1.0, 0.3])));
+
+    let arrow = Path::from_points(
+        &vec![
+            Point::from((-4.0, -5.0)),
+            Point::from((4.0, 0.0)),
+            Point::from((-4.0, 5.0)),
+            Point::from((-4.0, -5.0)),
+        ],
+        true,
+    );
+    let circle = primitives::circle(430.0, 128.0, 60.0);
+    context.fill(
+        &Stamp::new(arrow, 16.0).apply(&circle),
+        &style(Rgba([0.8, 0.1, 0.3, 1.0])),
+    );
+
+    let img: RgbaImage = (&context.image).into();
+    img.save("./path_effect.png").unwrap();
+}
@@ -0,0 +1,59 @@
+use nanachi::{
+    compositor,
+    context::{Context, FillStyle},
+    contrib::draw_image_transformed::draw_image_transformed,
+    fill_color, fill_rule,
+    homography::Homography,
+    image::ImageBuffer,
+    interpolation,
+    path_transform::path_project,
+    point::Point,
+    primitives,
+};
+
+fn main() {
+    let file = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| "nanachi.png".to_string());
+    let src = image::open(file).unwrap().into_rgba8();
+    let (width, height) = src.dimensions();
+    let (w, h) = (width as f32, height as f32);
+
+    let homography = Homography::from_quad_to_quad(
+        [
+            Point::from((0.0, 0.0)),
+            Point::from((w, 0.0)),
+            Point::from((w, h)),
+            Point::from((0.0, h)),
+        ],
+        [
+            Point::from((w * 0.3, h * 0.1)),
+            Point::from((w * 0.7, h * 0.1)),
+            Point::from((w * 0.95, h * 0.9)),
+            Point::from((w * 0.05, h * 0.9)),
+        ],
+    )
+    .unwrap();
+
+    let mut img = ImageBuffer::new(width, height);
+    draw_image_transformed(
+        &mut img,
+        &src,
+        [0.0, 0.0, w, h],
+        homography,
+        &compositor::SrcOver,
+        interpolation::Bilinear,
+    );
+
+    let mut context = Context::from_image(&mut img).high_quality();
+    let fill_style = FillStyle::new(
+        fill_color::Solid::new(image::Rgba([255, 0, 0, 255])),
+        compositor::SrcOver,
+        fill_rule::NonZero,
+    );
+    let mut path = primitives::rect(20.0, 20.0, w - 40.0, h - 40.0);
+    path.merge(&primitives::circle(w / 2.0, h / 2.0, w / 4.0));
+    context.stroke(&path_project(&path, &homography, 0.1), &fill_style, 4.0);
+
+    img.save("./perspective.png").unwrap();
+}
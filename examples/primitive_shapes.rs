@@ -8,7 +8,7 @@ use nanachi::{
 };
 
 fn main() {
-    let (width, height) = (200, 200);
+    let (width, height) = (600, 200);
 
     let mut context = Context::from_pixel(width, height, Rgba([0.0, 0.0, 0.0, 1.0])).high_quality();
 
@@ -57,6 +57,37 @@ fn main() {
         ),
     );
 
+    let shapes = [
+        primitives::rounded_rect(220.0, 20.0, 70.0, 70.0, [5.0, 15.0, 25.0, 35.0]),
+        primitives::ellipse(350.0, 55.0, 40.0, 25.0),
+        primitives::pie(430.0, 55.0, 35.0, 0.5, 5.5),
+        primitives::donut(520.0, 55.0, 20.0, 40.0, 3.5, 8.0),
+        primitives::star(255.0, 140.0, 5, 45.0, 20.0),
+        primitives::superellipse(350.0, 140.0, 40.0, 40.0, 4.0),
+        primitives::rounded_ngon(430.0, 145.0, 6, 45.0, 10.0),
+        primitives::arrow(480.0, 180.0, 570.0, 110.0, 10.0, 30.0, 25.0),
+    ];
+    for (i, shape) in shapes.iter().enumerate() {
+        let t = i as f32 / shapes.len() as f32;
+        context.fill(
+            shape,
+            &FillStyle::new(
+                fill_color::Solid::new(Rgba([1.0 - t, 0.5, t, 0.9])),
+                compositor::SrcOver,
+                fill_rule::NonZero,
+            ),
+        );
+    }
+    context.stroke(
+        &primitives::arc(520.0, 55.0, 48.0, 0.0, 3.0),
+        &FillStyle::new(
+            fill_color::Solid::new(Rgba([1.0, 1.0, 1.0, 1.0])),
+            compositor::SrcOver,
+            fill_rule::NonZero,
+        ),
+        3.0,
+    );
+
     let img: RgbaImage = (&context.image).into();
     img.save("./primitive_shapes.png").unwrap();
 }
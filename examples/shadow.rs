@@ -0,0 +1,87 @@
+use nanachi::{
+    compositor,
+    context::{Context, FillStyle},
+    fill_color, fill_rule,
+    image::RgbaImage,
+    interpolation,
+    pixel::Rgba,
+    primitives,
+    shadow::Shadow,
+};
+
+fn main() {
+    let (width, height) = (600, 220);
+    let mut context =
+        Context::from_pixel(width, height, Rgba([0.95, 0.95, 0.95, 1.0])).high_quality();
+    let style = |color: Rgba| {
+        FillStyle::new(
+            fill_color::Solid::new(color),
+            compositor::SrcOver,
+            fill_rule::NonZero,
+        )
+    };
+
+    // Drop shadow on a fill and a stroke.
+    context.shadows = vec![Shadow::Drop {
+        color: Rgba([0.0, 0.0, 0.0, 0.5]),
+        blur: 12.0,
+        offset_x: 8.0,
+        offset_y: 8.0,
+    }];
+    context.fill(
+        &primitives::rounded_rect(30.0, 30.0, 140.0, 90.0, [16.0; 4]),
+        &style(Rgba([0.3, 0.6, 0.9, 1.0])),
+    );
+    context.stroke(
+        &primitives::star(100.0, 170.0, 5, 36.0, 16.0),
+        &style(Rgba([0.9, 0.6, 0.2, 1.0])),
+        6.0,
+    );
+
+    // Outer glow on an image.
+    let mut checker = Context::from_pixel(16, 16, Rgba([1.0, 1.0, 1.0, 1.0]));
+    checker.fill(
+        &primitives::rect(0.0, 0.0, 8.0, 8.0),
+        &style(Rgba([0.2, 0.2, 0.2, 1.0])),
+    );
+    checker.fill(
+        &primitives::rect(8.0, 8.0, 8.0, 8.0),
+        &style(Rgba([0.2, 0.2, 0.2, 1.0])),
+    );
+    context.shadows = vec![Shadow::Glow {
+        color: Rgba([1.0, 0.2, 0.6, 1.0]),
+        blur: 20.0,
+        spread: 0.3,
+    }];
+    context.draw_image(
+        &checker.image,
+        [0.0, 0.0, 16.0, 16.0],
+        [240.0, 50.0, 360.0, 170.0],
+        interpolation::NearestNeighbor,
+        &compositor::SrcOver,
+        1.0,
+    );
+
+    // Inner shadow with a drop shadow.
+    context.shadows = vec![
+        Shadow::Drop {
+            color: Rgba([0.0, 0.0, 0.0, 0.3]),
+            blur: 6.0,
+            offset_x: 0.0,
+            offset_y: 3.0,
+        },
+        Shadow::Inner {
+            color: Rgba([0.0, 0.0, 0.0, 0.6]),
+            blur: 10.0,
+            offset_x: 4.0,
+            offset_y: 6.0,
+        },
+    ];
+    context.fill(
+        &primitives::circle(490.0, 110.0, 70.0),
+        &style(Rgba([0.9, 0.9, 0.8, 1.0])),
+    );
+
+    let img: RgbaImage = (&context.image).into();
+    img.save("./shadow.png").unwrap();
+}
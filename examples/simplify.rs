@@ -0,0 +1,69 @@
+use nanachi::{
+    compositor,
+    context::{Context, FillStyle},
+    fill_color, fill_rule,
+    image::RgbaImage,
+    matrix::Matrix,
+    path::Path,
+    path_transform::path_transform,
+    pixel::Rgba,
+    point::Point,
+    primitives,
+};
+
+fn main() {
+    let (width, height) = (512, 512);
+    let mut context = Context::from_pixel(width, height, Rgba([1.0, 1.0, 1.0, 1.0])).high_quality();
+
+    // A dense and noisy polyline like freehand input.
+    let points: Vec<Point> = (0..=400)
+        .map(|i| {
+            let t = i as f32 / 400.0;
+            Point::from((
+                40.0 + t * 432.0 + (t * 173.0).sin() * 2.0,
+                120.0 + (t * 9.0).sin() * 60.0 + (t * 211.0).cos() * 2.0,
+            ))
+        })
+        .collect();
+    let path = Path::from_points(&points, false);
+    let style = |color: Rgba| {
+        FillStyle::new(
+            fill_color::Solid::new(color),
+            compositor::SrcOver,
+            fill_rule::NonZero,
+        )
+    };
+    let offset = |path: &Path, dy: f32| path_transform(path, &Matrix::new().translate(0.0, dy));
+
+    context.stroke(&path, &style(Rgba([0.5, 0.5, 0.5, 1.0])), 1.0);
+
+    let simplified = path.simplify(4.0);
+    context.stroke(
+        &offset(&simplified, 120.0),
+        &style(Rgba([0.2, 0.4, 1.0, 1.0])),
+        2.0,
+    );
+
+    let fitted = path.fit_curves(3.0);
+    context.stroke(
+        &offset(&fitted, 240.0),
+        &style(Rgba([1.0, 0.3, 0.2, 1.0])),
+        2.0,
+    );
+
+    let spline = Path::from_catmull_rom(&simplified.as_points_list().unwrap()[0], false, 0.5);
+    context.stroke(
+        &offset(&spline, 240.0),
+        &style(Rgba([0.1, 0.7, 0.2, 0.6])),
+        2.0,
+    );
+    for p in simplified.as_points_list().unwrap()[0].iter() {
+        context.fill(
+            &primitives::circle(p.x(), p.y() + 120.0, 3.0),
+            &style(Rgba([0.0, 0.0, 0.0, 1.0])),
+        );
+    }
+
+    let img: RgbaImage = (&context.image).into();
+    img.save("./simplify.png").unwrap();
+}
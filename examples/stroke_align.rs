@@ -0,0 +1,83 @@
+use nanachi::{
+    compositor,
+    context::{Context, FillStyle},
+    fill_color, fill_rule,
+    image::RgbaImage,
+    path::{Path, PathItem},
+    path_builder::PathBuilder,
+    path_outline::{path_outline, Cap, Join, StrokeAlign},
+    pixel::Rgba,
+    point::Point,
+    primitives,
+};
+use std::f32::consts::PI;
+
+fn main() {
+    let (width, height) = (720, 320);
+    let mut context = Context::from_pixel(width, height, Rgba([1.0, 1.0, 1.0, 1.0])).high_quality();
+    let style = |color: Rgba| {
+        FillStyle::new(
+            fill_color::Solid::new(color),
+            compositor::SrcOver,
+            fill_rule::NonZero,
+        )
+    };
+    let stroke = style(Rgba([0.2, 0.4, 0.8, 0.8]));
+    let guide = style(Rgba([0.0, 0.0, 0.0, 1.0]));
+
+    // Joins of a zigzag.
+    let joins = [
+        Join::Miter(4.0),
+        Join::Miter(1.5),
+        Join::MiterClip(1.5),
+        Join::Arcs(1.5),
+    ];
+    for (i, join) in joins.iter().enumerate() {
+        let x = 40.0 + i as f32 * 170.0;
+        let mut pb = PathBuilder::new();
+        pb.move_to(x, 120.0);
+        pb.line_to(x + 30.0, 40.0);
+        pb.line_to(x + 60.0, 120.0);
+        pb.line_to(x + 120.0, 60.0);
+        let path = pb.end();
+        context.stroke_with_style(&path, &stroke, 16.0, join, &Cap::Butt);
+        context.stroke(&path, &guide, 1.0);
+    }
+
+    // `arcs` join extends the outer edges of two arcs meeting at a point.
+    let r = 60.0;
+    let (c1, c2) = (Point::from((620.0, 240.0)), Point::from((700.0, 240.0)));
+    let a = (40.0f32 / r).acos();
+    let mut items = primitives::arc(c2.x(), c2.y(), r, PI + a, PI - a).0;
+    items.extend(primitives::arc(c1.x(), c1.y(), r, a, -a).0);
+    items.push(PathItem::CloseAndJump);
+    let lens = Path::new(items);
+    for (join, color) in [
+        (Join::Miter(8.0), Rgba([0.2, 0.4, 0.8, 1.0])),
+        (Join::Arcs(8.0), Rgba([0.9, 0.4, 0.1, 1.0])),
+    ] {
+        context.fill(&path_outline(&lens, 5.0, &join, &Cap::Butt), &style(color));
+    }
+    context.stroke(&lens, &guide, 1.0);
+
+    // Alignments of strokes to closed paths.
+    let aligns = [
+        StrokeAlign::Center,
+        StrokeAlign::Inside,
+        StrokeAlign::Outside,
+    ];
+    for (i, align) in aligns.iter().enumerate() {
+        let x = 30.0 + i as f32 * 180.0;
+        context.stroke_align = *align;
+        let rect = primitives::rect(x, 190.0, 50.0, 80.0);
+        context.stroke_with_style(&rect, &stroke, 16.0, &Join::Miter(4.0), &Cap::Butt);
+        let star = primitives::star(x + 115.0, 232.0, 5, 32.0, 14.0);
+        context.stroke_with_style(&star, &stroke, 8.0, &Join::MiterClip(2.0), &Cap::Butt);
+        context.stroke_align = StrokeAlign::Center;
+        context.stroke(&rect, &guide, 1.0);
+        context.stroke(&star, &guide, 1.0);
+    }
+
+    let img: RgbaImage = (&context.image).into();
+    img.save("./stroke_align.png").unwrap();
+}
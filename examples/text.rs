@@ -3,75 +3,109 @@ use nanachi::{
     compositor,
     context::{Context, FillStyle},
     fill_color, fill_rule,
+    matrix::Matrix,
+    path_builder::PathBuilder,
     pixel::Rgba,
-    primitives::rect,
-    writer::image_writer,
+    primitives,
+    text::{text_along_path, Align, Font, PathTextOptions, Side, TextStyle},
 };
-use rusttype::*;
 
 fn main() {
-    let (width, height) = (500, 100);
+    let (width, height) = (500, 400);
 
     let mut context = Context::from_pixel(width, height, Rgba([1.0, 1.0, 1.0, 1.0])).high_quality();
 
-    let rect_fill_style = FillStyle {
-        color: fill_color::Solid::new(Rgba([0.0, 0.0, 1.0, 0.3])),
-        fill_rule: fill_rule::NonZero,
-        compositor: compositor::SrcOver,
-        pixel: Default::default(),
-    };
-
-    {
-        let text = "Hello, Nanachi!!";
-        let color = fill_color::Solid::new(Rgba([1.0, 0.0, 0.0, 1.0]));
-        let compositor = compositor::SrcOver;
-        let font_path = "./examples/IPAexfont00401/ipaexg.ttf";
-        let bytes = std::fs::read(font_path).unwrap();
-        let font = Font::try_from_bytes(&bytes).unwrap();
-
-        let scale = Scale::uniform(40.0);
-        let v_metrics = font.v_metrics(scale);
-
-        let start = point(0.0, v_metrics.ascent);
-        let glyphs: Vec<_> = font.layout(text, scale, start).collect();
+    let font_path = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| "./examples/IPAexfont00401/ipaexg.ttf".to_string());
+    let bytes = std::fs::read(font_path).unwrap();
+    let font = Font::from_bytes(&bytes).unwrap();
 
-        // get bounding box
-        let (left, top, right, bottom) = glyphs.iter().fold((500, 100, 0, 0), |b, g| {
-            if let Some(bb) = g.pixel_bounding_box() {
-                (
-                    b.0.min(bb.min.x),
-                    b.1.min(bb.min.y),
-                    b.2.max(bb.max.x),
-                    b.3.max(bb.max.y),
-                )
-            } else {
-                b
-            }
-        });
+    let fill_style = FillStyle::new(
+        fill_color::LinearGradient::new(
+            (0.0, 0.0),
+            (400.0, 0.0),
+            vec![
+                (0.0, Rgba([1.0, 0.0, 0.0, 1.0])),
+                (1.0, Rgba([0.0, 0.0, 1.0, 1.0])),
+            ],
+        ),
+        compositor::SrcOver,
+        fill_rule::NonZero,
+    );
+    let text_style = TextStyle::new(&font, 40.0);
+    context.fill_text("Hello, Nanachi!!", 10.0, 50.0, &text_style, &fill_style);
 
-        // show rectangle
-        context.fill(
-            &rect(
-                left as f32,
-                top as f32,
-                (right - left) as f32,
-                (bottom - top) as f32,
-            ),
-            &rect_fill_style,
+    let stroke_style = FillStyle::new(
+        fill_color::Solid::new(Rgba([0.0, 0.0, 0.0, 1.0])),
+        compositor::SrcOver,
+        fill_rule::NonZero,
+    );
+    let text_style = TextStyle {
+        align: Align::Center,
+        ..TextStyle::new(&font, 30.0)
+    };
+    context
+        .transformed_context(&Matrix::new().rotate(-0.1).translate(250.0, 120.0))
+        .stroke_text(
+            "なんてこった\nNaNachi",
+            0.0,
+            0.0,
+            &text_style,
+            &stroke_style,
+            1.0,
         );
 
-        // render text
-        let mut write = image_writer(&mut context.image, &color, &compositor);
-        for glyph in glyphs {
-            if let Some(bounding_box) = glyph.pixel_bounding_box() {
-                glyph.draw(|x, y, v| {
-                    let x = x + bounding_box.min.x as u32;
-                    let y = y + bounding_box.min.y as u32;
-                    write(x, y, v.min(1.0) as f32);
-                });
-            }
-        }
-    }
+    let mut builder = PathBuilder::new();
+    builder.move_to(20.0, 300.0);
+    builder.cubic(100.0, 200.0, 200.0, 400.0, 280.0, 300.0);
+    let curve = builder.end();
+    context.stroke(&curve, &stroke_style, 1.0);
+    let text_style = TextStyle::new(&font, 24.0);
+    context.fill(
+        &text_along_path(
+            "along the curve",
+            &curve,
+            &text_style,
+            &PathTextOptions {
+                offset: 10.0,
+                ..Default::default()
+            },
+        ),
+        &fill_style,
+    );
+    context.fill(
+        &text_along_path(
+            "and below",
+            &curve,
+            &text_style,
+            &PathTextOptions {
+                offset: 60.0,
+                side: Side::Below,
+                ..Default::default()
+            },
+        ),
+        &fill_style,
+    );
+
+    let circle = primitives::circle(400.0, 300.0, 60.0);
+    context.stroke(&circle, &stroke_style, 1.0);
+    let text_style = TextStyle {
+        align: Align::Center,
+        ..TextStyle::new(&font, 20.0)
+    };
+    context.fill(
+        &text_along_path(
+            "circular badge",
+            &circle,
+            &text_style,
+            &PathTextOptions {
+                offset: 60.0 * std::f32::consts::PI * 1.5,
+                ..Default::default()
+            },
+        ),
+        &fill_style,
+    );
 
     let img: RgbaImage = (&context.image).into();
     img.save("./text.png").unwrap();
@@ -0,0 +1,70 @@
+use nanachi::{
+    compositor,
+    context::{Context, FillStyle},
+    fill_color, fill_rule,
+    image::RgbaImage,
+    matrix::Matrix,
+    path_builder::PathBuilder,
+    path_outline::{path_outline_calligraphic, path_outline_variable, Cap, Join, Pen, WidthStops},
+    path_transform::path_transform,
+    pixel::Rgba,
+    primitives,
+};
+
+fn main() {
+    let (width, height) = (512, 384);
+    let mut context = Context::from_pixel(width, height, Rgba([1.0, 1.0, 1.0, 1.0])).high_quality();
+    let style = |color: Rgba| {
+        FillStyle::new(
+            fill_color::Solid::new(color),
+            compositor::SrcOver,
+            fill_rule::NonZero,
+        )
+    };
+
+    let mut pb = PathBuilder::new();
+    pb.move_to(40.0, 100.0);
+    pb.cubic(120.0, -20.0, 200.0, 220.0, 280.0, 100.0);
+    pb.line_to(330.0, 40.0);
+    pb.quad(470.0, 60.0, 420.0, 160.0);
+    let path = pb.end();
+
+    // A brush stroke which swells in the middle.
+    let stops = WidthStops::Normalized(vec![(0.0, 1.0), (0.3, 24.0), (0.7, 12.0), (1.0, 1.0)]);
+    let outline = path_outline_variable(&path, &stops, &Join::Round, &Cap::Round, 0.2);
+    context.fill(&outline, &style(Rgba([0.1, 0.1, 0.3, 1.0])));
+
+    // Widths by the distance on a closed path.
+    let circle = primitives::circle(110.0, 270.0, 70.0);
+    let stops = WidthStops::Length(vec![(0.0, 2.0), (220.0, 30.0), (440.0, 2.0)]);
+    let outline = path_outline_variable(&circle, &stops, &Join::Bevel, &Cap::Butt, 0.2);
+    context.fill(&outline, &style(Rgba([0.9, 0.4, 0.1, 1.0])));
+
+    // Calligraphic pens.
+    let mut pb = PathBuilder::new();
+    pb.move_to(230.0, 330.0);
+    pb.cubic(230.0, 180.0, 330.0, 180.0, 330.0, 280.0);
+    pb.cubic(330.0, 360.0, 470.0, 360.0, 470.0, 220.0);
+    let path = pb.end();
+    let nib = Pen::Nib {
+        width: 16.0,
+        angle: -0.7,
+    };
+    context.fill(
+        &path_outline_calligraphic(&path, &nib, 0.2),
+        &style(Rgba([0.0, 0.3, 0.6, 1.0])),
+    );
+    let pen = Pen::Ellipse {
+        radius_x: 6.0,
+        radius_y: 2.0,
+        angle: 0.5,
+    };
+    let path = path_transform(&path, &Matrix::new().translate(0.0, -40.0));
+    context.fill(
+        &path_outline_calligraphic(&path, &pen, 0.2),
+        &style(Rgba([0.6, 0.0, 0.3, 1.0])),
+    );
+
+    let img: RgbaImage = (&context.image).into();
+    img.save("./variable_stroke.png").unwrap();
+}
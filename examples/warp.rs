@@ -0,0 +1,117 @@
+use nanachi::{
+    buffer::{Buffer, GenericBuffer},
+    compositor,
+    context::{Context, FillStyle},
+    contrib::{
+        gauss_blur::Extrapolation,
+        warp::{displacement_map, MeshWarp, Warp},
+    },
+    fill_color, fill_rule,
+    image::RgbaImage,
+    interpolation::{self, Bilinear},
+    pixel::Rgba,
+    point::Point,
+    primitives,
+};
+
+fn main() {
+    let size = 160;
+    let s = size as f32;
+
+    // A checkerboard with a star to warp.
+    let mut scene = Context::from_pixel(size, size, Rgba([1.0, 1.0, 1.0, 1.0])).high_quality();
+    scene.clear(&fill_color::BlockCheck::new(
+        Rgba([0.85, 0.85, 0.9, 1.0]),
+        Rgba([0.6, 0.65, 0.8, 1.0]),
+        16.0,
+    ));
+    scene.fill(
+        &primitives::star(80.0, 80.0, 5, 60.0, 26.0),
+        &FillStyle::new(
+            fill_color::Solid::new(Rgba([0.9, 0.4, 0.2, 1.0])),
+            compositor::SrcOver,
+            fill_rule::NonZero,
+        ),
+    );
+    let scene = scene.image;
+    let edge = Extrapolation::ExtendEdge;
+    let center = [s / 2.0, s / 2.0];
+
+    let warps = [
+        Warp::Ripple {
+            center,
+            amplitude: 3.0,
+            wavelength: 20.0,
+            phase: 0.0,
+        },
+        Warp::Swirl {
+            center,
+            radius: 75.0,
+            angle: 2.5,
+        },
+        Warp::Barrel {
+            center,
+            radius: 80.0,
+            strength: 0.4,
+        },
+        Warp::Barrel {
+            center,
+            radius: 80.0,
+            strength: -0.25,
+        },
+    ];
+    let mut panels: Vec<GenericBuffer<Rgba>> = warps
+        .iter()
+        .map(|warp| {
+            let mut dst = scene.clone();
+            warp.apply(&mut dst, &scene, &Bilinear, &edge);
+            dst
+        })
+        .collect();
+
+    // Pull the center of a 2x2 mesh toward the top left corner.
+    let mut mesh = MeshWarp::new([0.0, 0.0, s, s], 2, 2);
+    *mesh.point_mut(1, 1) = Point::from((110.0, 110.0));
+    let mut dst = scene.clone();
+    mesh.apply(&mut dst, &scene, &Bilinear, &edge);
+    panels.push(dst);
+
+    // Glitchy horizontal displacement by bands of a map.
+    let mut map = GenericBuffer::from_pixel(size, size, Rgba([0.5, 0.5, 0.5, 1.0]));
+    for y in 0..size {
+        let v = (((y / 8) * 7919) % 13) as f32 / 12.0;
+        for x in 0..size {
+            map.put_pixel(x, y, Rgba([v, 0.5, 0.5, 1.0]));
+        }
+    }
+    let mut dst = scene.clone();
+    displacement_map(
+        &mut dst,
+        &scene,
+        &map,
+        |p: &Rgba| p.0[0],
+        |p: &Rgba| p.0[1],
+        24.0,
+        &Bilinear,
+        &edge,
+    );
+    panels.push(dst);
+
+    let (w, _) = scene.dimensions();
+    let mut context =
+        Context::from_pixel(w * panels.len() as u32, size, Rgba([1.0, 1.0, 1.0, 1.0]));
+    for (i, panel) in panels.iter().enumerate() {
+        let x = s * i as f32;
+        context.draw_image(
+            panel,
+            [0.0, 0.0, s, s],
+            [x, 0.0, x + s, s],
+            interpolation::NearestNeighbor,
+            &compositor::SrcOver,
+            1.0,
+        );
+    }
+
+    let img: RgbaImage = (&context.image).into();
+    img.save("./warp.png").unwrap();
+}
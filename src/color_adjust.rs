@@ -0,0 +1,371 @@
+//! Color adjustments like levels, curves, hue rotation and posterization.
+//!
+//! [`ColorAdjust`] applies a list of [`Adjustment`]s to unpremultiplied colors.
+//! Consecutive linear adjustments are composed into a single 4x5 color matrix, so intermediate
+//! colors between them are not clamped. Use it on buffers with [`ColorAdjust::apply`] or on
+//! colors generated on the fly with [`Adjusted`](crate::fill_color::Adjusted).
+
+use crate::buffer::Buffer;
+use crate::filter::ColorMatrix;
+use crate::pixel::{Pixel, Rgba};
+
+const IDENTITY: [f32; 20] = [
+    1.0, 0.0, 0.0, 0.0, 0.0, //
+    0.0, 1.0, 0.0, 0.0, 0.0, //
+    0.0, 0.0, 1.0, 0.0, 0.0, //
+    0.0, 0.0, 0.0, 1.0, 0.0,
+];
+
+/// A color adjustment. Amounts are from 0 (no effect) to 1 unless noted.
+#[derive(Debug, Clone)]
+pub enum Adjustment {
+    /// Map `input_black..input_white` to `output_black..output_white` with `gamma` in between.
+    /// An empty input range thresholds at `input_black`.
+    Levels {
+        input_black: f32,
+        input_white: f32,
+        gamma: f32,
+        output_black: f32,
+        output_white: f32,
+    },
+    /// Smooth monotone curve through `(input, output)` points applied to RGB.
+    Curves(Vec<(f32, f32)>),
+    /// `brightness` is added and `contrast` from -1 scales around the middle gray.
+    BrightnessContrast {
+        brightness: f32,
+        contrast: f32,
+    },
+    /// Rotation of hue in degrees.
+    HueRotate(f32),
+    /// Saturation, where 0 is gray and values above 1 oversaturate.
+    Saturate(f32),
+    /// `c^(1 / gamma)`, where values above 1 brighten.
+    Gamma(f32),
+    Invert(f32),
+    Sepia(f32),
+    Grayscale(f32),
+    /// Black or white by the luminance.
+    Threshold(f32),
+    /// Number of levels of each channel.
+    Posterize(u32),
+    /// 4x5 row-major matrix applied to `[r, g, b, a, 1]`.
+    Matrix([f32; 20]),
+}
+
+impl Adjustment {
+    /// Get the color matrix if the adjustment is linear.
+    pub fn matrix(&self) -> Option<[f32; 20]> {
+        let lerp = |m: [f32; 9], amount: f32| {
+            let mut res = IDENTITY;
+            for i in 0..3 {
+                for j in 0..3 {
+                    let id = if i == j { 1.0 } else { 0.0 };
+                    res[i * 5 + j] = id + (m[i * 3 + j] - id) * amount.clamp(0.0, 1.0);
+                }
+            }
+            res
+        };
+        match self {
+            Adjustment::BrightnessContrast {
+                brightness,
+                contrast,
+            } => {
+                let s = 1.0 + contrast;
+                let t = 0.5 - 0.5 * s + brightness;
+                let mut m = IDENTITY;
+                for i in 0..3 {
+                    m[i * 5 + i] = s;
+                    m[i * 5 + 4] = t;
+                }
+                Some(m)
+            }
+            Adjustment::HueRotate(deg) => Some(ColorMatrix::HueRotate(*deg).matrix()),
+            Adjustment::Saturate(s) => Some(ColorMatrix::Saturate(*s).matrix()),
+            Adjustment::Invert(amount) => {
+                let a = amount.clamp(0.0, 1.0);
+                let mut m = IDENTITY;
+                for i in 0..3 {
+                    m[i * 5 + i] = 1.0 - 2.0 * a;
+                    m[i * 5 + 4] = a;
+                }
+                Some(m)
+            }
+            Adjustment::Sepia(amount) => Some(lerp(
+                [
+                    0.393, 0.769, 0.189, //
+                    0.349, 0.686, 0.168, //
+                    0.272, 0.534, 0.131,
+                ],
+                *amount,
+            )),
+            Adjustment::Grayscale(amount) => Some(lerp(
+                [
+                    0.2126, 0.7152, 0.0722, //
+                    0.2126, 0.7152, 0.0722, //
+                    0.2126, 0.7152, 0.0722,
+                ],
+                *amount,
+            )),
+            Adjustment::Matrix(m) => Some(*m),
+            _ => None,
+        }
+    }
+}
+
+/// Monotone cubic interpolation of points (Fritsch-Carlson).
+#[derive(Debug, Clone)]
+struct Curve {
+    points: Vec<(f32, f32)>,
+    tangents: Vec<f32>,
+}
+
+impl Curve {
+    fn new(points: &[(f32, f32)]) -> Self {
+        let mut points = points.to_vec();
+        points.sort_by(|a, b| a.0.total_cmp(&b.0));
+        points.dedup_by(|a, b| a.0 == b.0);
+        let n = points.len();
+        let secants: Vec<f32> = points
+            .windows(2)
+            .map(|w| (w[1].1 - w[0].1) / (w[1].0 - w[0].0))
+            .collect();
+        let mut tangents: Vec<f32> = (0..n)
+            .map(
+                |i| match (i.checked_sub(1).map(|i| secants[i]), secants.get(i)) {
+                    (Some(a), Some(&b)) if a * b > 0.0 => (a + b) / 2.0,
+                    (Some(_), Some(_)) | (None, None) => 0.0,
+                    (Some(a), None) => a,
+                    (None, Some(&b)) => b,
+                },
+            )
+            .collect();
+        for (i, &d) in secants.iter().enumerate() {
+            if d == 0.0 {
+                tangents[i] = 0.0;
+                tangents[i + 1] = 0.0;
+                continue;
+            }
+            let (a, b) = (tangents[i] / d, tangents[i + 1] / d);
+            let h = a.hypot(b);
+            if h > 3.0 {
+                tangents[i] = 3.0 * a / h * d;
+                tangents[i + 1] = 3.0 * b / h * d;
+            }
+        }
+        Curve { points, tangents }
+    }
+
+    fn eval(&self, x: f32) -> f32 {
+        let p = &self.points;
+        match p.len() {
+            0 => return x,
+            1 => return p[0].1,
+            _ => {}
+        }
+        if x <= p[0].0 {
+            return p[0].1;
+        }
+        if x >= p[p.len() - 1].0 {
+            return p[p.len() - 1].1;
+        }
+        let i = p.partition_point(|q| q.0 <= x) - 1;
+        let h = p[i + 1].0 - p[i].0;
+        let t = (x - p[i].0) / h;
+        let (t2, t3) = (t * t, t * t * t);
+        (2.0 * t3 - 3.0 * t2 + 1.0) * p[i].1
+            + (t3 - 2.0 * t2 + t) * h * self.tangents[i]
+            + (-2.0 * t3 + 3.0 * t2) * p[i + 1].1
+            + (t3 - t2) * h * self.tangents[i + 1]
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Stage {
+    Matrix([f32; 20]),
+    Curve(Curve),
+    Function(Adjustment),
+}
+
+/// A list of [`Adjustment`]s compiled for applying.
+#[derive(Debug, Clone)]
+pub struct ColorAdjust {
+    stages: Vec<Stage>,
+}
+
+impl ColorAdjust {
+    /// Compile adjustments applied in order.
+    pub fn new(adjustments: &[Adjustment]) -> Self {
+        let mut stages: Vec<Stage> = Vec::new();
+        for adjustment in adjustments {
+            match (adjustment.matrix(), stages.last_mut()) {
+                (Some(m), Some(Stage::Matrix(last))) => *last = concat(last, &m),
+                (Some(m), _) => stages.push(Stage::Matrix(m)),
+                (None, _) => stages.push(match adjustment {
+                    Adjustment::Curves(points) => Stage::Curve(Curve::new(points)),
+                    _ => Stage::Function(adjustment.clone()),
+                }),
+            }
+        }
+        ColorAdjust { stages }
+    }
+
+    /// The composed color matrix if all adjustments are linear.
+    pub fn matrix(&self) -> Option<[f32; 20]> {
+        match self.stages.as_slice() {
+            [] => Some(IDENTITY),
+            [Stage::Matrix(m)] => Some(*m),
+            _ => None,
+        }
+    }
+
+    /// Adjust an unpremultiplied color.
+    pub fn adjust_rgba(&self, color: &Rgba) -> Rgba {
+        let mut c = color.0;
+        for stage in self.stages.iter() {
+            c = match stage {
+                Stage::Matrix(m) => {
+                    let row =
+                        |r: &[f32]| r[0] * c[0] + r[1] * c[1] + r[2] * c[2] + r[3] * c[3] + r[4];
+                    [
+                        row(&m[0..5]),
+                        row(&m[5..10]),
+                        row(&m[10..15]),
+                        row(&m[15..20]),
+                    ]
+                }
+                Stage::Curve(curve) => [curve.eval(c[0]), curve.eval(c[1]), curve.eval(c[2]), c[3]],
+                Stage::Function(adjustment) => apply_function(adjustment, c),
+            };
+            c = c.map(|v| v.clamp(0.0, 1.0));
+        }
+        Rgba(c)
+    }
+
+    /// Adjust a pixel. Premultiplied pixels are unpremultiplied before adjusting.
+    pub fn adjust<P: Pixel + Into<Rgba> + From<Rgba>>(&self, pixel: &P) -> P {
+        P::from(self.adjust_rgba(&pixel.clone().into()))
+    }
+
+    /// Adjust all pixels of the buffer.
+    pub fn apply<P: Pixel + Into<Rgba> + From<Rgba>, B: Buffer<P>>(&self, buf: &mut B) {
+        let (w, h) = buf.dimensions();
+        for y in 0..h {
+            for x in 0..w {
+                let p = self.adjust(buf.get_pixel(x, y));
+                buf.put_pixel(x, y, p);
+            }
+        }
+    }
+}
+
+fn apply_function(adjustment: &Adjustment, c: [f32; 4]) -> [f32; 4] {
+    let rgb = |f: &dyn Fn(f32) -> f32| [f(c[0]), f(c[1]), f(c[2]), c[3]];
+    match adjustment {
+        Adjustment::Levels {
+            input_black,
+            input_white,
+            gamma,
+            output_black,
+            output_white,
+        } => rgb(&|v| {
+            let range = input_white - input_black;
+            // A degenerate range is a threshold at `input_black`.
+            let v = if range == 0.0 {
+                if v < *input_black {
+                    0.0
+                } else {
+                    1.0
+                }
+            } else {
+                (v - input_black) / range
+            }
+            .clamp(0.0, 1.0);
+            output_black + v.powf(1.0 / gamma) * (output_white - output_black)
+        }),
+        Adjustment::Gamma(gamma) => rgb(&|v| v.max(0.0).powf(1.0 / gamma)),
+        Adjustment::Threshold(t) => {
+            let l = 0.2126 * c[0] + 0.7152 * c[1] + 0.0722 * c[2];
+            let v = if l >= *t { 1.0 } else { 0.0 };
+            [v, v, v, c[3]]
+        }
+        Adjustment::Posterize(levels) => {
+            let n = (levels.max(&2) - 1) as f32;
+            rgb(&|v| (v * n).round() / n)
+        }
+        _ => c,
+    }
+}
+
+/// Composes color matrices as `b` after `a`.
+fn concat(a: &[f32; 20], b: &[f32; 20]) -> [f32; 20] {
+    let mut res = [0.0; 20];
+    for i in 0..4 {
+        for j in 0..5 {
+            let mut v = (0..4).map(|k| b[i * 5 + k] * a[k * 5 + j]).sum::<f32>();
+            if j == 4 {
+                v += b[i * 5 + 4];
+            }
+            res[i * 5 + j] = v;
+        }
+    }
+    res
+}
+
+#[test]
+fn test() {
+    use crate::pixel::PremultipliedRgba;
+
+    let close = |a: Rgba, b: [f32; 4]| a.0.iter().zip(b.iter()).all(|(a, b)| (a - b).abs() < 1e-4);
+    let color = Rgba([0.8, 0.4, 0.2, 0.5]);
+
+    // Linear adjustments are composed into one matrix.
+    let adjustments = [
+        Adjustment::Invert(1.0),
+        Adjustment::HueRotate(40.0),
+        Adjustment::BrightnessContrast {
+            brightness: 0.05,
+            contrast: -0.2,
+        },
+        Adjustment::Sepia(0.3),
+    ];
+    let adjust = ColorAdjust::new(&adjustments);
+    assert!(adjust.matrix().is_some());
+    let expected = adjustments
+        .iter()
+        .fold(color, |c, a| ColorAdjust::new(&[a.clone()]).adjust_rgba(&c));
+    assert!(close(adjust.adjust_rgba(&color), expected.0));
+    let gray = ColorAdjust::new(&[Adjustment::Grayscale(1.0), Adjustment::Saturate(3.0)]);
+    let [r, g, b, _] = gray.adjust_rgba(&color).0;
+    assert!((r - g).abs() < 1e-4 && (g - b).abs() < 1e-4);
+
+    // Nonlinear adjustments.
+    let adjust = ColorAdjust::new(&[
+        Adjustment::Levels {
+            input_black: 0.2,
+            input_white: 0.6,
+            gamma: 1.0,
+            output_black: 0.0,
+            output_white: 1.0,
+        },
+        Adjustment::Posterize(3),
+    ]);
+    assert!(adjust.matrix().is_none());
+    assert!(close(adjust.adjust_rgba(&color), [1.0, 0.5, 0.0, 0.5]));
+    let curve = Curve::new(&[(0.0, 0.0), (0.5, 0.8), (1.0, 1.0)]);
+    assert!((curve.eval(0.5) - 0.8).abs() < 1e-6);
+    assert!((0..=100).all(|i| curve.eval(i as f32 / 100.0) <= curve.eval((i + 1) as f32 / 100.0)));
+
+    let threshold = ColorAdjust::new(&[Adjustment::Levels {
+        input_black: 0.5,
+        input_white: 0.5,
+        gamma: 1.0,
+        output_black: 0.0,
+        output_white: 1.0,
+    }]);
+    assert!(close(threshold.adjust_rgba(&color), [1.0, 0.0, 0.0, 0.5]));
+
+    // Premultiplied pixels are adjusted as unpremultiplied colors.
+    let adjust = ColorAdjust::new(&[Adjustment::Invert(1.0)]);
+    let p = adjust.adjust(&PremultipliedRgba::from(color));
+    assert!(close(Rgba::from(p), [0.2, 0.6, 0.8, 0.5]));
+}
@@ -3,20 +3,28 @@
 use crate::{
     buffer::{Buffer, GenericBuffer},
     compositor::Compositor,
-    fill_color::{FillColor, Transform},
-    fill_rule::FillRule,
+    fill_color::{FillColor, Solid, Transform},
+    fill_rule::{FillRule, NonZero},
+    filter::{Filter, FilterPixel},
+    interpolation::Interpolation,
+    mask::Mask,
     matrix::Matrix,
     path::Path,
     path_flatten::Flatten,
-    path_outline::{path_outline, Cap, Join},
+    path_outline::{path_outline_aligned, Cap, Join, StrokeAlign},
     path_segments::Segments,
     path_transform::path_transform,
-    pixel::Pixel,
+    pixel::{Pixel, PremultipliedRgba},
     point::Point,
     rasterize::RasterizeBuffer,
+    shadow::Shadow,
     writer::image_writer,
 };
 use std::borrow::BorrowMut;
+use std::sync::Arc;
+
+#[cfg(feature = "text")]
+use crate::text::{text_path, TextStyle};
 
 pub type ChildContext<'a, P, B> = Context<P, B, &'a mut B, &'a mut RasterizeBuffer>;
 
@@ -63,6 +71,12 @@ where
     pub antialiasing: bool,
     pub join: Join,
     pub cap: Cap,
+    /// Alignment of strokes to closed subpaths.
+    pub stroke_align: StrokeAlign,
+    /// Mask applied to fills, strokes and images in the device coordinates.
+    pub mask: Option<Arc<Mask>>,
+    /// Shadow effects applied to fills, strokes and images.
+    pub shadows: Vec<Shadow<P>>,
     pub matrix: Matrix,
     rasterizer: R,
     pixel: std::marker::PhantomData<P>,
@@ -109,6 +123,8 @@ where
     }
 
     /// Draw stroke of the path with specified [`FillStyle`].
+    ///
+    /// The stroke is aligned to closed subpaths according to `stroke_align`.
     pub fn stroke<FC: FillColor<P>, C: Compositor<P>, FR: FillRule>(
         &mut self,
         path: &Path,
@@ -116,7 +132,7 @@ where
         width: f32,
     ) {
         let path = self.path_transform_and_flatten(path);
-        let path = path_outline(&path, width / 2.0, &self.join, &self.cap);
+        let path = path_outline_aligned(&path, width, self.stroke_align, &self.join, &self.cap);
         self.fill_(fill_style, &path);
     }
 
@@ -130,7 +146,7 @@ where
         cap: &Cap,
     ) {
         let path = self.path_transform_and_flatten(path);
-        let path = path_outline(&path, width / 2.0, join, cap);
+        let path = path_outline_aligned(&path, width, self.stroke_align, join, cap);
         self.fill_(fill_style, &path);
     }
 
@@ -143,6 +159,75 @@ where
         }
     }
 
+    /// Fill the text whose first baseline starts at (`x`, `y`).
+    #[cfg(feature = "text")]
+    pub fn fill_text<FC: FillColor<P>, C: Compositor<P>, FR: FillRule>(
+        &mut self,
+        text: &str,
+        x: f32,
+        y: f32,
+        text_style: &TextStyle,
+        fill_style: &FillStyle<P, FC, C, FR>,
+    ) {
+        self.fill(&text_path(text, x, y, text_style), fill_style);
+    }
+
+    /// Draw stroke of the text whose first baseline starts at (`x`, `y`).
+    #[cfg(feature = "text")]
+    pub fn stroke_text<FC: FillColor<P>, C: Compositor<P>, FR: FillRule>(
+        &mut self,
+        text: &str,
+        x: f32,
+        y: f32,
+        text_style: &TextStyle,
+        fill_style: &FillStyle<P, FC, C, FR>,
+        width: f32,
+    ) {
+        self.stroke(&text_path(text, x, y, text_style), fill_style, width);
+    }
+
+    /// Draw the `src_rect` area of the image into the `dst_rect` area.
+    ///
+    /// Rectangles are given as `[x1, y1, x2, y2]`. The image is transformed with the current matrix
+    /// and its edges are anti-aliased like paths. `opacity` is multiplied to the coverage.
+    /// [`Context::shadows`] are cast by the quad of `dst_rect`, not by the alpha of the image.
+    pub fn draw_image<BS: Buffer<P>, IP: Interpolation<P, BS>, C: Compositor<P>>(
+        &mut self,
+        image: &BS,
+        src_rect: [f32; 4],
+        dst_rect: [f32; 4],
+        interpolation: IP,
+        compositor: &C,
+        opacity: f32,
+    ) {
+        let path = Path::from_points(
+            &vec![
+                Point([dst_rect[0], dst_rect[1]]),
+                Point([dst_rect[0], dst_rect[3]]),
+                Point([dst_rect[2], dst_rect[3]]),
+                Point([dst_rect[2], dst_rect[1]]),
+                Point([dst_rect[0], dst_rect[1]]),
+            ],
+            true,
+        );
+        let path = self.path_transform_and_flatten(&path);
+        let color = ImageColor {
+            image,
+            interpolation,
+            matrix: Matrix::new()
+                .translate(-dst_rect[0], -dst_rect[1])
+                .scale(
+                    (src_rect[2] - src_rect[0]) / (dst_rect[2] - dst_rect[0]),
+                    (src_rect[3] - src_rect[1]) / (dst_rect[3] - dst_rect[1]),
+                )
+                .translate(src_rect[0], src_rect[1]),
+            src_rect,
+            pixel: Default::default(),
+        };
+        let color = Transform::new(&color, self.matrix);
+        self.rasterize_(&color, compositor, NonZero, &path, opacity);
+    }
+
     /// Clear buffer entirely with specified [`FillColor`]
     pub fn clear<FC: FillColor<P>>(&mut self, fill_color: &FC) {
         let image = self.image.borrow_mut();
@@ -156,6 +241,51 @@ where
         }
     }
 
+    /// Draw into a transparent layer with `draw`, apply the [`Filter`] to the layer and composite it.
+    ///
+    /// The layer inherits the settings of this context except [`Context::mask`],
+    /// which is applied when the filtered layer is composited.
+    pub fn filter_layer<C: Compositor<P>>(
+        &mut self,
+        filter: &Filter,
+        compositor: &C,
+        draw: impl FnOnce(&mut ChildContext<P, GenericBuffer<P>>),
+    ) where
+        P: FilterPixel,
+    {
+        let (width, height) = self.image.borrow_mut().dimensions();
+        let transparent = P::from_premultiplied(&PremultipliedRgba([0.0, 0.0, 0.0, 0.0]));
+        let mut layer = GenericBuffer::from_pixel(width, height, transparent);
+        draw(&mut Context {
+            image: &mut layer,
+            rasterizer: self.rasterizer.borrow_mut(),
+            flatten_tolerance: self.flatten_tolerance,
+            antialiasing: self.antialiasing,
+            join: self.join.clone(),
+            cap: self.cap.clone(),
+            stroke_align: self.stroke_align,
+            mask: None,
+            shadows: self.shadows.clone(),
+            matrix: self.matrix,
+            pixel: self.pixel,
+            b: Default::default(),
+        });
+        let layer = filter.apply(&layer);
+        let composite = compositor.composite_with_alpha();
+        let image = self.image.borrow_mut();
+        for y in 0..height {
+            for x in 0..width {
+                let src = layer.get_pixel(x, y);
+                if compositor.keep_dst_on_transparent_src() && src.to_premultiplied().0[3] <= 0.0 {
+                    continue;
+                }
+                let m = self.mask.as_ref().map_or(1.0, |mask| mask.get(x, y));
+                let p = composite(image.get_pixel(x, y), src, m);
+                image.put_pixel(x, y, p);
+            }
+        }
+    }
+
     #[inline]
     fn fill_<FC: FillColor<P>, C: Compositor<P>, FR: FillRule>(
         &mut self,
@@ -163,26 +293,112 @@ where
         path: &Path,
     ) {
         let color = Transform::new(&fill_style.color, self.matrix);
-        let mut writer = image_writer(self.image.borrow_mut(), &color, &fill_style.compositor);
-        let pis = Flatten::new(path.0.iter(), self.flatten_tolerance);
-        let segments = Segments::new(pis);
-        let write_transparent_src = !fill_style.compositor.keep_dst_on_transparent_src()
-            || fill_style.fill_rule.is_inverse();
-        if self.antialiasing {
-            self.rasterizer.borrow_mut().rasterize(
-                segments,
-                fill_style.fill_rule,
-                &mut writer,
-                write_transparent_src,
-            );
-        } else {
-            self.rasterizer.borrow_mut().rasterize_no_aa(
-                segments,
-                fill_style.fill_rule,
-                &mut writer,
-                write_transparent_src,
-            );
+        self.rasterize_(
+            &color,
+            &fill_style.compositor,
+            fill_style.fill_rule,
+            path,
+            1.0,
+        );
+    }
+
+    #[inline]
+    fn rasterize_<FC: FillColor<P>, C: Compositor<P>, FR: FillRule>(
+        &mut self,
+        color: &FC,
+        compositor: &C,
+        fill_rule: FR,
+        path: &Path,
+        opacity: f32,
+    ) {
+        let shadows = std::mem::take(&mut self.shadows);
+        for shadow in shadows.iter().filter(|s| !s.is_inner()) {
+            self.draw_shadow(shadow, compositor, fill_rule, path, opacity);
+        }
+        {
+            let mut writer = image_writer(self.image.borrow_mut(), color, compositor);
+            let mask = self.mask.as_deref();
+            let mut writer = |x: u32, y: u32, v: f32| {
+                let m = mask.map_or(1.0, |mask| mask.get(x, y));
+                writer(x, y, v * opacity * m)
+            };
+            let pis = Flatten::new(path.0.iter(), self.flatten_tolerance);
+            let segments = Segments::new(pis);
+            let write_transparent_src =
+                !compositor.keep_dst_on_transparent_src() || fill_rule.is_inverse();
+            if self.antialiasing {
+                self.rasterizer.borrow_mut().rasterize(
+                    segments,
+                    fill_rule,
+                    &mut writer,
+                    write_transparent_src,
+                );
+            } else {
+                self.rasterizer.borrow_mut().rasterize_no_aa(
+                    segments,
+                    fill_rule,
+                    &mut writer,
+                    write_transparent_src,
+                );
+            }
+        }
+        for shadow in shadows.iter().filter(|s| s.is_inner()) {
+            self.draw_shadow(shadow, compositor, fill_rule, path, opacity);
         }
+        self.shadows = shadows;
+    }
+
+    fn draw_shadow<C: Compositor<P>, FR: FillRule>(
+        &mut self,
+        shadow: &Shadow<P>,
+        compositor: &C,
+        fill_rule: FR,
+        path: &Path,
+        opacity: f32,
+    ) {
+        let image = self.image.borrow_mut();
+        let (width, height) = image.dimensions();
+        let path = Path::new(Flatten::new(path.0.iter(), self.flatten_tolerance).collect());
+        let ([x0, y0], coverage) = match shadow.coverage(&path, fill_rule, width, height) {
+            Some(c) => c,
+            None => return,
+        };
+        let color = Solid::new(shadow.color().clone());
+        let mut writer = image_writer(image, &color, compositor);
+        let (w, h) = coverage.dimensions();
+        for y in y0.max(0)..(y0 + h as i32).min(height as i32) {
+            for x in x0.max(0)..(x0 + w as i32).min(width as i32) {
+                let v = *coverage.get_pixel((x - x0) as u32, (y - y0) as u32);
+                if v <= 0.0 {
+                    continue;
+                }
+                let (x, y) = (x as u32, y as u32);
+                let m = self.mask.as_ref().map_or(1.0, |mask| mask.get(x, y));
+                writer(x, y, v * opacity * m);
+            }
+        }
+    }
+}
+
+/// Samples `src_rect` of an image; positions outside of the rectangle are clamped to its edges.
+struct ImageColor<'a, P: Pixel, B: Buffer<P>, I: Interpolation<P, B>> {
+    image: &'a B,
+    interpolation: I,
+    /// Maps destination coordinates to image coordinates.
+    matrix: Matrix,
+    src_rect: [f32; 4],
+    pixel: std::marker::PhantomData<P>,
+}
+
+impl<'a, P: Pixel, B: Buffer<P>, I: Interpolation<P, B>> FillColor<P> for ImageColor<'a, P, B, I> {
+    fn fill_color(&self, pos: [f32; 2]) -> P {
+        let [x, y] = self.matrix.apply(pos);
+        let r = &self.src_rect;
+        let pos = Point::from((
+            x.clamp(r[0], (r[2] - 1.0).max(r[0])),
+            y.clamp(r[1], (r[3] - 1.0).max(r[1])),
+        ));
+        self.interpolation.interpolate(self.image, pos)
     }
 }
 
@@ -199,6 +415,9 @@ where
             antialiasing: true,
             join: Join::Round,
             cap: Cap::Round,
+            stroke_align: StrokeAlign::Center,
+            mask: None,
+            shadows: Vec::new(),
             matrix: Matrix::default(),
             pixel: Default::default(),
             b: Default::default(),
@@ -221,6 +440,9 @@ where
             antialiasing: true,
             join: Join::Round,
             cap: Cap::Round,
+            stroke_align: StrokeAlign::Center,
+            mask: None,
+            shadows: Vec::new(),
             matrix: Matrix::default(),
             pixel: Default::default(),
             b: Default::default(),
@@ -244,6 +466,9 @@ where
             antialiasing: self.antialiasing,
             join: self.join.clone(),
             cap: self.cap.clone(),
+            stroke_align: self.stroke_align,
+            mask: self.mask.clone(),
+            shadows: self.shadows.clone(),
             matrix: self.matrix,
             pixel: self.pixel,
             b: Default::default(),
@@ -258,3 +483,90 @@ where
         }
     }
 }
+
+#[test]
+fn test() {
+    use crate::compositor::SrcOver;
+    use crate::interpolation::NearestNeighbor;
+    use crate::pixel::Rgba;
+
+    let mut image = GenericBuffer::from_pixel(4, 4, Rgba([0.0; 4]));
+    for y in 0..4 {
+        for x in 0..4 {
+            image.put_pixel(x, y, Rgba([x as f32 / 4.0, y as f32 / 4.0, 0.5, 1.0]));
+        }
+    }
+    let background = Rgba([0.0, 0.0, 0.0, 1.0]);
+
+    // The identity matrix copies the pixels to the destination rectangle.
+    let mut context = Context::from_pixel(10, 10, background);
+    let src_rect = [0.0, 0.0, 4.0, 4.0];
+    context.draw_image(
+        &image,
+        src_rect,
+        [2.0, 3.0, 6.0, 7.0],
+        NearestNeighbor,
+        &SrcOver,
+        1.0,
+    );
+    for y in 0..10 {
+        for x in 0..10 {
+            let expected = if (2..6).contains(&x) && (3..7).contains(&y) {
+                image.get_pixel(x - 2, y - 3)
+            } else {
+                &background
+            };
+            assert_eq!(context.image.get_pixel(x, y).0, expected.0, "{x} {y}");
+        }
+    }
+
+    // Scaling the context enlarges each pixel to 2x2 pixels.
+    let mut context = Context::from_pixel(10, 10, background);
+    context
+        .transformed_context(&Matrix::new().scale(2.0, 2.0))
+        .draw_image(
+            &image,
+            src_rect,
+            [1.0, 0.0, 5.0, 4.0],
+            NearestNeighbor,
+            &SrcOver,
+            1.0,
+        );
+    for y in 0..10 {
+        for x in 0..10 {
+            let expected = if (2..10).contains(&x) && y < 8 {
+                image.get_pixel(x / 2 - 1, y / 2)
+            } else {
+                &background
+            };
+            assert_eq!(context.image.get_pixel(x, y).0, expected.0, "{x} {y}");
+        }
+    }
+
+    // Shadows of images are rectangular regardless of the alpha.
+    let mut context = Context::from_pixel(10, 10, background);
+    context.shadows = vec![Shadow::Drop {
+        color: Rgba([1.0, 1.0, 1.0, 1.0]),
+        blur: 0.0,
+        offset_x: 2.0,
+        offset_y: 0.0,
+    }];
+    let transparent = GenericBuffer::from_pixel(4, 4, Rgba([0.0; 4]));
+    context.draw_image(
+        &transparent,
+        src_rect,
+        [2.0, 3.0, 6.0, 7.0],
+        NearestNeighbor,
+        &SrcOver,
+        1.0,
+    );
+    for y in 0..10 {
+        for x in 0..10 {
+            let shadowed = (4..8).contains(&x) && (3..7).contains(&y);
+            assert_eq!(
+                context.image.get_pixel(x, y).0[0],
+                if shadowed { 1.0 } else { 0.0 }
+            );
+        }
+    }
+}
@@ -0,0 +1,481 @@
+//! Convolution with arbitrary kernels, and sharpening, edge detection, emboss and motion blur on it.
+
+use crate::{
+    buffer::Buffer,
+    contrib::gauss_blur::{gauss_blur, Extrapolation},
+    pixel::{Arithmetic, MinMax, Pixel},
+};
+
+/// A convolution kernel. The value at `(x, y)` is the weight of the pixel at the offset
+/// `(width / 2 - x, height / 2 - y)`, i.e. the kernel is rotated by 180 degrees like `feConvolveMatrix`.
+#[derive(Debug, Clone)]
+pub enum Kernel {
+    /// Row-major `width x height` values.
+    Matrix {
+        width: u32,
+        height: u32,
+        values: Vec<f32>,
+    },
+    /// The outer product of `vertical` and `horizontal`, applied in two passes.
+    Separable {
+        horizontal: Vec<f32>,
+        vertical: Vec<f32>,
+    },
+}
+
+impl Kernel {
+    /// Create a [`Kernel::Matrix`] from rows.
+    pub fn from_rows(rows: &[&[f32]]) -> Self {
+        let width = rows.first().map_or(0, |r| r.len());
+        assert!(rows.iter().all(|r| r.len() == width));
+        Kernel::Matrix {
+            width: width as u32,
+            height: rows.len() as u32,
+            values: rows.iter().flat_map(|r| r.iter().copied()).collect(),
+        }
+    }
+
+    /// Get `(width, height)`.
+    pub fn dimensions(&self) -> (u32, u32) {
+        match self {
+            Kernel::Matrix { width, height, .. } => (*width, *height),
+            Kernel::Separable {
+                horizontal,
+                vertical,
+            } => (horizontal.len() as u32, vertical.len() as u32),
+        }
+    }
+
+    /// Get the weight at the column `x` and the row `y`.
+    pub fn get(&self, x: u32, y: u32) -> f32 {
+        match self {
+            Kernel::Matrix { width, values, .. } => values[(y * width + x) as usize],
+            Kernel::Separable {
+                horizontal,
+                vertical,
+            } => horizontal[x as usize] * vertical[y as usize],
+        }
+    }
+
+    /// Sum of the weights.
+    pub fn sum(&self) -> f32 {
+        let (w, h) = self.dimensions();
+        (0..h)
+            .flat_map(|y| (0..w).map(move |x| (x, y)))
+            .map(|(x, y)| self.get(x, y))
+            .sum()
+    }
+
+    /// Decompose into horizontal and vertical kernels if the kernel has rank 1.
+    pub fn separate(&self) -> Option<(Vec<f32>, Vec<f32>)> {
+        let (width, height, values) = match self {
+            Kernel::Separable {
+                horizontal,
+                vertical,
+            } => return Some((horizontal.clone(), vertical.clone())),
+            Kernel::Matrix {
+                width,
+                height,
+                values,
+            } => (*width as usize, *height as usize, values),
+        };
+        let (pivot, _) = values
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1.abs().total_cmp(&b.1.abs()))?;
+        let (px, py) = (pivot % width, pivot / width);
+        let horizontal: Vec<f32> = values[py * width..(py + 1) * width].to_vec();
+        let vertical: Vec<f32> = (0..height)
+            .map(|y| values[y * width + px] / values[pivot])
+            .collect();
+        let scale = values[pivot].abs().max(1e-6);
+        let separable = (0..height).all(|y| {
+            (0..width).all(|x| {
+                (values[y * width + x] - vertical[y] * horizontal[x]).abs() <= 1e-5 * scale
+            })
+        });
+        separable.then_some((horizontal, vertical))
+    }
+}
+
+/// Convolve the image with the kernel.
+///
+/// Each pixel becomes `sum(weight * pixel) / divisor + bias`, where weights are taken from
+/// the kernel rotated by 180 degrees as in true convolution. Separable kernels, including
+/// [`Kernel::Matrix`] of rank 1, are applied with a horizontal and a vertical pass.
+pub fn convolve<P: Pixel + Arithmetic, B: Buffer<P>, C: Buffer<P>>(
+    buf: &mut B,
+    tmp: &mut C,
+    kernel: &Kernel,
+    divisor: f32,
+    bias: &P,
+    extrapolation: &Extrapolation<P>,
+) {
+    let (w, h) = buf.dimensions();
+    let (kw, kh) = kernel.dimensions();
+    let scale = 1.0 / divisor;
+    if let Some((horizontal, vertical)) = kernel.separate().filter(|_| kw > 1 && kh > 1) {
+        let taps = taps_1d(&horizontal, scale);
+        for y in 0..h {
+            for x in 0..w {
+                let p = weighted_sum(
+                    buf,
+                    x,
+                    y,
+                    taps.iter().map(|&(d, v)| (d, 0, v)),
+                    extrapolation,
+                );
+                tmp.put_pixel(x, y, p);
+            }
+        }
+        // Pixels outside of the image are extrapolated as if they were filtered horizontally.
+        let extrapolation = match extrapolation {
+            Extrapolation::ExtendEdge => Extrapolation::ExtendEdge,
+            Extrapolation::Constant(p) => {
+                Extrapolation::Constant(p.clone() * (horizontal.iter().sum::<f32>() * scale))
+            }
+        };
+        let taps = taps_1d(&vertical, 1.0);
+        for y in 0..h {
+            for x in 0..w {
+                let p = weighted_sum(
+                    tmp,
+                    x,
+                    y,
+                    taps.iter().map(|&(d, v)| (0, d, v)),
+                    &extrapolation,
+                );
+                buf.put_pixel(x, y, p + bias.clone());
+            }
+        }
+        return;
+    }
+
+    let taps: Vec<(i64, i64, f32)> = (0..kh)
+        .flat_map(|y| (0..kw).map(move |x| (x, y)))
+        .map(|(x, y)| {
+            let (dx, dy) = ((kw / 2) as i64 - x as i64, (kh / 2) as i64 - y as i64);
+            (dx, dy, kernel.get(x, y) * scale)
+        })
+        .filter(|t| t.2 != 0.0)
+        .collect();
+    for y in 0..h {
+        for x in 0..w {
+            let p = weighted_sum(buf, x, y, taps.iter().copied(), extrapolation);
+            tmp.put_pixel(x, y, p + bias.clone());
+        }
+    }
+    for y in 0..h {
+        for x in 0..w {
+            buf.put_pixel(x, y, tmp.get_pixel(x, y).clone());
+        }
+    }
+}
+
+fn taps_1d(values: &[f32], scale: f32) -> Vec<(i64, f32)> {
+    let center = (values.len() / 2) as i64;
+    values
+        .iter()
+        .enumerate()
+        .filter(|(_, &v)| v != 0.0)
+        .map(|(i, &v)| (center - i as i64, v * scale))
+        .collect()
+}
+
+fn weighted_sum<P: Pixel + Arithmetic, B: Buffer<P>>(
+    buf: &B,
+    x: u32,
+    y: u32,
+    taps: impl Iterator<Item = (i64, i64, f32)>,
+    extrapolation: &Extrapolation<P>,
+) -> P {
+    let (w, h) = buf.dimensions();
+    taps.fold(P::zero(), |acc, (dx, dy, v)| {
+        let (sx, sy) = (x as i64 + dx, y as i64 + dy);
+        let p = if 0 <= sx && sx < w as i64 && 0 <= sy && sy < h as i64 {
+            buf.get_pixel(sx as u32, sy as u32)
+        } else {
+            match extrapolation {
+                Extrapolation::ExtendEdge => buf.get_pixel(
+                    sx.clamp(0, w as i64 - 1) as u32,
+                    sy.clamp(0, h as i64 - 1) as u32,
+                ),
+                Extrapolation::Constant(p) => p,
+            }
+        };
+        acc + p.clone() * v
+    })
+}
+
+fn pixels<P: Pixel, B: Buffer<P>>(buf: &B) -> Vec<P> {
+    let (w, h) = buf.dimensions();
+    (0..h)
+        .flat_map(|y| (0..w).map(move |x| (x, y)))
+        .map(|(x, y)| buf.get_pixel(x, y).clone())
+        .collect()
+}
+
+/// Sharpen the image by adding `amount` times the difference from its gaussian blur.
+pub fn unsharp_mask<P: Pixel + Arithmetic, B: Buffer<P>, C: Buffer<P>>(
+    buf: &mut B,
+    tmp: &mut C,
+    radius: f64,
+    amount: f32,
+    extrapolation: &Extrapolation<P>,
+) {
+    let (w, h) = buf.dimensions();
+    let original = pixels(buf);
+    gauss_blur(buf, tmp, radius, extrapolation, 3);
+    for y in 0..h {
+        for x in 0..w {
+            let p = original[(y * w + x) as usize].clone();
+            let blurred = buf.get_pixel(x, y).clone();
+            buf.put_pixel(x, y, p.clone() + (p - blurred) * amount);
+        }
+    }
+}
+
+/// Gradient operators for [`edge_detect`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EdgeOperator {
+    Sobel,
+    Scharr,
+}
+
+impl EdgeOperator {
+    /// Kernels of the horizontal and vertical derivatives, normalized to give 1 on a unit slope.
+    pub fn kernels(&self) -> (Kernel, Kernel) {
+        let smooth = match self {
+            EdgeOperator::Sobel => vec![0.25, 0.5, 0.25],
+            EdgeOperator::Scharr => vec![3.0 / 16.0, 10.0 / 16.0, 3.0 / 16.0],
+        };
+        let derivative = vec![0.5, 0.0, -0.5];
+        (
+            Kernel::Separable {
+                horizontal: derivative.clone(),
+                vertical: smooth.clone(),
+            },
+            Kernel::Separable {
+                horizontal: smooth,
+                vertical: derivative,
+            },
+        )
+    }
+}
+
+/// Replace the image with the magnitude of its gradient, approximated by `|gx| + |gy|`.
+pub fn edge_detect<P: Pixel + Arithmetic + MinMax, B: Buffer<P>, C: Buffer<P>>(
+    buf: &mut B,
+    tmp: &mut C,
+    operator: EdgeOperator,
+    extrapolation: &Extrapolation<P>,
+) {
+    let (w, h) = buf.dimensions();
+    let (kx, ky) = operator.kernels();
+    let original = pixels(buf);
+    convolve(buf, tmp, &kx, 1.0, &P::zero(), extrapolation);
+    let gx = pixels(buf);
+    for y in 0..h {
+        for x in 0..w {
+            buf.put_pixel(x, y, original[(y * w + x) as usize].clone());
+        }
+    }
+    convolve(buf, tmp, &ky, 1.0, &P::zero(), extrapolation);
+    let abs = |p: P| p.max(&(P::zero() - p.clone()));
+    for y in 0..h {
+        for x in 0..w {
+            let gy = buf.get_pixel(x, y).clone();
+            let g = abs(gx[(y * w + x) as usize].clone()) + abs(gy);
+            buf.put_pixel(x, y, g);
+        }
+    }
+}
+
+/// Emboss the image as lit from `angle` in radians, keeping its colors on flat areas.
+pub fn emboss<P: Pixel + Arithmetic, B: Buffer<P>, C: Buffer<P>>(
+    buf: &mut B,
+    tmp: &mut C,
+    angle: f32,
+    strength: f32,
+    extrapolation: &Extrapolation<P>,
+) {
+    let (s, c) = angle.sin_cos();
+    let values = (-1..=1)
+        .flat_map(|y| (-1..=1).map(move |x| (x, y)))
+        .map(|(x, y)| {
+            let v = (x as f32 * c + y as f32 * s) * strength;
+            if x == 0 && y == 0 {
+                1.0
+            } else {
+                v
+            }
+        })
+        .collect();
+    let kernel = Kernel::Matrix {
+        width: 3,
+        height: 3,
+        values,
+    };
+    convolve(buf, tmp, &kernel, 1.0, &P::zero(), extrapolation);
+}
+
+/// Blur the image along the direction of `angle` in radians over `length` pixels.
+pub fn motion_blur<P: Pixel + Arithmetic, B: Buffer<P>, C: Buffer<P>>(
+    buf: &mut B,
+    tmp: &mut C,
+    length: f32,
+    angle: f32,
+    extrapolation: &Extrapolation<P>,
+) {
+    let r = (length / 2.0).ceil().max(0.0) as i32 + 1;
+    let size = (2 * r + 1) as usize;
+    let mut values = vec![0.0; size * size];
+    // Splat samples along the segment with bilinear weights.
+    let (s, c) = angle.sin_cos();
+    let n = (length * 4.0).ceil().max(1.0) as usize;
+    for i in 0..=n {
+        let t = if n == 0 {
+            0.0
+        } else {
+            i as f32 / n as f32 - 0.5
+        };
+        let (x, y) = (t * length * c + r as f32, t * length * s + r as f32);
+        let (x0, y0) = (x.floor(), y.floor());
+        let (fx, fy) = (x - x0, y - y0);
+        let (x0, y0) = (x0 as usize, y0 as usize);
+        for (dx, dy, v) in [
+            (0, 0, (1.0 - fx) * (1.0 - fy)),
+            (1, 0, fx * (1.0 - fy)),
+            (0, 1, (1.0 - fx) * fy),
+            (1, 1, fx * fy),
+        ] {
+            if x0 + dx < size && y0 + dy < size {
+                values[(y0 + dy) * size + x0 + dx] += v;
+            }
+        }
+    }
+    let kernel = Kernel::Matrix {
+        width: size as u32,
+        height: size as u32,
+        values,
+    };
+    let sum = kernel.sum();
+    convolve(buf, tmp, &kernel, sum, &P::zero(), extrapolation);
+}
+
+#[test]
+fn test() {
+    use crate::buffer::GenericBuffer;
+
+    let (w, h) = (16, 12);
+    let mut src = GenericBuffer::from_pixel(w, h, 0.0f32);
+    for y in 0..h {
+        for x in 0..w {
+            src.put_pixel(x, y, ((x * 7 + y * 13) % 10) as f32 / 10.0);
+        }
+    }
+    let mut tmp = src.clone();
+
+    // The separable path gives the same result as the general one.
+    let kernel = Kernel::from_rows(&[&[1.0, 2.0, 1.0], &[2.0, 4.0, 2.0], &[1.0, 2.0, 1.0]]);
+    assert!(kernel.separate().is_some());
+    assert!(Kernel::from_rows(&[&[1.0, 0.0], &[0.0, 1.0]])
+        .separate()
+        .is_none());
+    let asymmetric = Kernel::from_rows(&[&[1.0, 2.0, 3.0], &[0.0, 1.0, 0.0], &[-1.0, 0.0, 2.0]]);
+    let separable = Kernel::Separable {
+        horizontal: vec![1.0, 2.0, 4.0],
+        vertical: vec![1.0, 0.0, -1.0],
+    };
+    for kernel in [&kernel, &asymmetric, &separable] {
+        for extrapolation in [Extrapolation::ExtendEdge, Extrapolation::Constant(0.3)] {
+            let mut a = src.clone();
+            convolve(&mut a, &mut tmp, kernel, 16.0, &0.1, &extrapolation);
+            for y in 0..h as i64 {
+                for x in 0..w as i64 {
+                    let mut v = 0.0;
+                    for ky in 0..3 {
+                        for kx in 0..3 {
+                            let (sx, sy) = (x + 1 - kx, y + 1 - ky);
+                            let inside = 0 <= sx && sx < w as i64 && 0 <= sy && sy < h as i64;
+                            let p = match (&extrapolation, inside) {
+                                (_, true) => *src.get_pixel(sx as u32, sy as u32),
+                                (Extrapolation::ExtendEdge, false) => *src.get_pixel(
+                                    sx.clamp(0, w as i64 - 1) as u32,
+                                    sy.clamp(0, h as i64 - 1) as u32,
+                                ),
+                                (Extrapolation::Constant(c), false) => *c,
+                            };
+                            v += kernel.get(kx as u32, ky as u32) * p;
+                        }
+                    }
+                    let expected = v / 16.0 + 0.1;
+                    assert!((a.get_pixel(x as u32, y as u32) - expected).abs() < 1e-5);
+                }
+            }
+        }
+    }
+
+    // Kernels are flipped, so a weight on the left picks the pixel on the right.
+    let mut a = src.clone();
+    let shift = Kernel::from_rows(&[&[1.0, 0.0, 0.0]]);
+    convolve(
+        &mut a,
+        &mut tmp,
+        &shift,
+        1.0,
+        &0.0,
+        &Extrapolation::ExtendEdge,
+    );
+    assert_eq!(*a.get_pixel(3, 2), *src.get_pixel(4, 2));
+
+    // Flat images are kept by sharpening, embossing and motion blur, and have no edges.
+    let flat = GenericBuffer::from_pixel(w, h, 0.5f32);
+    let mut buf = flat.clone();
+    unsharp_mask(&mut buf, &mut tmp, 2.0, 1.5, &Extrapolation::ExtendEdge);
+    emboss(&mut buf, &mut tmp, 0.7, 1.0, &Extrapolation::ExtendEdge);
+    motion_blur(&mut buf, &mut tmp, 7.0, 0.3, &Extrapolation::ExtendEdge);
+    assert!(buf.as_slice().iter().all(|v| (v - 0.5).abs() < 1e-5));
+    edge_detect(
+        &mut buf,
+        &mut tmp,
+        EdgeOperator::Sobel,
+        &Extrapolation::ExtendEdge,
+    );
+    assert!(buf.as_slice().iter().all(|v| v.abs() < 1e-5));
+
+    // A unit step spreads over two pixels.
+    let mut buf = GenericBuffer::from_pixel(w, h, 0.0f32);
+    for y in 0..h {
+        for x in 8..w {
+            buf.put_pixel(x, y, 1.0);
+        }
+    }
+    edge_detect(
+        &mut buf,
+        &mut tmp,
+        EdgeOperator::Scharr,
+        &Extrapolation::ExtendEdge,
+    );
+    assert!((buf.get_pixel(7, 5) - 0.5).abs() < 1e-5);
+    assert!((buf.get_pixel(8, 5) - 0.5).abs() < 1e-5);
+    assert_eq!(*buf.get_pixel(3, 5), 0.0);
+
+    // Derivative kernels give 1 on a unit slope.
+    let mut slope = GenericBuffer::from_pixel(w, h, 0.0f32);
+    for y in 0..h {
+        for x in 0..w {
+            slope.put_pixel(x, y, x as f32);
+        }
+    }
+    let (kx, _) = EdgeOperator::Sobel.kernels();
+    convolve(
+        &mut slope,
+        &mut tmp,
+        &kx,
+        1.0,
+        &0.0,
+        &Extrapolation::ExtendEdge,
+    );
+    assert!((slope.get_pixel(5, 5) - 1.0).abs() < 1e-5);
+}
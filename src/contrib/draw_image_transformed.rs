@@ -1,14 +1,18 @@
 use crate::buffer::Buffer;
 use crate::compositor::Compositor;
+use crate::homography::Homography;
 use crate::interpolation::Interpolation;
 use crate::matrix::Matrix;
 use crate::pixel::Pixel;
 
-pub fn draw_image_transformed<P, BD, BS, C, I>(
+/// Draw `src_rect` of `src` image to `dst` image transformed with `matrix`.
+///
+/// `matrix` can be a [`Matrix`] or a [`Homography`] for perspective drawing.
+pub fn draw_image_transformed<P, BD, BS, C, I, M>(
     dst: &mut BD,
     src: &BS,
     src_rect: [f32; 4], // x1, y1, x2, y2
-    matrix: Matrix,
+    matrix: M,
     compositor: &C,
     interpolation: I,
 ) where
@@ -17,10 +21,10 @@ pub fn draw_image_transformed<P, BD, BS, C, I>(
     BS: Buffer<P>,
     C: Compositor<P>,
     I: Interpolation<P, BS>,
+    M: Into<Homography>,
 {
-    let matrix = Matrix::new()
-        .translate(-src_rect[0], -src_rect[1])
-        .then(&matrix);
+    let matrix =
+        Homography::from(Matrix::new().translate(-src_rect[0], -src_rect[1])).then(&matrix.into());
 
     let [left, top, right, bottom]: [f32; 4] = [
         matrix.apply((src_rect[0], src_rect[1])),
@@ -1,9 +1,15 @@
+//! Gaussian blur approximated by box blurs, or with an exact kernel for small standard deviations.
+//!
+//! [`gauss_blur_with`] supports separate standard deviations for each axis, blurring a sub-rectangle
+//! and multithreading. It blurs colors with alpha premultiplied so that transparent pixels do not
+//! darken the edges.
+
 use crate::{
     buffer::Buffer,
-    pixel::{Arithmetic, Pixel},
+    pixel::{Arithmetic, Pixel, PremultipliedRgba, Rgba},
 };
 
-pub enum Extrapolation<P: Pixel + Arithmetic> {
+pub enum Extrapolation<P: Pixel> {
     ExtendEdge,
     Constant(P),
 }
@@ -118,3 +124,289 @@ fn boxes_for_gauss(sigma: f64, n: i32) -> Vec<i32> {
 
     (0..n).map(|i| if i < m { wl } else { wu }).collect()
 }
+
+/// A pixel type that [`gauss_blur_with`] can be applied to.
+pub trait BlurPixel: Pixel + Arithmetic {
+    /// The type pixels are blurred in, which is premultiplied for colors with alpha.
+    type Blurred: Pixel + Arithmetic + Send + Sync;
+    fn to_blurred(&self) -> Self::Blurred;
+    fn from_blurred(pixel: &Self::Blurred) -> Self;
+}
+
+macro_rules! impl_blur_pixel_identity {
+    ($($t:ty),*) => {
+        $(impl BlurPixel for $t {
+            type Blurred = $t;
+            fn to_blurred(&self) -> $t {
+                *self
+            }
+            fn from_blurred(pixel: &$t) -> $t {
+                *pixel
+            }
+        })*
+    };
+}
+
+impl_blur_pixel_identity!(f32, PremultipliedRgba);
+
+impl BlurPixel for Rgba {
+    type Blurred = PremultipliedRgba;
+
+    fn to_blurred(&self) -> PremultipliedRgba {
+        (*self).into()
+    }
+
+    fn from_blurred(pixel: &PremultipliedRgba) -> Rgba {
+        (*pixel).into()
+    }
+}
+
+/// Options for [`gauss_blur_with`].
+#[derive(Debug, Clone)]
+pub struct GaussBlurOptions {
+    pub std_deviation_x: f64,
+    pub std_deviation_y: f64,
+    /// `[x1, y1, x2, y2]` to blur. The rectangle is blurred as an image of its own,
+    /// and pixels outside of it are left unchanged.
+    pub rect: Option<[u32; 4]>,
+    /// Standard deviations below this use an exact kernel instead of three box blurs.
+    pub exact_threshold: f64,
+    /// Number of threads. 0 uses the available parallelism.
+    pub threads: usize,
+}
+
+impl GaussBlurOptions {
+    /// Options with the same standard deviation for both axes.
+    pub fn new(std_deviation: f64) -> Self {
+        GaussBlurOptions {
+            std_deviation_x: std_deviation,
+            std_deviation_y: std_deviation,
+            ..Default::default()
+        }
+    }
+}
+
+impl Default for GaussBlurOptions {
+    fn default() -> Self {
+        GaussBlurOptions {
+            std_deviation_x: 0.0,
+            std_deviation_y: 0.0,
+            rect: None,
+            exact_threshold: 2.0,
+            threads: 1,
+        }
+    }
+}
+
+/// Blur `buf` in place. See [`GaussBlurOptions`].
+///
+/// Unlike [`gauss_blur`], the caller doesn't pass a temporary buffer. The blurred area is copied
+/// into internal buffers of [`BlurPixel::Blurred`], and transposed for the vertical pass.
+pub fn gauss_blur_with<P: BlurPixel, B: Buffer<P>>(
+    buf: &mut B,
+    options: &GaussBlurOptions,
+    extrapolation: &Extrapolation<P>,
+) {
+    let (width, height) = buf.dimensions();
+    let [x1, y1, x2, y2] = options.rect.unwrap_or([0, 0, width, height]);
+    let (x2, y2) = (x2.min(width), y2.min(height));
+    if x2 <= x1 || y2 <= y1 {
+        return;
+    }
+    let (w, h) = ((x2 - x1) as usize, (y2 - y1) as usize);
+    let extrapolation = match extrapolation {
+        Extrapolation::ExtendEdge => Extrapolation::ExtendEdge,
+        Extrapolation::Constant(p) => Extrapolation::Constant(p.to_blurred()),
+    };
+    let threads = match options.threads {
+        0 => std::thread::available_parallelism().map_or(1, |n| n.get()),
+        n => n,
+    };
+
+    let mut data: Vec<P::Blurred> = Vec::with_capacity(w * h);
+    for y in y1..y2 {
+        for x in x1..x2 {
+            data.push(buf.get_pixel(x, y).to_blurred());
+        }
+    }
+    if let Some(kernel) = LineKernel::new(options.std_deviation_x, options.exact_threshold) {
+        blur_lines(&mut data, w, &kernel, &extrapolation, threads);
+    }
+    if let Some(kernel) = LineKernel::new(options.std_deviation_y, options.exact_threshold) {
+        let mut transposed = transpose(&data, w, h);
+        blur_lines(&mut transposed, h, &kernel, &extrapolation, threads);
+        data = transpose(&transposed, h, w);
+    }
+    for (i, p) in data.iter().enumerate() {
+        let (x, y) = ((i % w) as u32, (i / w) as u32);
+        buf.put_pixel(x1 + x, y1 + y, P::from_blurred(p));
+    }
+}
+
+enum LineKernel {
+    /// Radii of box blurs applied in order.
+    Boxes(Vec<usize>),
+    /// Normalized weights from `-r` to `r`.
+    Exact(Vec<f32>),
+}
+
+impl LineKernel {
+    fn new(std_deviation: f64, exact_threshold: f64) -> Option<LineKernel> {
+        if std_deviation <= 0.0 {
+            return None;
+        }
+        if std_deviation < exact_threshold {
+            let r = (std_deviation * 3.0).ceil() as i32;
+            let weights: Vec<f64> = (-r..=r)
+                .map(|i| (-(i * i) as f64 / (2.0 * std_deviation * std_deviation)).exp())
+                .collect();
+            let sum: f64 = weights.iter().sum();
+            return Some(LineKernel::Exact(
+                weights.iter().map(|w| (w / sum) as f32).collect(),
+            ));
+        }
+        let radii: Vec<usize> = boxes_for_gauss(std_deviation, 3)
+            .into_iter()
+            .map(|b| ((b - 1) / 2).max(0) as usize)
+            .filter(|&r| r > 0)
+            .collect();
+        (!radii.is_empty()).then_some(LineKernel::Boxes(radii))
+    }
+}
+
+fn blur_lines<P: Pixel + Arithmetic + Send + Sync>(
+    data: &mut [P],
+    width: usize,
+    kernel: &LineKernel,
+    extrapolation: &Extrapolation<P>,
+    threads: usize,
+) {
+    let lines = data.len() / width;
+    if threads <= 1 || lines < 2 {
+        for line in data.chunks_mut(width) {
+            blur_line(line, kernel, extrapolation);
+        }
+        return;
+    }
+    let per_thread = lines.div_ceil(threads);
+    std::thread::scope(|s| {
+        for chunk in data.chunks_mut(per_thread * width) {
+            s.spawn(move || {
+                for line in chunk.chunks_mut(width) {
+                    blur_line(line, kernel, extrapolation);
+                }
+            });
+        }
+    });
+}
+
+fn blur_line<P: Pixel + Arithmetic>(
+    line: &mut [P],
+    kernel: &LineKernel,
+    extrapolation: &Extrapolation<P>,
+) {
+    let n = line.len();
+    let padded = |line: &[P], r: usize| -> Vec<P> {
+        let (first, last) = match extrapolation {
+            Extrapolation::ExtendEdge => (&line[0], &line[n - 1]),
+            Extrapolation::Constant(p) => (p, p),
+        };
+        std::iter::repeat_n(first, r)
+            .chain(line.iter())
+            .chain(std::iter::repeat_n(last, r))
+            .cloned()
+            .collect()
+    };
+    match kernel {
+        LineKernel::Boxes(radii) => {
+            for &r in radii {
+                let src = padded(line, r);
+                let k = 1.0 / (2 * r + 1) as f32;
+                let mut sum = src[..2 * r + 1]
+                    .iter()
+                    .fold(P::zero(), |s, p| s + p.clone());
+                line[0] = sum.clone() * k;
+                for i in 1..n {
+                    sum = sum + src[i + 2 * r].clone() - src[i - 1].clone();
+                    line[i] = sum.clone() * k;
+                }
+            }
+        }
+        LineKernel::Exact(weights) => {
+            let src = padded(line, weights.len() / 2);
+            for (i, p) in line.iter_mut().enumerate() {
+                *p = src[i..i + weights.len()]
+                    .iter()
+                    .zip(weights)
+                    .fold(P::zero(), |s, (p, &w)| s + p.clone() * w);
+            }
+        }
+    }
+}
+
+fn transpose<P: Clone>(data: &[P], width: usize, height: usize) -> Vec<P> {
+    (0..width * height)
+        .map(|i| data[(i % height) * width + i / height].clone())
+        .collect()
+}
+
+#[test]
+fn test() {
+    use crate::buffer::GenericBuffer;
+
+    let (w, h) = (19, 13);
+    let mut src = GenericBuffer::from_pixel(w, h, 0.0f32);
+    for (i, &(x, y)) in [(3, 4), (9, 6), (17, 11), (0, 0)].iter().enumerate() {
+        src.put_pixel(x, y, (i + 1) as f32);
+    }
+
+    // The exact kernel matches the brute force, and only blurs along x.
+    let mut buf = src.clone();
+    let options = GaussBlurOptions {
+        std_deviation_x: 1.5,
+        ..Default::default()
+    };
+    gauss_blur_with(&mut buf, &options, &Extrapolation::Constant(0.5));
+    let g = |d: i32| (-(d * d) as f32 / 4.5).exp();
+    let sum: f32 = (-5..=5).map(g).sum();
+    for y in 0..h {
+        for x in 0..w as i32 {
+            let expected: f32 = (-5..=5)
+                .map(|d| match x + d {
+                    sx if 0 <= sx && sx < w as i32 => *src.get_pixel(sx as u32, y) * g(d),
+                    _ => 0.5 * g(d),
+                })
+                .sum::<f32>()
+                / sum;
+            assert!((buf.get_pixel(x as u32, y) - expected).abs() < 1e-5);
+        }
+    }
+
+    // Box blurs spread the pixels, threads do not change the result,
+    // and pixels outside of the rectangle are kept.
+    let mut options = GaussBlurOptions::new(3.0);
+    options.rect = Some([2, 1, 16, 12]);
+    let mut buf = src.clone();
+    gauss_blur_with(&mut buf, &options, &Extrapolation::Constant(0.0));
+    assert!(*buf.get_pixel(9, 6) < 0.1 && *buf.get_pixel(12, 8) > 0.0);
+    assert_eq!(*buf.get_pixel(0, 0), 4.0);
+    assert_eq!(*buf.get_pixel(17, 11), 3.0);
+    options.threads = 4;
+    let mut threaded = src.clone();
+    gauss_blur_with(&mut threaded, &options, &Extrapolation::Constant(0.0));
+    assert_eq!(buf.as_slice(), threaded.as_slice());
+
+    // Transparent pixels do not darken opaque ones.
+    let mut buf = GenericBuffer::from_pixel(w, h, Rgba([0.0, 0.0, 0.0, 0.0]));
+    buf.put_pixel(9, 6, Rgba([1.0, 0.5, 0.25, 1.0]));
+    gauss_blur_with(
+        &mut buf,
+        &GaussBlurOptions::new(1.0),
+        &Extrapolation::ExtendEdge,
+    );
+    let p = buf.get_pixel(10, 6).0;
+    assert!(p[3] < 0.5);
+    for (a, b) in p.iter().zip([1.0, 0.5, 0.25]) {
+        assert!((a - b).abs() < 1e-5);
+    }
+}
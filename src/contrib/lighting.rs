@@ -0,0 +1,241 @@
+//! Diffuse and specular lighting of height maps like SVG `feDiffuseLighting` and `feSpecularLighting`.
+//!
+//! Heights are read from any channel of a [`Buffer`] and scaled by `surface_scale`.
+//! Surface normals are computed with Sobel kernels, extending the edges of the image.
+
+use crate::{
+    buffer::{Buffer, GenericBuffer},
+    pixel::{Pixel, Rgba},
+};
+
+/// A light source. Positions are in pixels, and `z` is above the surface.
+#[derive(Debug, Clone)]
+pub enum LightSource {
+    /// `feDistantLight`. Angles are in degrees.
+    Distant { azimuth: f32, elevation: f32 },
+    /// `fePointLight`.
+    Point { x: f32, y: f32, z: f32 },
+    /// `feSpotLight` pointing at `points_at`, with the cone angle in degrees.
+    Spot {
+        x: f32,
+        y: f32,
+        z: f32,
+        points_at: [f32; 3],
+        specular_exponent: f32,
+        limiting_cone_angle: Option<f32>,
+    },
+}
+
+impl LightSource {
+    /// Translate the position of the light.
+    pub fn translate(&self, dx: f32, dy: f32) -> LightSource {
+        match self.clone() {
+            LightSource::Distant { .. } => self.clone(),
+            LightSource::Point { x, y, z } => LightSource::Point {
+                x: x + dx,
+                y: y + dy,
+                z,
+            },
+            LightSource::Spot {
+                x,
+                y,
+                z,
+                points_at: [px, py, pz],
+                specular_exponent,
+                limiting_cone_angle,
+            } => LightSource::Spot {
+                x: x + dx,
+                y: y + dy,
+                z,
+                points_at: [px + dx, py + dy, pz],
+                specular_exponent,
+                limiting_cone_angle,
+            },
+        }
+    }
+
+    /// Unit vector from the surface point to the light, and the light color reaching the point.
+    fn vector_and_color(&self, surface: [f32; 3], color: [f32; 3]) -> ([f32; 3], [f32; 3]) {
+        match self {
+            LightSource::Distant { azimuth, elevation } => {
+                let (a, e) = (azimuth.to_radians(), elevation.to_radians());
+                ([a.cos() * e.cos(), a.sin() * e.cos(), e.sin()], color)
+            }
+            LightSource::Point { x, y, z } => (normalize(sub([*x, *y, *z], surface)), color),
+            LightSource::Spot {
+                x,
+                y,
+                z,
+                points_at,
+                specular_exponent,
+                limiting_cone_angle,
+            } => {
+                let l = normalize(sub([*x, *y, *z], surface));
+                let s = normalize(sub(*points_at, [*x, *y, *z]));
+                let cos = -dot(l, s);
+                let inside = limiting_cone_angle.is_none_or(|a| cos >= a.to_radians().cos());
+                if cos <= 0.0 || !inside {
+                    return (l, [0.0; 3]);
+                }
+                let k = cos.powf(*specular_exponent);
+                (l, color.map(|c| c * k))
+            }
+        }
+    }
+}
+
+/// Diffuse lighting. The output is opaque with the color `kd * (N . L) * lighting_color`.
+pub fn diffuse_lighting<P: Pixel, B: Buffer<P>>(
+    buf: &B,
+    height: impl Fn(&P) -> f32,
+    light: &LightSource,
+    lighting_color: &Rgba,
+    surface_scale: f32,
+    diffuse_constant: f32,
+) -> GenericBuffer<Rgba> {
+    lighting(
+        buf,
+        height,
+        light,
+        lighting_color,
+        surface_scale,
+        |n, l, c| {
+            let k = diffuse_constant * dot(n, l).max(0.0);
+            Rgba([c[0] * k, c[1] * k, c[2] * k, 1.0].map(|v| v.clamp(0.0, 1.0)))
+        },
+    )
+}
+
+/// Specular lighting with the color `ks * (N . H)^specular_exponent * lighting_color`.
+///
+/// The alpha of the output is the maximum of its channels, so compositing it
+/// with [`SrcOver`](crate::compositor::SrcOver) adds highlights.
+pub fn specular_lighting<P: Pixel, B: Buffer<P>>(
+    buf: &B,
+    height: impl Fn(&P) -> f32,
+    light: &LightSource,
+    lighting_color: &Rgba,
+    surface_scale: f32,
+    specular_constant: f32,
+    specular_exponent: f32,
+) -> GenericBuffer<Rgba> {
+    lighting(
+        buf,
+        height,
+        light,
+        lighting_color,
+        surface_scale,
+        |n, l, c| {
+            let h = normalize([l[0], l[1], l[2] + 1.0]);
+            let k = specular_constant * dot(n, h).max(0.0).powf(specular_exponent);
+            let [r, g, b] = c.map(|v| (v * k).clamp(0.0, 1.0));
+            let a = r.max(g).max(b);
+            if a == 0.0 {
+                Rgba([0.0; 4])
+            } else {
+                Rgba([r / a, g / a, b / a, a])
+            }
+        },
+    )
+}
+
+fn lighting<P: Pixel, B: Buffer<P>>(
+    buf: &B,
+    height: impl Fn(&P) -> f32,
+    light: &LightSource,
+    lighting_color: &Rgba,
+    surface_scale: f32,
+    shade: impl Fn([f32; 3], [f32; 3], [f32; 3]) -> Rgba,
+) -> GenericBuffer<Rgba> {
+    let (w, h) = buf.dimensions();
+    let mut out = GenericBuffer::from_pixel(w, h, Rgba([0.0; 4]));
+    if w == 0 || h == 0 {
+        return out;
+    }
+    let at = |x: i64, y: i64| {
+        let (x, y) = (x.clamp(0, w as i64 - 1), y.clamp(0, h as i64 - 1));
+        height(buf.get_pixel(x as u32, y as u32))
+    };
+    let color = [
+        lighting_color.0[0],
+        lighting_color.0[1],
+        lighting_color.0[2],
+    ];
+    for y in 0..h as i64 {
+        for x in 0..w as i64 {
+            let nx = -surface_scale / 4.0
+                * ((at(x + 1, y - 1) + 2.0 * at(x + 1, y) + at(x + 1, y + 1))
+                    - (at(x - 1, y - 1) + 2.0 * at(x - 1, y) + at(x - 1, y + 1)));
+            let ny = -surface_scale / 4.0
+                * ((at(x - 1, y + 1) + 2.0 * at(x, y + 1) + at(x + 1, y + 1))
+                    - (at(x - 1, y - 1) + 2.0 * at(x, y - 1) + at(x + 1, y - 1)));
+            let n = normalize([nx, ny, 1.0]);
+            let surface = [x as f32, y as f32, surface_scale * at(x, y)];
+            let (l, c) = light.vector_and_color(surface, color);
+            out.put_pixel(x as u32, y as u32, shade(n, l, c));
+        }
+    }
+    out
+}
+
+fn sub(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+fn dot(a: [f32; 3], b: [f32; 3]) -> f32 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn normalize(a: [f32; 3]) -> [f32; 3] {
+    let d = dot(a, a).sqrt();
+    if d == 0.0 {
+        a
+    } else {
+        a.map(|v| v / d)
+    }
+}
+
+#[test]
+fn test() {
+    // A flat surface is lit by the cosine of the elevation.
+    let flat = GenericBuffer::from_pixel(8, 8, Rgba([0.0, 0.0, 0.0, 1.0]));
+    let white = Rgba([1.0; 4]);
+    let light = LightSource::Distant {
+        azimuth: 0.0,
+        elevation: 30.0,
+    };
+    let out = diffuse_lighting(&flat, |p| p.0[3], &light, &white, 5.0, 1.0);
+    assert!((out.get_pixel(4, 4).0[0] - 0.5).abs() < 1e-5);
+    assert_eq!(out.get_pixel(4, 4).0[3], 1.0);
+
+    // A slope facing the light is brighter than one facing away.
+    let mut ridge = GenericBuffer::from_pixel(9, 9, Rgba([0.0; 4]));
+    for y in 0..9 {
+        for x in 0..9 {
+            let a = 1.0 - (x as f32 - 4.0).abs() / 4.0;
+            ridge.put_pixel(x, y, Rgba([0.0, 0.0, 0.0, a]));
+        }
+    }
+    let out = diffuse_lighting(&ridge, |p| p.0[3], &light, &white, 4.0, 1.0);
+    assert!(out.get_pixel(2, 4).0[0] < out.get_pixel(6, 4).0[0]);
+
+    // Highlights of a light right above, and a spot light pointing away.
+    let light = LightSource::Point {
+        x: 4.0,
+        y: 4.0,
+        z: 10.0,
+    };
+    let out = specular_lighting(&flat, |p| p.0[3], &light, &white, 1.0, 1.0, 20.0);
+    assert!((out.get_pixel(4, 4).0[3] - 1.0).abs() < 1e-5);
+    assert!(out.get_pixel(0, 0).0[3] < 0.5);
+    let light = LightSource::Spot {
+        x: 4.0,
+        y: 4.0,
+        z: 10.0,
+        points_at: [100.0, 4.0, 0.0],
+        specular_exponent: 1.0,
+        limiting_cone_angle: Some(30.0),
+    };
+    let out = diffuse_lighting(&flat, |p| p.0[3], &light, &white, 1.0, 1.0);
+    assert_eq!(out.get_pixel(4, 4).0, [0.0, 0.0, 0.0, 1.0]);
+}
@@ -1,5 +1,9 @@
 //! Collection of experimental functions.
 
+pub mod convolve;
 pub mod draw_image_transformed;
 pub mod gauss_blur;
 pub mod k_curve;
+pub mod lighting;
+pub mod morphology;
+pub mod warp;
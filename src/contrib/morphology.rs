@@ -0,0 +1,337 @@
+//! Morphological operators: dilation, erosion, opening and closing.
+//!
+//! Maximums and minimums over lines are computed with the van Herk/Gil-Werman algorithm,
+//! so structuring elements take O(1) per pixel regardless of radii.
+//! Elliptic ones are approximated by octagons made of a rectangle and two diagonal lines,
+//! which are coarser for flat ellipses.
+
+use crate::{
+    buffer::{Buffer, GenericBuffer},
+    contrib::gauss_blur::Extrapolation,
+    pixel::{MinMax, Pixel},
+};
+use std::iter::repeat_n;
+
+/// Shape of the structuring element with the radii `radius_x` and `radius_y`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StructuringElement {
+    /// `(2 * radius_x + 1) x (2 * radius_y + 1)` pixels like SVG `feMorphology`.
+    Rectangle,
+    /// The octagon circumscribing the ellipse. Its diagonal edges touch the ellipse.
+    Ellipse,
+}
+
+/// Dilate the image, which grows bright and opaque areas.
+pub fn dilate<P: Pixel + MinMax, B: Buffer<P>, C: Buffer<P>>(
+    buf: &mut B,
+    tmp: &mut C,
+    radius_x: u32,
+    radius_y: u32,
+    element: StructuringElement,
+    extrapolation: &Extrapolation<P>,
+) {
+    morphology(buf, tmp, radius_x, radius_y, element, extrapolation, P::max);
+}
+
+/// Erode the image, which shrinks bright and opaque areas.
+pub fn erode<P: Pixel + MinMax, B: Buffer<P>, C: Buffer<P>>(
+    buf: &mut B,
+    tmp: &mut C,
+    radius_x: u32,
+    radius_y: u32,
+    element: StructuringElement,
+    extrapolation: &Extrapolation<P>,
+) {
+    morphology(buf, tmp, radius_x, radius_y, element, extrapolation, P::min);
+}
+
+/// Erosion followed by dilation, which removes small bright details.
+pub fn open<P: Pixel + MinMax, B: Buffer<P>, C: Buffer<P>>(
+    buf: &mut B,
+    tmp: &mut C,
+    radius_x: u32,
+    radius_y: u32,
+    element: StructuringElement,
+    extrapolation: &Extrapolation<P>,
+) {
+    erode(buf, tmp, radius_x, radius_y, element, extrapolation);
+    dilate(buf, tmp, radius_x, radius_y, element, extrapolation);
+}
+
+/// Dilation followed by erosion, which fills small dark holes.
+pub fn close<P: Pixel + MinMax, B: Buffer<P>, C: Buffer<P>>(
+    buf: &mut B,
+    tmp: &mut C,
+    radius_x: u32,
+    radius_y: u32,
+    element: StructuringElement,
+    extrapolation: &Extrapolation<P>,
+) {
+    dilate(buf, tmp, radius_x, radius_y, element, extrapolation);
+    erode(buf, tmp, radius_x, radius_y, element, extrapolation);
+}
+
+fn morphology<P: Pixel, B: Buffer<P>, C: Buffer<P>>(
+    buf: &mut B,
+    tmp: &mut C,
+    radius_x: u32,
+    radius_y: u32,
+    element: StructuringElement,
+    extrapolation: &Extrapolation<P>,
+    f: fn(&P, &P) -> P,
+) {
+    let (a, b, d) = match element {
+        StructuringElement::Rectangle => (radius_x, radius_y, 0),
+        StructuringElement::Ellipse => octagon(radius_x, radius_y),
+    };
+    if d == 0 {
+        extremum_h(buf, tmp, a as usize, extrapolation, f);
+        extremum_t(tmp, buf, b as usize, extrapolation, f);
+        return;
+    }
+
+    // The diagonal lines leave the image through the corners, so the image is padded
+    // with the extrapolation to keep all the windows of the original pixels inside.
+    let (w, h) = buf.dimensions();
+    let (pw, ph) = (w + 2 * radius_x, h + 2 * radius_y);
+    let mut padded = GenericBuffer::from_pixel(pw, ph, buf.get_pixel(0, 0).clone());
+    for y in 0..ph {
+        for x in 0..pw {
+            let (sx, sy) = (x as i64 - radius_x as i64, y as i64 - radius_y as i64);
+            let p = match extrapolation {
+                Extrapolation::Constant(p)
+                    if sx < 0 || w as i64 <= sx || sy < 0 || h as i64 <= sy =>
+                {
+                    p.clone()
+                }
+                _ => buf
+                    .get_pixel(
+                        sx.clamp(0, w as i64 - 1) as u32,
+                        sy.clamp(0, h as i64 - 1) as u32,
+                    )
+                    .clone(),
+            };
+            padded.put_pixel(x, y, p);
+        }
+    }
+    let mut padded_tmp = padded.clone();
+    extremum_h(&mut padded, &mut padded_tmp, a as usize, extrapolation, f);
+    extremum_t(&mut padded_tmp, &mut padded, b as usize, extrapolation, f);
+    extremum_diagonal(&padded, &mut padded_tmp, d as usize, 1, f);
+    extremum_diagonal(&padded_tmp, &mut padded, d as usize, -1, f);
+    for y in 0..h {
+        for x in 0..w {
+            buf.put_pixel(x, y, padded.get_pixel(x + radius_x, y + radius_y).clone());
+        }
+    }
+}
+
+/// Radii of the rectangle and the diagonal lines whose sum is the octagon circumscribing the ellipse.
+///
+/// Pixels on the diagonal lines sum up to a checkerboard, which the rectangle fills if it is wider than one pixel.
+fn octagon(radius_x: u32, radius_y: u32) -> (u32, u32, u32) {
+    let (rx, ry) = (radius_x as f32, radius_y as f32);
+    // The largest `x + y` on the ellipse is `hypot(rx, ry)`, and it is `rx + ry - 2 * d` on the octagon.
+    let d = ((rx + ry - rx.hypot(ry)) / 2.0).round() as u32;
+    let d = d
+        .min(radius_x.min(radius_y) / 2)
+        .min(radius_x.max(radius_y).saturating_sub(1) / 2);
+    (radius_x - 2 * d, radius_y - 2 * d, d)
+}
+
+fn extremum_h<P: Pixel, B: Buffer<P>, C: Buffer<P>>(
+    src: &mut B,
+    dst: &mut C,
+    r: usize,
+    extrapolation: &Extrapolation<P>,
+    f: fn(&P, &P) -> P,
+) {
+    let (w, h) = src.dimensions();
+    for y in 0..h {
+        let line: Vec<P> = (0..w).map(|x| src.get_pixel(x, y).clone()).collect();
+        for (x, p) in extremum_1d(&line, r, extrapolation, f)
+            .into_iter()
+            .enumerate()
+        {
+            dst.put_pixel(x as u32, y, p);
+        }
+    }
+}
+
+fn extremum_t<P: Pixel, B: Buffer<P>, C: Buffer<P>>(
+    src: &mut B,
+    dst: &mut C,
+    r: usize,
+    extrapolation: &Extrapolation<P>,
+    f: fn(&P, &P) -> P,
+) {
+    let (w, h) = src.dimensions();
+    for x in 0..w {
+        let line: Vec<P> = (0..h).map(|y| src.get_pixel(x, y).clone()).collect();
+        for (y, p) in extremum_1d(&line, r, extrapolation, f)
+            .into_iter()
+            .enumerate()
+        {
+            dst.put_pixel(x, y as u32, p);
+        }
+    }
+}
+
+/// Extremums over the diagonal lines `(x + k, y + k * dy)` for `-r <= k <= r`.
+///
+/// Pixels out of the buffer are extended from the ends of the lines.
+fn extremum_diagonal<P: Pixel, B: Buffer<P>, C: Buffer<P>>(
+    src: &B,
+    dst: &mut C,
+    r: usize,
+    dy: i64,
+    f: fn(&P, &P) -> P,
+) {
+    let (w, h) = src.dimensions();
+    let (w, h) = (w as i64, h as i64);
+    // Lines start on the left edge or on the top (or bottom if `dy` is negative) edge.
+    let edge = if 0 < dy { 0 } else { h - 1 };
+    let starts = (0..w)
+        .map(|x| (x, edge))
+        .chain((1..h).map(|i| (0, edge + i * dy)));
+    for (x0, y0) in starts {
+        let n = (w - x0).min(if 0 < dy { h - y0 } else { y0 + 1 });
+        let line: Vec<P> = (0..n)
+            .map(|i| src.get_pixel((x0 + i) as u32, (y0 + i * dy) as u32).clone())
+            .collect();
+        for (i, p) in extremum_1d(&line, r, &Extrapolation::ExtendEdge, f)
+            .into_iter()
+            .enumerate()
+        {
+            let i = i as i64;
+            dst.put_pixel((x0 + i) as u32, (y0 + i * dy) as u32, p);
+        }
+    }
+}
+
+/// Extremums over the windows of `2 * r + 1` pixels centered at each pixel of the line.
+fn extremum_1d<P: Pixel>(
+    line: &[P],
+    r: usize,
+    extrapolation: &Extrapolation<P>,
+    f: fn(&P, &P) -> P,
+) -> Vec<P> {
+    if r == 0 || line.is_empty() {
+        return line.to_vec();
+    }
+    let (first, last) = match extrapolation {
+        Extrapolation::ExtendEdge => (&line[0], &line[line.len() - 1]),
+        Extrapolation::Constant(p) => (p, p),
+    };
+    let padded: Vec<P> = repeat_n(first, r)
+        .chain(line.iter())
+        .chain(repeat_n(last, r))
+        .cloned()
+        .collect();
+    let k = 2 * r + 1;
+    // Extremums from the start of each block of `k` pixels, and to the end of it.
+    let mut g = padded.clone();
+    for i in 1..g.len() {
+        if i % k != 0 {
+            g[i] = f(&g[i - 1], &padded[i]);
+        }
+    }
+    let mut h = padded.clone();
+    for i in (0..h.len() - 1).rev() {
+        if (i + 1) % k != 0 {
+            h[i] = f(&h[i + 1], &padded[i]);
+        }
+    }
+    (0..line.len()).map(|i| f(&h[i], &g[i + k - 1])).collect()
+}
+
+#[test]
+fn test() {
+    use crate::buffer::GenericBuffer;
+
+    let (w, h) = (23, 17);
+    let mut src = GenericBuffer::from_pixel(w, h, 0.0f32);
+    for (i, &(x, y)) in [(3, 4), (11, 8), (20, 15), (0, 0), (12, 9)]
+        .iter()
+        .enumerate()
+    {
+        src.put_pixel(x, y, 0.2 * (i + 1) as f32);
+    }
+
+    // Compare with the brute force for both elements and extrapolations.
+    for element in [StructuringElement::Rectangle, StructuringElement::Ellipse] {
+        for (rx, ry) in [(0, 0), (1, 1), (3, 2), (2, 5), (7, 4), (6, 6), (12, 3)] {
+            for extrapolation in [Extrapolation::ExtendEdge, Extrapolation::Constant(0.5)] {
+                let mut buf = src.clone();
+                let mut tmp = src.clone();
+                dilate(&mut buf, &mut tmp, rx, ry, element, &extrapolation);
+                let (a, b, d) = octagon(rx, ry);
+                for y in 0..h as i32 {
+                    for x in 0..w as i32 {
+                        let mut expected = f32::MIN;
+                        for dy in -(ry as i32)..=ry as i32 {
+                            for dx in -(rx as i32)..=rx as i32 {
+                                if element == StructuringElement::Ellipse
+                                    && rx > 0
+                                    && ry > 0
+                                    && dx.abs() + dy.abs() > (a + b + 2 * d) as i32
+                                {
+                                    continue;
+                                }
+                                let (sx, sy) = (x + dx, y + dy);
+                                let inside = 0 <= sx && sx < w as i32 && 0 <= sy && sy < h as i32;
+                                let v = match (&extrapolation, inside) {
+                                    (_, true) => *src.get_pixel(sx as u32, sy as u32),
+                                    (Extrapolation::ExtendEdge, false) => *src.get_pixel(
+                                        sx.clamp(0, w as i32 - 1) as u32,
+                                        sy.clamp(0, h as i32 - 1) as u32,
+                                    ),
+                                    (Extrapolation::Constant(c), false) => *c,
+                                };
+                                expected = expected.max(v);
+                            }
+                        }
+                        assert_eq!(*buf.get_pixel(x as u32, y as u32), expected);
+                    }
+                }
+            }
+        }
+    }
+
+    // The octagon contains the ellipse and stays close to it unless the ellipse is flat.
+    for (rx, ry) in [(3, 3), (6, 6), (10, 4), (20, 20), (40, 15)] {
+        let (a, b, d) = octagon(rx, ry);
+        for dy in -(ry as i32)..=ry as i32 {
+            for dx in -(rx as i32)..=rx as i32 {
+                let (fx, fy) = (dx as f32 / rx as f32, dy as f32 / ry as f32);
+                let inside = dx.abs() + dy.abs() <= (a + b + 2 * d) as i32;
+                assert!(inside || 1.0 < fx * fx + fy * fy, "{rx} {ry} {dx} {dy}");
+                assert!(!inside || fx * fx + fy * fy < 1.5, "{rx} {ry} {dx} {dy}");
+            }
+        }
+    }
+
+    // Opening removes details smaller than the element and closing fills holes.
+    let mut buf = src.clone();
+    let mut tmp = src.clone();
+    open(
+        &mut buf,
+        &mut tmp,
+        1,
+        1,
+        StructuringElement::Rectangle,
+        &Extrapolation::Constant(0.0),
+    );
+    assert!(buf.as_slice().iter().all(|&v| v == 0.0));
+    let mut buf = GenericBuffer::from_pixel(w, h, 1.0f32);
+    buf.put_pixel(10, 10, 0.0);
+    close(
+        &mut buf,
+        &mut tmp,
+        2,
+        2,
+        StructuringElement::Ellipse,
+        &Extrapolation::ExtendEdge,
+    );
+    assert!(buf.as_slice().iter().all(|&v| v == 1.0));
+}
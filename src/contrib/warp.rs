@@ -0,0 +1,333 @@
+//! Displacement maps and image warps: ripple, swirl, barrel/pincushion and mesh grids.
+//!
+//! Warps map each destination pixel to a position in the source image, which is sampled with an
+//! [`Interpolation`]. Positions outside of the source are handled by [`Extrapolation`].
+
+use crate::{
+    buffer::Buffer, contrib::gauss_blur::Extrapolation, interpolation::Interpolation, pixel::Pixel,
+    point::Point,
+};
+
+/// Fill `dst` with `src` sampled at `map(x, y)` for each destination pixel.
+pub fn warp<P, BD, BS, I>(
+    dst: &mut BD,
+    src: &BS,
+    interpolation: &I,
+    extrapolation: &Extrapolation<P>,
+    map: impl Fn(f32, f32) -> (f32, f32),
+) where
+    P: Pixel,
+    BD: Buffer<P>,
+    BS: Buffer<P>,
+    I: Interpolation<P, BS>,
+{
+    let (w, h) = dst.dimensions();
+    let (sw, sh) = src.dimensions();
+    let (max_x, max_y) = (sw as f32 - 1.0, sh as f32 - 1.0);
+    for y in 0..h {
+        for x in 0..w {
+            let (sx, sy) = map(x as f32, y as f32);
+            let inside = (0.0..=max_x).contains(&sx) && (0.0..=max_y).contains(&sy);
+            let p = match extrapolation {
+                _ if inside => interpolation.interpolate(src, Point::from((sx, sy))),
+                Extrapolation::ExtendEdge => interpolation.interpolate(
+                    src,
+                    Point::from((sx.clamp(0.0, max_x.max(0.0)), sy.clamp(0.0, max_y.max(0.0)))),
+                ),
+                Extrapolation::Constant(p) => p.clone(),
+            };
+            dst.put_pixel(x, y, p);
+        }
+    }
+}
+
+/// Displace pixels of `src` by two channels of `map` like SVG `feDisplacementMap`.
+///
+/// The pixel at `(x, y)` is sampled at `(x + scale * (x_channel - 0.5), y + scale * (y_channel - 0.5))`,
+/// where the channels are read from the pixel of `map` at `(x, y)`.
+#[allow(clippy::too_many_arguments)]
+pub fn displacement_map<P, PM, BD, BS, BM, I>(
+    dst: &mut BD,
+    src: &BS,
+    map: &BM,
+    x_channel: impl Fn(&PM) -> f32,
+    y_channel: impl Fn(&PM) -> f32,
+    scale: f32,
+    interpolation: &I,
+    extrapolation: &Extrapolation<P>,
+) where
+    P: Pixel,
+    PM: Pixel,
+    BD: Buffer<P>,
+    BS: Buffer<P>,
+    BM: Buffer<PM>,
+    I: Interpolation<P, BS>,
+{
+    let (mw, mh) = map.dimensions();
+    warp(dst, src, interpolation, extrapolation, |x, y| {
+        let (mx, my) = (x as u32, y as u32);
+        if mx >= mw || my >= mh {
+            return (x, y);
+        }
+        let p = map.get_pixel(mx, my);
+        (
+            x + scale * (x_channel(p) - 0.5),
+            y + scale * (y_channel(p) - 0.5),
+        )
+    });
+}
+
+/// Parametric warps. Positions and radii are in pixels and angles are in radians.
+#[derive(Debug, Clone)]
+pub enum Warp {
+    /// Concentric waves displacing pixels toward and away from the center.
+    Ripple {
+        center: [f32; 2],
+        amplitude: f32,
+        wavelength: f32,
+        phase: f32,
+    },
+    /// Rotation by `angle` at the center, decreasing to zero at `radius`.
+    Swirl {
+        center: [f32; 2],
+        radius: f32,
+        angle: f32,
+    },
+    /// Radial distortion normalized by `radius`. Positive `strength` gives barrel distortion
+    /// and negative gives pincushion.
+    Barrel {
+        center: [f32; 2],
+        radius: f32,
+        strength: f32,
+    },
+}
+
+impl Warp {
+    /// Map a destination position to the source position.
+    pub fn map(&self, x: f32, y: f32) -> (f32, f32) {
+        match self {
+            Warp::Ripple {
+                center,
+                amplitude,
+                wavelength,
+                phase,
+            } => {
+                let (dx, dy) = (x - center[0], y - center[1]);
+                let r = dx.hypot(dy);
+                if r == 0.0 {
+                    return (x, y);
+                }
+                let d = amplitude * (std::f32::consts::TAU * r / wavelength + phase).sin();
+                (x + dx / r * d, y + dy / r * d)
+            }
+            Warp::Swirl {
+                center,
+                radius,
+                angle,
+            } => {
+                let (dx, dy) = (x - center[0], y - center[1]);
+                let r = dx.hypot(dy);
+                if r >= *radius {
+                    return (x, y);
+                }
+                let t = 1.0 - r / radius;
+                let (s, c) = (angle * t * t).sin_cos();
+                (center[0] + dx * c - dy * s, center[1] + dx * s + dy * c)
+            }
+            Warp::Barrel {
+                center,
+                radius,
+                strength,
+            } => {
+                let (dx, dy) = (x - center[0], y - center[1]);
+                let k = 1.0 + strength * (dx * dx + dy * dy) / (radius * radius);
+                (center[0] + dx * k, center[1] + dy * k)
+            }
+        }
+    }
+
+    /// Apply the warp to `src`.
+    pub fn apply<P, BD, BS, I>(
+        &self,
+        dst: &mut BD,
+        src: &BS,
+        interpolation: &I,
+        extrapolation: &Extrapolation<P>,
+    ) where
+        P: Pixel,
+        BD: Buffer<P>,
+        BS: Buffer<P>,
+        I: Interpolation<P, BS>,
+    {
+        warp(dst, src, interpolation, extrapolation, |x, y| {
+            self.map(x, y)
+        });
+    }
+}
+
+/// A grid over `rect` of the destination whose vertices sample the source at `points`.
+///
+/// Positions inside each cell are interpolated bilinearly from its four vertices,
+/// and positions outside of `rect` are not moved.
+#[derive(Debug, Clone)]
+pub struct MeshWarp {
+    /// `[x1, y1, x2, y2]`.
+    rect: [f32; 4],
+    columns: usize,
+    rows: usize,
+    /// Source positions of `(columns + 1) x (rows + 1)` vertices in row-major order.
+    points: Vec<Point>,
+}
+
+impl MeshWarp {
+    /// Create a grid which does not move anything. `columns` and `rows` are at least 1.
+    pub fn new(rect: [f32; 4], columns: usize, rows: usize) -> Self {
+        let (columns, rows) = (columns.max(1), rows.max(1));
+        let points = (0..=rows)
+            .flat_map(|j| (0..=columns).map(move |i| (i, j)))
+            .map(|(i, j)| {
+                Point::from((
+                    rect[0] + (rect[2] - rect[0]) * i as f32 / columns as f32,
+                    rect[1] + (rect[3] - rect[1]) * j as f32 / rows as f32,
+                ))
+            })
+            .collect();
+        MeshWarp {
+            rect,
+            columns,
+            rows,
+            points,
+        }
+    }
+
+    /// Get `[x1, y1, x2, y2]` of the grid.
+    pub fn rect(&self) -> [f32; 4] {
+        self.rect
+    }
+
+    /// Get `(columns, rows)` of the grid.
+    pub fn dimensions(&self) -> (usize, usize) {
+        (self.columns, self.rows)
+    }
+
+    /// Source positions of the vertices in row-major order.
+    pub fn points(&self) -> &[Point] {
+        &self.points
+    }
+
+    /// Get the mutable source position of the vertex at the column `i` and the row `j`.
+    pub fn point_mut(&mut self, i: usize, j: usize) -> &mut Point {
+        &mut self.points[j * (self.columns + 1) + i]
+    }
+
+    /// Map a destination position to the source position.
+    pub fn map(&self, x: f32, y: f32) -> (f32, f32) {
+        let r = &self.rect;
+        if x < r[0] || r[2] < x || y < r[1] || r[3] < y {
+            return (x, y);
+        }
+        let u = (x - r[0]) / (r[2] - r[0]) * self.columns as f32;
+        let v = (y - r[1]) / (r[3] - r[1]) * self.rows as f32;
+        let (i, j) = (
+            (u as usize).min(self.columns - 1),
+            (v as usize).min(self.rows - 1),
+        );
+        let (fu, fv) = (u - i as f32, v - j as f32);
+        let n = self.columns + 1;
+        let p = |i: usize, j: usize| self.points[j * n + i];
+        let top = p(i, j).lerp(p(i + 1, j), fu);
+        let bottom = p(i, j + 1).lerp(p(i + 1, j + 1), fu);
+        top.lerp(bottom, fv).into()
+    }
+
+    /// Apply the warp to `src`.
+    pub fn apply<P, BD, BS, I>(
+        &self,
+        dst: &mut BD,
+        src: &BS,
+        interpolation: &I,
+        extrapolation: &Extrapolation<P>,
+    ) where
+        P: Pixel,
+        BD: Buffer<P>,
+        BS: Buffer<P>,
+        I: Interpolation<P, BS>,
+    {
+        warp(dst, src, interpolation, extrapolation, |x, y| {
+            self.map(x, y)
+        });
+    }
+}
+
+#[test]
+fn test() {
+    use crate::buffer::GenericBuffer;
+    use crate::interpolation::{Bilinear, NearestNeighbor};
+
+    let (w, h) = (12, 10);
+    let mut src = GenericBuffer::from_pixel(w, h, 0.0f32);
+    for y in 0..h {
+        for x in 0..w {
+            src.put_pixel(x, y, (x + y * w) as f32);
+        }
+    }
+    let mut dst = src.clone();
+
+    // Neutral warps keep the image.
+    let mesh = MeshWarp::new([2.0, 2.0, 9.0, 8.0], 3, 2);
+    mesh.apply(&mut dst, &src, &Bilinear, &Extrapolation::ExtendEdge);
+    for (a, b) in dst.as_slice().iter().zip(src.as_slice()) {
+        assert!((a - b).abs() < 1e-3);
+    }
+    let swirl = Warp::Swirl {
+        center: [6.0, 5.0],
+        radius: 3.0,
+        angle: 2.0,
+    };
+    assert_eq!(swirl.map(6.0, 5.0), (6.0, 5.0));
+    assert_eq!(swirl.map(10.0, 5.0), (10.0, 5.0));
+    let (x, y) = swirl.map(7.5, 5.0);
+    assert!(((x - 6.0).hypot(y - 5.0) - 1.5).abs() < 1e-5 && y > 5.0);
+    let barrel = Warp::Barrel {
+        center: [6.0, 5.0],
+        radius: 4.0,
+        strength: 0.5,
+    };
+    assert_eq!(barrel.map(10.0, 5.0), (12.0, 5.0));
+
+    // Empty grids are made of one cell.
+    let mesh = MeshWarp::new([0.0, 0.0, 4.0, 4.0], 0, 0);
+    assert_eq!((mesh.dimensions(), mesh.points().len()), ((1, 1), 4));
+    assert_eq!(mesh.map(2.0, 3.0), (2.0, 3.0));
+
+    // Moving vertices moves the cells.
+    let mut mesh = MeshWarp::new([0.0, 0.0, 11.0, 9.0], 1, 1);
+    for p in mesh.points.iter_mut() {
+        *p = Point::from((p.x() + 1.0, p.y()));
+    }
+    mesh.apply(
+        &mut dst,
+        &src,
+        &NearestNeighbor,
+        &Extrapolation::Constant(-1.0),
+    );
+    assert_eq!(*dst.get_pixel(3, 4), *src.get_pixel(4, 4));
+    assert_eq!(*dst.get_pixel(11, 4), -1.0);
+
+    // Displacement by a map, where 0.5 is neutral.
+    use crate::pixel::Rgba;
+    let mut map = GenericBuffer::from_pixel(w, h, Rgba([0.5, 0.5, 0.0, 1.0]));
+    map.put_pixel(5, 5, Rgba([1.0, 0.0, 0.0, 1.0]));
+    displacement_map(
+        &mut dst,
+        &src,
+        &map,
+        |p: &Rgba| p.0[0],
+        |p: &Rgba| p.0[1],
+        4.0,
+        &NearestNeighbor,
+        &Extrapolation::ExtendEdge,
+    );
+    assert_eq!(*dst.get_pixel(5, 5), *src.get_pixel(7, 3));
+    assert_eq!(*dst.get_pixel(6, 5), *src.get_pixel(6, 5));
+}
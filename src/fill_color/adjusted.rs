@@ -0,0 +1,34 @@
+use std::marker::PhantomData;
+
+use crate::{color_adjust::ColorAdjust, fill_color::FillColor, pixel::Rgba};
+
+/// Adjusts colors of the inner [`FillColor`] with [`ColorAdjust`].
+#[derive(Debug, Clone)]
+pub struct Adjusted<'a, C: Clone, FC: FillColor<C>> {
+    fill_color: &'a FC,
+    adjust: &'a ColorAdjust,
+    c: PhantomData<C>,
+}
+
+impl<'a, C: Clone, FC: FillColor<C>> Adjusted<'a, C, FC> {
+    pub fn new(fill_color: &'a FC, adjust: &'a ColorAdjust) -> Self {
+        Adjusted {
+            fill_color,
+            adjust,
+            c: PhantomData,
+        }
+    }
+}
+
+impl<'a, C, FC> FillColor<C> for Adjusted<'a, C, FC>
+where
+    C: Clone + Into<Rgba> + From<Rgba>,
+    FC: FillColor<C>,
+{
+    fn fill_color(&self, pos: [f32; 2]) -> C {
+        C::from(
+            self.adjust
+                .adjust_rgba(&self.fill_color.fill_color(pos).into()),
+        )
+    }
+}
@@ -5,11 +5,14 @@
 //! - [`RadialGradient`]
 //! - [`Pattern`]
 //! - [`Transform`]
+//! - [`Adjusted`]
 
+pub mod adjusted;
 pub mod gradient;
 pub mod pattern;
 pub mod transform;
 
+pub use adjusted::Adjusted;
 pub use gradient::{ConicGradient, LinearGradient, RadialGradient};
 pub use pattern::Pattern;
 pub use transform::Transform;
@@ -1,5 +1,6 @@
 use crate::buffer::Buffer;
 use crate::fill_color::FillColor;
+use crate::homography::Homography;
 use crate::interpolation::Interpolation;
 use crate::matrix::Matrix;
 use crate::pixel::Pixel;
@@ -16,7 +17,7 @@ where
     image: DB,
     interpolation: I,
     /// Expects inverted matrix.
-    matrix: Matrix,
+    matrix: Homography,
     _pixel: std::marker::PhantomData<P>,
 }
 
@@ -28,10 +29,17 @@ where
     I: Interpolation<P, B>,
 {
     pub fn new(image: DB, interpolation: I, matrix: Matrix) -> Self {
+        Pattern::with_homography(image, interpolation, matrix.into())
+    }
+
+    /// Create [`Pattern`] with a projective transformation.
+    ///
+    /// Like [`Pattern::new`], `homography` maps the destination coordinates to the image coordinates.
+    pub fn with_homography(image: DB, interpolation: I, homography: Homography) -> Self {
         Pattern {
             image,
             interpolation,
-            matrix,
+            matrix: homography,
             _pixel: Default::default(),
         }
     }
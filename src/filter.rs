@@ -0,0 +1,756 @@
+//! SVG-like filter effects.
+//!
+//! A [`Filter`] is a list of [`FilterEffect`]s evaluated in order like the children of SVG `<filter>`.
+//! Each primitive reads [`FilterInput`]s, that are the source image, its alpha or results of previous
+//! primitives, and its output can be named to be referred later.
+//! Primitives are evaluated on [`PremultipliedRgba`] within the filter region, and
+//! [`Context::filter_layer`](crate::context::Context::filter_layer) applies a filter to a layer.
+//! Colors are processed in sRGB, like `color-interpolation-filters="sRGB"`.
+
+use crate::buffer::{Buffer, GenericBuffer};
+use crate::compositor::{Basic, Compositor};
+use crate::contrib::gauss_blur::{gauss_blur_with, Extrapolation, GaussBlurOptions};
+use crate::contrib::lighting::{diffuse_lighting, specular_lighting, LightSource};
+use crate::contrib::morphology::{dilate, erode, StructuringElement};
+use crate::contrib::warp::displacement_map;
+use crate::interpolation::Bilinear;
+use crate::pixel::{Arithmetic, Pixel, PremultipliedRgba, Rgba};
+use std::collections::HashMap;
+
+/// A pixel type that filters can be applied to.
+pub trait FilterPixel: Pixel {
+    fn to_premultiplied(&self) -> PremultipliedRgba;
+    fn from_premultiplied(pixel: &PremultipliedRgba) -> Self;
+}
+
+impl FilterPixel for Rgba {
+    fn to_premultiplied(&self) -> PremultipliedRgba {
+        (*self).into()
+    }
+
+    fn from_premultiplied(pixel: &PremultipliedRgba) -> Self {
+        (*pixel).into()
+    }
+}
+
+impl FilterPixel for PremultipliedRgba {
+    fn to_premultiplied(&self) -> PremultipliedRgba {
+        *self
+    }
+
+    fn from_premultiplied(pixel: &PremultipliedRgba) -> Self {
+        *pixel
+    }
+}
+
+/// An input of a filter primitive like SVG `in` and `in2` attributes.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FilterInput {
+    /// The image the filter is applied to.
+    SourceGraphic,
+    /// The alpha channel of the source image with black color.
+    SourceAlpha,
+    /// The output of the previous primitive, or the source image for the first primitive.
+    Previous,
+    /// The output of the primitive named by [`FilterEffect::result`].
+    /// Unknown names refer to the previous output.
+    Result(String),
+}
+
+/// Operators of [`FilterPrimitive::Composite`] like `feComposite`.
+#[derive(Debug, Clone)]
+pub enum CompositeOperator {
+    Over,
+    In,
+    Out,
+    Atop,
+    Xor,
+    Lighter,
+    /// `k1 * i1 * i2 + k2 * i1 + k3 * i2 + k4` on premultiplied colors.
+    Arithmetic {
+        k1: f32,
+        k2: f32,
+        k3: f32,
+        k4: f32,
+    },
+}
+
+/// Operators of [`FilterPrimitive::Morphology`] like `feMorphology`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MorphologyOperator {
+    Erode,
+    Dilate,
+}
+
+/// Color transformations of [`FilterPrimitive::ColorMatrix`] like `feColorMatrix`.
+#[derive(Debug, Clone)]
+pub enum ColorMatrix {
+    /// 4x5 row-major matrix applied to `[r, g, b, a, 1]`.
+    Matrix([f32; 20]),
+    Saturate(f32),
+    /// Rotation of hue in degrees.
+    HueRotate(f32),
+    LuminanceToAlpha,
+}
+
+impl ColorMatrix {
+    /// Get the 4x5 row-major matrix.
+    pub fn matrix(&self) -> [f32; 20] {
+        match self {
+            ColorMatrix::Matrix(m) => *m,
+            ColorMatrix::Saturate(s) => [
+                0.213 + 0.787 * s,
+                0.715 - 0.715 * s,
+                0.072 - 0.072 * s,
+                0.0,
+                0.0,
+                0.213 - 0.213 * s,
+                0.715 + 0.285 * s,
+                0.072 - 0.072 * s,
+                0.0,
+                0.0,
+                0.213 - 0.213 * s,
+                0.715 - 0.715 * s,
+                0.072 + 0.928 * s,
+                0.0,
+                0.0,
+                0.0,
+                0.0,
+                0.0,
+                1.0,
+                0.0,
+            ],
+            ColorMatrix::HueRotate(deg) => {
+                let (s, c) = deg.to_radians().sin_cos();
+                [
+                    0.213 + c * 0.787 - s * 0.213,
+                    0.715 - c * 0.715 - s * 0.715,
+                    0.072 - c * 0.072 + s * 0.928,
+                    0.0,
+                    0.0,
+                    0.213 - c * 0.213 + s * 0.143,
+                    0.715 + c * 0.285 + s * 0.140,
+                    0.072 - c * 0.072 - s * 0.283,
+                    0.0,
+                    0.0,
+                    0.213 - c * 0.213 - s * 0.787,
+                    0.715 - c * 0.715 + s * 0.715,
+                    0.072 + c * 0.928 + s * 0.072,
+                    0.0,
+                    0.0,
+                    0.0,
+                    0.0,
+                    0.0,
+                    1.0,
+                    0.0,
+                ]
+            }
+            ColorMatrix::LuminanceToAlpha => {
+                let mut m = [0.0; 20];
+                m[15] = 0.2125;
+                m[16] = 0.7154;
+                m[17] = 0.0721;
+                m
+            }
+        }
+    }
+}
+
+/// Transfer functions of [`FilterPrimitive::ComponentTransfer`] like `feFuncR`.
+#[derive(Debug, Clone)]
+pub enum TransferFunction {
+    Identity,
+    /// Piecewise linear interpolation of the values.
+    Table(Vec<f32>),
+    /// Step function of the values.
+    Discrete(Vec<f32>),
+    Linear {
+        slope: f32,
+        intercept: f32,
+    },
+    Gamma {
+        amplitude: f32,
+        exponent: f32,
+        offset: f32,
+    },
+}
+
+impl TransferFunction {
+    /// Transfer a component in `0.0..=1.0`.
+    pub fn apply(&self, c: f32) -> f32 {
+        let v = match self {
+            TransferFunction::Identity => c,
+            TransferFunction::Table(values) if values.len() == 1 => values[0],
+            TransferFunction::Table(values) if !values.is_empty() => {
+                let n = (values.len() - 1) as f32;
+                let k = ((c * n) as usize).min(values.len() - 2);
+                values[k] + (c * n - k as f32) * (values[k + 1] - values[k])
+            }
+            TransferFunction::Discrete(values) if !values.is_empty() => {
+                let k = ((c * values.len() as f32) as usize).min(values.len() - 1);
+                values[k]
+            }
+            TransferFunction::Table(_) | TransferFunction::Discrete(_) => c,
+            TransferFunction::Linear { slope, intercept } => slope * c + intercept,
+            TransferFunction::Gamma {
+                amplitude,
+                exponent,
+                offset,
+            } => amplitude * c.powf(*exponent) + offset,
+        };
+        v.clamp(0.0, 1.0)
+    }
+}
+
+/// Filter primitives like SVG `fe*` elements.
+#[derive(Clone)]
+pub enum FilterPrimitive {
+    /// `feGaussianBlur`. Zero or negative deviation disables blurring in the direction.
+    GaussianBlur {
+        input: FilterInput,
+        std_deviation_x: f32,
+        std_deviation_y: f32,
+    },
+    /// `feOffset`. The offset is rounded to pixels.
+    Offset {
+        input: FilterInput,
+        dx: f32,
+        dy: f32,
+    },
+    /// `feFlood` with the color including `flood-opacity`.
+    Flood { color: Rgba },
+    /// `feBlend`, where `input` is blended over `input2`.
+    Blend {
+        input: FilterInput,
+        input2: FilterInput,
+        mode: Basic,
+    },
+    /// `feComposite`, where `input` is the source and `input2` is the destination.
+    Composite {
+        input: FilterInput,
+        input2: FilterInput,
+        operator: CompositeOperator,
+    },
+    /// `feMerge`, where later inputs are drawn over earlier ones.
+    Merge { inputs: Vec<FilterInput> },
+    /// `feMorphology`. The radii are rounded to pixels.
+    Morphology {
+        input: FilterInput,
+        operator: MorphologyOperator,
+        radius_x: f32,
+        radius_y: f32,
+    },
+    /// `feDiffuseLighting` of the alpha of the input. The light is positioned in pixels of the image.
+    DiffuseLighting {
+        input: FilterInput,
+        light: LightSource,
+        lighting_color: Rgba,
+        surface_scale: f32,
+        diffuse_constant: f32,
+    },
+    /// `feSpecularLighting` of the alpha of the input. The light is positioned in pixels of the image.
+    SpecularLighting {
+        input: FilterInput,
+        light: LightSource,
+        lighting_color: Rgba,
+        surface_scale: f32,
+        specular_constant: f32,
+        specular_exponent: f32,
+    },
+    /// `feDisplacementMap` displacing `input` by the channels of unpremultiplied `input2`,
+    /// where channels are indices of `[r, g, b, a]`.
+    DisplacementMap {
+        input: FilterInput,
+        input2: FilterInput,
+        scale: f32,
+        x_channel: usize,
+        y_channel: usize,
+    },
+    /// `feColorMatrix` applied to unpremultiplied colors.
+    ColorMatrix {
+        input: FilterInput,
+        matrix: ColorMatrix,
+    },
+    /// `feComponentTransfer` with functions for `[r, g, b, a]` applied to unpremultiplied colors.
+    ComponentTransfer {
+        input: FilterInput,
+        functions: [TransferFunction; 4],
+    },
+}
+
+/// A primitive in a [`Filter`] with its options.
+#[derive(Clone)]
+pub struct FilterEffect {
+    pub primitive: FilterPrimitive,
+    /// Name of the output to refer by [`FilterInput::Result`].
+    pub result: Option<String>,
+    /// Primitive subregion as `[x1, y1, x2, y2]`. The output outside of it is transparent.
+    pub subregion: Option<[f32; 4]>,
+}
+
+/// A filter graph like SVG `<filter>`.
+#[derive(Clone)]
+pub struct Filter {
+    /// Filter region as `[x1, y1, x2, y2]` in pixels. The output outside of it is transparent.
+    pub region: [f32; 4],
+    pub effects: Vec<FilterEffect>,
+}
+
+type Image = GenericBuffer<PremultipliedRgba>;
+
+impl Filter {
+    /// Create an empty [`Filter`], which outputs the source within `region`.
+    pub fn new(region: [f32; 4]) -> Self {
+        Filter {
+            region,
+            effects: Vec::new(),
+        }
+    }
+
+    /// Append a primitive.
+    pub fn push(&mut self, primitive: FilterPrimitive) -> &mut Self {
+        self.effects.push(FilterEffect {
+            primitive,
+            result: None,
+            subregion: None,
+        });
+        self
+    }
+
+    /// Append a primitive with the name of its output.
+    pub fn push_named(&mut self, result: &str, primitive: FilterPrimitive) -> &mut Self {
+        self.effects.push(FilterEffect {
+            primitive,
+            result: Some(result.to_string()),
+            subregion: None,
+        });
+        self
+    }
+
+    /// Apply the filter to `source` and get the output of the last primitive.
+    pub fn apply<P: FilterPixel, B: Buffer<P>>(&self, source: &B) -> GenericBuffer<P> {
+        let (width, height) = source.dimensions();
+        let zero = PremultipliedRgba::zero();
+        let mut out = GenericBuffer::from_pixel(width, height, P::from_premultiplied(&zero));
+        let [x0, y0, x1, y1] = match clip_rect(&self.region, width, height) {
+            Some(r) => r,
+            None => return out,
+        };
+        let mut graphic = GenericBuffer::from_pixel(x1 - x0, y1 - y0, zero);
+        for y in y0..y1 {
+            for x in x0..x1 {
+                graphic.put_pixel(x - x0, y - y0, source.get_pixel(x, y).to_premultiplied());
+            }
+        }
+        let result = self.evaluate(graphic, [x0, y0]);
+        for y in y0..y1 {
+            for x in x0..x1 {
+                out.put_pixel(
+                    x,
+                    y,
+                    P::from_premultiplied(result.get_pixel(x - x0, y - y0)),
+                );
+            }
+        }
+        out
+    }
+
+    fn evaluate(&self, graphic: Image, origin: [u32; 2]) -> Image {
+        let alpha = map(&graphic, |p| PremultipliedRgba([0.0, 0.0, 0.0, p.0[3]]));
+        let mut outputs: Vec<Image> = Vec::with_capacity(self.effects.len());
+        let mut names: HashMap<&str, usize> = HashMap::new();
+        for effect in self.effects.iter() {
+            let input = |i: &FilterInput| -> &Image {
+                let previous = outputs.last().unwrap_or(&graphic);
+                match i {
+                    FilterInput::SourceGraphic => &graphic,
+                    FilterInput::SourceAlpha => &alpha,
+                    FilterInput::Previous => previous,
+                    FilterInput::Result(name) => {
+                        names.get(name.as_str()).map_or(previous, |&i| &outputs[i])
+                    }
+                }
+            };
+            let mut out = effect
+                .primitive
+                .evaluate(&input, graphic.dimensions(), origin);
+            if let Some(subregion) = &effect.subregion {
+                clear_outside(&mut out, subregion, origin);
+            }
+            if let Some(name) = &effect.result {
+                names.insert(name, outputs.len());
+            }
+            outputs.push(out);
+        }
+        outputs.pop().unwrap_or(graphic)
+    }
+}
+
+impl FilterPrimitive {
+    fn evaluate<'a>(
+        &self,
+        input: &dyn Fn(&FilterInput) -> &'a Image,
+        (width, height): (u32, u32),
+        [ox, oy]: [u32; 2],
+    ) -> Image {
+        match self {
+            FilterPrimitive::GaussianBlur {
+                input: i,
+                std_deviation_x,
+                std_deviation_y,
+            } => {
+                let mut buf = input(i).clone();
+                blur(&mut buf, *std_deviation_x, *std_deviation_y);
+                // Running sums of box blurs leave tiny errors around zero.
+                map(&buf, |p| PremultipliedRgba(p.0.map(|c| c.clamp(0.0, 1.0))))
+            }
+            FilterPrimitive::Offset { input: i, dx, dy } => {
+                let src = input(i);
+                let (dx, dy) = (dx.round() as i64, dy.round() as i64);
+                let mut buf = GenericBuffer::from_pixel(width, height, PremultipliedRgba::zero());
+                for y in 0..height {
+                    for x in 0..width {
+                        let (sx, sy) = (x as i64 - dx, y as i64 - dy);
+                        if 0 <= sx && sx < width as i64 && 0 <= sy && sy < height as i64 {
+                            buf.put_pixel(x, y, *src.get_pixel(sx as u32, sy as u32));
+                        }
+                    }
+                }
+                buf
+            }
+            FilterPrimitive::Flood { color } => {
+                GenericBuffer::from_pixel(width, height, (*color).into())
+            }
+            FilterPrimitive::Blend {
+                input: i,
+                input2,
+                mode,
+            } => {
+                let composite = Compositor::<PremultipliedRgba>::composite(mode);
+                zip_with(input(input2), input(i), composite)
+            }
+            FilterPrimitive::Composite {
+                input: i,
+                input2,
+                operator,
+            } => {
+                if let CompositeOperator::Arithmetic { k1, k2, k3, k4 } = operator {
+                    return zip_with(input(i), input(input2), |a, b| {
+                        let c = |j: usize| {
+                            (k1 * a.0[j] * b.0[j] + k2 * a.0[j] + k3 * b.0[j] + k4).clamp(0.0, 1.0)
+                        };
+                        let alpha = c(3);
+                        PremultipliedRgba([
+                            c(0).min(alpha),
+                            c(1).min(alpha),
+                            c(2).min(alpha),
+                            alpha,
+                        ])
+                    });
+                }
+                zip_with(input(i), input(input2), |src, dst| {
+                    porter_duff(operator, src, dst)
+                })
+            }
+            FilterPrimitive::Merge { inputs } => {
+                let empty = GenericBuffer::from_pixel(width, height, PremultipliedRgba::zero());
+                inputs.iter().fold(empty, |dst, i| {
+                    zip_with(input(i), &dst, |src, dst| {
+                        porter_duff(&CompositeOperator::Over, src, dst)
+                    })
+                })
+            }
+            FilterPrimitive::Morphology {
+                input: i,
+                operator,
+                radius_x,
+                radius_y,
+            } => {
+                let mut buf = input(i).clone();
+                let mut tmp = buf.clone();
+                let (rx, ry) = (
+                    radius_x.round().max(0.0) as u32,
+                    radius_y.round().max(0.0) as u32,
+                );
+                let extrapolation = Extrapolation::Constant(PremultipliedRgba::zero());
+                let element = StructuringElement::Rectangle;
+                match operator {
+                    MorphologyOperator::Erode => {
+                        erode(&mut buf, &mut tmp, rx, ry, element, &extrapolation)
+                    }
+                    MorphologyOperator::Dilate => {
+                        dilate(&mut buf, &mut tmp, rx, ry, element, &extrapolation)
+                    }
+                }
+                buf
+            }
+            FilterPrimitive::DiffuseLighting {
+                input: i,
+                light,
+                lighting_color,
+                surface_scale,
+                diffuse_constant,
+            } => {
+                let light = light.translate(-(ox as f32), -(oy as f32));
+                let out = diffuse_lighting(
+                    input(i),
+                    |p| p.0[3],
+                    &light,
+                    lighting_color,
+                    *surface_scale,
+                    *diffuse_constant,
+                );
+                map_rgba(&out)
+            }
+            FilterPrimitive::SpecularLighting {
+                input: i,
+                light,
+                lighting_color,
+                surface_scale,
+                specular_constant,
+                specular_exponent,
+            } => {
+                let light = light.translate(-(ox as f32), -(oy as f32));
+                let out = specular_lighting(
+                    input(i),
+                    |p| p.0[3],
+                    &light,
+                    lighting_color,
+                    *surface_scale,
+                    *specular_constant,
+                    *specular_exponent,
+                );
+                map_rgba(&out)
+            }
+            FilterPrimitive::DisplacementMap {
+                input: i,
+                input2,
+                scale,
+                x_channel,
+                y_channel,
+            } => {
+                let mut buf = GenericBuffer::from_pixel(width, height, PremultipliedRgba::zero());
+                let channel = |p: &PremultipliedRgba, i: usize| {
+                    let p: Rgba = (*p).into();
+                    p.0[i]
+                };
+                displacement_map(
+                    &mut buf,
+                    input(i),
+                    input(input2),
+                    |p| channel(p, *x_channel),
+                    |p| channel(p, *y_channel),
+                    *scale,
+                    &Bilinear,
+                    &Extrapolation::Constant(PremultipliedRgba::zero()),
+                );
+                buf
+            }
+            FilterPrimitive::ColorMatrix { input: i, matrix } => {
+                let m = matrix.matrix();
+                map_unpremultiplied(input(i), |c| {
+                    let row = |r: &[f32]| {
+                        (r[0] * c[0] + r[1] * c[1] + r[2] * c[2] + r[3] * c[3] + r[4])
+                            .clamp(0.0, 1.0)
+                    };
+                    [
+                        row(&m[0..5]),
+                        row(&m[5..10]),
+                        row(&m[10..15]),
+                        row(&m[15..20]),
+                    ]
+                })
+            }
+            FilterPrimitive::ComponentTransfer {
+                input: i,
+                functions,
+            } => map_unpremultiplied(input(i), |c| {
+                [
+                    functions[0].apply(c[0]),
+                    functions[1].apply(c[1]),
+                    functions[2].apply(c[2]),
+                    functions[3].apply(c[3]),
+                ]
+            }),
+        }
+    }
+}
+
+/// Composites premultiplied colors with the Porter-Duff operator as `src * fa + dst * fb`.
+fn porter_duff(
+    operator: &CompositeOperator,
+    src: &PremultipliedRgba,
+    dst: &PremultipliedRgba,
+) -> PremultipliedRgba {
+    let (sa, da) = (src.0[3], dst.0[3]);
+    let (fa, fb) = match operator {
+        CompositeOperator::Over => (1.0, 1.0 - sa),
+        CompositeOperator::In => (da, 0.0),
+        CompositeOperator::Out => (1.0 - da, 0.0),
+        CompositeOperator::Atop => (da, 1.0 - sa),
+        CompositeOperator::Xor => (1.0 - da, 1.0 - sa),
+        CompositeOperator::Lighter | CompositeOperator::Arithmetic { .. } => (1.0, 1.0),
+    };
+    let c = |j: usize| (src.0[j] * fa + dst.0[j] * fb).min(1.0);
+    PremultipliedRgba([c(0), c(1), c(2), c(3)])
+}
+
+fn blur(buf: &mut Image, std_deviation_x: f32, std_deviation_y: f32) {
+    let options = GaussBlurOptions {
+        std_deviation_x: std_deviation_x as f64,
+        std_deviation_y: std_deviation_y as f64,
+        ..Default::default()
+    };
+    gauss_blur_with(
+        buf,
+        &options,
+        &Extrapolation::Constant(PremultipliedRgba::zero()),
+    );
+}
+
+fn map(src: &Image, f: impl Fn(&PremultipliedRgba) -> PremultipliedRgba) -> Image {
+    GenericBuffer {
+        width: src.width,
+        height: src.height,
+        buffer: src.buffer.iter().map(f).collect(),
+    }
+}
+
+fn map_rgba(src: &GenericBuffer<Rgba>) -> Image {
+    GenericBuffer {
+        width: src.width,
+        height: src.height,
+        buffer: src.buffer.iter().map(|&p| p.into()).collect(),
+    }
+}
+
+fn map_unpremultiplied(src: &Image, f: impl Fn([f32; 4]) -> [f32; 4]) -> Image {
+    map(src, |p| {
+        let c: Rgba = (*p).into();
+        Rgba(f(c.0)).into()
+    })
+}
+
+fn zip_with(
+    a: &Image,
+    b: &Image,
+    f: impl Fn(&PremultipliedRgba, &PremultipliedRgba) -> PremultipliedRgba,
+) -> Image {
+    GenericBuffer {
+        width: a.width,
+        height: a.height,
+        buffer: a
+            .buffer
+            .iter()
+            .zip(b.buffer.iter())
+            .map(|(a, b)| f(a, b))
+            .collect(),
+    }
+}
+
+/// Rounds out `rect` and clips it to the image. Returns `None` if it is empty.
+fn clip_rect(rect: &[f32; 4], width: u32, height: u32) -> Option<[u32; 4]> {
+    let x0 = rect[0].floor().clamp(0.0, width as f32) as u32;
+    let y0 = rect[1].floor().clamp(0.0, height as f32) as u32;
+    let x1 = rect[2].ceil().clamp(0.0, width as f32) as u32;
+    let y1 = rect[3].ceil().clamp(0.0, height as f32) as u32;
+    (x0 < x1 && y0 < y1).then_some([x0, y0, x1, y1])
+}
+
+fn clear_outside(buf: &mut Image, rect: &[f32; 4], [ox, oy]: [u32; 2]) {
+    let (width, height) = buf.dimensions();
+    let [x0, y0, x1, y1] = [
+        rect[0].round() - ox as f32,
+        rect[1].round() - oy as f32,
+        rect[2].round() - ox as f32,
+        rect[3].round() - oy as f32,
+    ];
+    for y in 0..height {
+        for x in 0..width {
+            let (fx, fy) = (x as f32, y as f32);
+            if fx < x0 || x1 <= fx || fy < y0 || y1 <= fy {
+                buf.put_pixel(x, y, PremultipliedRgba::zero());
+            }
+        }
+    }
+}
+
+#[test]
+fn test() {
+    let mut src = GenericBuffer::from_pixel(20, 20, Rgba([0.0, 0.0, 0.0, 0.0]));
+    for y in 5..10 {
+        for x in 5..10 {
+            src.put_pixel(x, y, Rgba([1.0, 0.0, 0.0, 1.0]));
+        }
+    }
+
+    // A hard drop shadow under the source.
+    let mut filter = Filter::new([0.0, 0.0, 20.0, 20.0]);
+    filter
+        .push_named(
+            "offset",
+            FilterPrimitive::Offset {
+                input: FilterInput::SourceAlpha,
+                dx: 3.0,
+                dy: 3.0,
+            },
+        )
+        .push(FilterPrimitive::Flood {
+            color: Rgba([0.0, 0.0, 1.0, 0.5]),
+        })
+        .push_named(
+            "shadow",
+            FilterPrimitive::Composite {
+                input: FilterInput::Previous,
+                input2: FilterInput::Result("offset".to_string()),
+                operator: CompositeOperator::In,
+            },
+        )
+        .push(FilterPrimitive::Merge {
+            inputs: vec![
+                FilterInput::Result("shadow".to_string()),
+                FilterInput::SourceGraphic,
+            ],
+        });
+    let out = filter.apply(&src);
+    assert_eq!(out.get_pixel(6, 6).0, [1.0, 0.0, 0.0, 1.0]);
+    assert_eq!(out.get_pixel(11, 11).0, [0.0, 0.0, 1.0, 0.5]);
+    assert_eq!(out.get_pixel(2, 2).0[3], 0.0);
+
+    // The region clips the output and blurring keeps the total alpha.
+    let mut filter = Filter::new([0.0, 0.0, 20.0, 12.0]);
+    filter.push(FilterPrimitive::GaussianBlur {
+        input: FilterInput::SourceGraphic,
+        std_deviation_x: 1.5,
+        std_deviation_y: 0.0,
+    });
+    let out = filter.apply(&src);
+    assert_eq!(out.get_pixel(7, 15).0[3], 0.0);
+    let row: f32 = (0..20).map(|x| out.get_pixel(x, 7).0[3]).sum();
+    assert!((row - 5.0).abs() < 1e-4);
+    assert!(out.get_pixel(4, 7).0[3] > 0.0);
+    assert_eq!(out.get_pixel(7, 4).0[3], 0.0);
+
+    // Color operations work on unpremultiplied colors.
+    let mut filter = Filter::new([0.0, 0.0, 20.0, 20.0]);
+    filter
+        .push(FilterPrimitive::ColorMatrix {
+            input: FilterInput::SourceGraphic,
+            matrix: ColorMatrix::HueRotate(0.0),
+        })
+        .push(FilterPrimitive::ComponentTransfer {
+            input: FilterInput::Previous,
+            functions: [
+                TransferFunction::Linear {
+                    slope: 0.5,
+                    intercept: 0.0,
+                },
+                TransferFunction::Table(vec![0.0, 1.0]),
+                TransferFunction::Discrete(vec![0.2, 0.8]),
+                TransferFunction::Identity,
+            ],
+        });
+    let out = filter.apply(&src);
+    let [r, g, b, a] = out.get_pixel(7, 7).0;
+    assert!((r - 0.5).abs() < 1e-3 && g.abs() < 1e-3 && (b - 0.2).abs() < 1e-6 && a == 1.0);
+}
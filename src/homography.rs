@@ -0,0 +1,171 @@
+//! Projective transformation
+
+use crate::matrix::Matrix;
+use crate::point::Point;
+
+/// 3x3 matrix for projective (perspective) transformation.
+///
+/// Elements are in row-major order; a point `(x, y)` is mapped to
+/// `((m0 x + m1 y + m2) / w, (m3 x + m4 y + m5) / w)` where `w = m6 x + m7 y + m8`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Homography(pub [f32; 9]);
+
+impl Homography {
+    /// Create a Homography that no transform.
+    pub fn new() -> Homography {
+        Homography([1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0])
+    }
+
+    /// Create a Homography that maps the unit square `(0, 0), (1, 0), (1, 1), (0, 1)` to the quad.
+    pub fn from_unit_square_to_quad(quad: [Point; 4]) -> Option<Homography> {
+        let [p0, p1, p2, p3] = quad;
+        let d1 = p1 - p2;
+        let d2 = p3 - p2;
+        let d3 = p0 - p1 + p2 - p3;
+        if d3.x() == 0.0 && d3.y() == 0.0 {
+            return Some(Homography([
+                p1.x() - p0.x(),
+                p2.x() - p1.x(),
+                p0.x(),
+                p1.y() - p0.y(),
+                p2.y() - p1.y(),
+                p0.y(),
+                0.0,
+                0.0,
+                1.0,
+            ]));
+        }
+        let det = d1.x() * d2.y() - d2.x() * d1.y();
+        if det == 0.0 {
+            return None;
+        }
+        let g = (d3.x() * d2.y() - d2.x() * d3.y()) / det;
+        let h = (d1.x() * d3.y() - d3.x() * d1.y()) / det;
+        Some(Homography([
+            p1.x() - p0.x() + g * p1.x(),
+            p3.x() - p0.x() + h * p3.x(),
+            p0.x(),
+            p1.y() - p0.y() + g * p1.y(),
+            p3.y() - p0.y() + h * p3.y(),
+            p0.y(),
+            g,
+            h,
+            1.0,
+        ]))
+    }
+
+    /// Create a Homography that maps the corners of `src` to the corners of `dst` in order.
+    ///
+    /// Returns `None` if either quad is degenerate.
+    pub fn from_quad_to_quad(src: [Point; 4], dst: [Point; 4]) -> Option<Homography> {
+        let src = Homography::from_unit_square_to_quad(src)?.try_inverse()?;
+        let dst = Homography::from_unit_square_to_quad(dst)?;
+        Some(src.then(&dst))
+    }
+
+    /// Transform the [`Point`].
+    pub fn apply<P: From<Point> + Into<Point>>(&self, p: P) -> P {
+        let p: Point = p.into();
+        let s = &self.0;
+        let w = p.x() * s[6] + p.y() * s[7] + s[8];
+        Point::from((
+            (p.x() * s[0] + p.y() * s[1] + s[2]) / w,
+            (p.x() * s[3] + p.y() * s[4] + s[5]) / w,
+        ))
+        .into()
+    }
+
+    /// Return the determinant.
+    pub fn determinant(&self) -> f32 {
+        let s = &self.0;
+        s[0] * (s[4] * s[8] - s[5] * s[7]) - s[1] * (s[3] * s[8] - s[5] * s[6])
+            + s[2] * (s[3] * s[7] - s[4] * s[6])
+    }
+
+    /// Inverse the homography. Returns `None` if it is singular.
+    pub fn try_inverse(&self) -> Option<Homography> {
+        let det = self.determinant();
+        if det == 0.0 || !det.is_finite() {
+            return None;
+        }
+        let s = &self.0;
+        let a = 1.0 / det;
+        Some(Homography([
+            a * (s[4] * s[8] - s[5] * s[7]),
+            a * (s[2] * s[7] - s[1] * s[8]),
+            a * (s[1] * s[5] - s[2] * s[4]),
+            a * (s[5] * s[6] - s[3] * s[8]),
+            a * (s[0] * s[8] - s[2] * s[6]),
+            a * (s[2] * s[3] - s[0] * s[5]),
+            a * (s[3] * s[7] - s[4] * s[6]),
+            a * (s[1] * s[6] - s[0] * s[7]),
+            a * (s[0] * s[4] - s[1] * s[3]),
+        ]))
+    }
+
+    /// Inverse the homography.
+    pub fn inverse(&self) -> Homography {
+        self.try_inverse().unwrap_or(Homography([f32::NAN; 9]))
+    }
+
+    /// Return the composition that applies `self` and then `rhs`.
+    pub fn then(&self, rhs: &Homography) -> Homography {
+        let s = &self.0;
+        let t = &rhs.0;
+        let mut m = [0.0; 9];
+        for r in 0..3 {
+            for c in 0..3 {
+                m[r * 3 + c] = t[r * 3] * s[c] + t[r * 3 + 1] * s[3 + c] + t[r * 3 + 2] * s[6 + c];
+            }
+        }
+        Homography(m)
+    }
+
+    /// Return whether it is directly or indirectly.
+    /// An indirect homography makes path flip.
+    pub fn is_direct(&self) -> bool {
+        0.0 <= self.determinant() * self.0[8]
+    }
+}
+
+impl Default for Homography {
+    fn default() -> Self {
+        Homography::new()
+    }
+}
+
+impl From<Matrix> for Homography {
+    fn from(matrix: Matrix) -> Self {
+        let m = &matrix.0;
+        Homography([m[0], m[1], m[2], m[3], m[4], m[5], 0.0, 0.0, 1.0])
+    }
+}
+
+#[test]
+fn test() {
+    let src = [
+        Point::from((0.0, 0.0)),
+        Point::from((100.0, 0.0)),
+        Point::from((100.0, 50.0)),
+        Point::from((0.0, 50.0)),
+    ];
+    let dst = [
+        Point::from((10.0, 20.0)),
+        Point::from((90.0, 5.0)),
+        Point::from((120.0, 80.0)),
+        Point::from((-5.0, 60.0)),
+    ];
+    let h = Homography::from_quad_to_quad(src, dst).unwrap();
+    for (s, d) in src.iter().zip(dst.iter()) {
+        assert!((h.apply(*s) - *d).norm() < 0.001);
+        assert!((h.inverse().apply(*d) - *s).norm() < 0.001);
+    }
+
+    let m = Matrix::new()
+        .rotate(0.3)
+        .scale(2.0, 0.5)
+        .translate(3.0, 4.0);
+    let p = Point::from((5.0, 6.0));
+    assert!((Homography::from(m).apply(p) - m.apply(p)).norm() < 0.0001);
+    assert!((Homography::from(m).then(&h).apply(p) - h.apply(m.apply(p))).norm() < 0.001);
+}
@@ -48,6 +48,13 @@ impl Into<image::Rgba<u8>> for crate::pixel::Rgba {
     }
 }
 
+impl From<image::Rgba<u8>> for crate::pixel::Rgba {
+    #[inline]
+    fn from(p: image::Rgba<u8>) -> crate::pixel::Rgba {
+        crate::pixel::Rgba(p.0.map(|v| v as f32 / 255.0))
+    }
+}
+
 impl Into<image::Rgba<u8>> for crate::pixel::PremultipliedRgba {
     #[inline]
     fn into(self) -> image::Rgba<u8> {
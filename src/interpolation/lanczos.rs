@@ -0,0 +1,44 @@
+use super::{convolve, Interpolation};
+use crate::buffer::Buffer;
+use crate::pixel::{Arithmetic, Pixel};
+use crate::point::Point;
+use std::f32::consts::PI;
+
+/// Lanczos interpolation with 3 lobes.
+#[derive(Debug, Clone)]
+pub struct Lanczos3;
+
+impl Lanczos3 {
+    fn weights(t: f32) -> [f32; 6] {
+        let mut ws = [0.0; 6];
+        for (i, w) in ws.iter_mut().enumerate() {
+            *w = lanczos(t + 2.0 - i as f32, 3.0);
+        }
+        let sum: f32 = ws.iter().sum();
+        ws.map(|w| w / sum)
+    }
+}
+
+impl<P: Pixel + Arithmetic, B: Buffer<P>> Interpolation<P, B> for Lanczos3 {
+    fn interpolate(&self, buffer: &B, pos: Point) -> P {
+        let (x, y) = (pos.x().floor(), pos.y().floor());
+        convolve(
+            buffer,
+            x as i32 - 2,
+            y as i32 - 2,
+            &Lanczos3::weights(pos.x() - x),
+            &Lanczos3::weights(pos.y() - y),
+        )
+    }
+}
+
+fn lanczos(x: f32, a: f32) -> f32 {
+    if x == 0.0 {
+        1.0
+    } else if x.abs() < a {
+        let px = PI * x;
+        a * px.sin() * (px / a).sin() / (px * px)
+    } else {
+        0.0
+    }
+}
@@ -0,0 +1,153 @@
+use super::{Bilinear, Interpolation};
+use crate::buffer::{Buffer, GenericBuffer};
+use crate::matrix::Matrix;
+use crate::pixel::{Arithmetic, Pixel};
+use crate::point::Point;
+
+/// Mipmapped interpolation.
+///
+/// Keeps the area-averaged reductions of an image and samples the level that suits the scale factor.
+/// Levels are blended linearly, so it avoids aliasing on downscaled images.
+///
+/// A [`Mipmap`] must be used with the buffer it was created from. The reductions are sampled
+/// instead of the buffer passed to [`Interpolation::interpolate`] except at the finest level.
+#[derive(Clone)]
+pub struct Mipmap<P: Pixel + Arithmetic> {
+    /// Reductions of the image; `levels[i]` is `2^(i+1)` times smaller than the original.
+    levels: Vec<GenericBuffer<P>>,
+    level: f32,
+    dimensions: (u32, u32),
+}
+
+impl<P: Pixel + Arithmetic> Mipmap<P> {
+    /// Create [`Mipmap`] for drawing `buffer` at `scale` times the original size.
+    pub fn new<B: Buffer<P>>(buffer: &B, scale: f32) -> Self {
+        let level = (-scale.log2()).max(0.0);
+        let mut levels: Vec<GenericBuffer<P>> = Vec::new();
+        for _ in 0..level.ceil() as usize {
+            let next = match levels.last() {
+                Some(last) => reduce(last),
+                None => reduce(buffer),
+            };
+            let stop = next.dimensions() == (1, 1);
+            levels.push(next);
+            if stop {
+                break;
+            }
+        }
+        Mipmap {
+            level: level.min(levels.len() as f32),
+            levels,
+            dimensions: buffer.dimensions(),
+        }
+    }
+
+    /// Create [`Mipmap`] for drawing `buffer` transformed with `matrix`.
+    ///
+    /// `matrix` maps the image coordinates to the destination coordinates.
+    pub fn from_matrix<B: Buffer<P>>(buffer: &B, matrix: &Matrix) -> Self {
+        let m = &matrix.0;
+        let scale = (m[0] * m[4] - m[1] * m[3]).abs().sqrt();
+        Mipmap::new(buffer, scale)
+    }
+
+    fn sample(&self, level: usize, pos: Point) -> Option<P> {
+        let scale = (1 << level) as f32;
+        let pos = (pos + Point::from((0.5, 0.5))) / scale - Point::from((0.5, 0.5));
+        self.levels
+            .get(level.wrapping_sub(1))
+            .map(|buffer| Bilinear.interpolate(buffer, pos))
+    }
+}
+
+impl<P: Pixel + Arithmetic, B: Buffer<P>> Interpolation<P, B> for Mipmap<P> {
+    fn interpolate(&self, buffer: &B, pos: Point) -> P {
+        debug_assert_eq!(
+            buffer.dimensions(),
+            self.dimensions,
+            "Mipmap is used with a buffer other than the source"
+        );
+        let lower = self.level.floor() as usize;
+        let p = self
+            .sample(lower, pos)
+            .unwrap_or_else(|| Bilinear.interpolate(buffer, pos));
+        let rate = self.level.fract();
+        match self.sample(lower + 1, pos) {
+            Some(q) if rate != 0.0 => p.lerp(&q, rate),
+            _ => p,
+        }
+    }
+}
+
+/// Halve the buffer by averaging 2x2 pixels.
+fn reduce<P: Pixel + Arithmetic, B: Buffer<P>>(buffer: &B) -> GenericBuffer<P> {
+    let (width, height) = buffer.dimensions();
+    let (w, h) = (width.div_ceil(2), height.div_ceil(2));
+    let mut reduced = GenericBuffer::from_pixel(w, h, P::zero());
+    for y in 0..h {
+        let (y1, y2) = (y * 2, (y * 2 + 1).min(height - 1));
+        for x in 0..w {
+            let (x1, x2) = (x * 2, (x * 2 + 1).min(width - 1));
+            let p = buffer.get_pixel(x1, y1).clone()
+                + buffer.get_pixel(x2, y1).clone()
+                + buffer.get_pixel(x1, y2).clone()
+                + buffer.get_pixel(x2, y2).clone();
+            reduced.put_pixel(x, y, p * 0.25);
+        }
+    }
+    reduced
+}
+
+#[test]
+fn test() {
+    use crate::compositor::Src;
+    use crate::contrib::draw_image_transformed::draw_image_transformed;
+    use crate::pixel::Rgba;
+
+    let mut image = GenericBuffer::from_pixel(16, 16, Rgba([0.0, 0.0, 0.0, 1.0]));
+    for y in 0..16 {
+        for x in 0..16 {
+            if (x + y) % 2 == 0 {
+                image.put_pixel(x, y, Rgba([1.0, 1.0, 1.0, 1.0]));
+            }
+        }
+    }
+
+    // Each level averages 2x2 pixels of the previous one.
+    let mipmap = Mipmap::new(&image, 0.25);
+    assert_eq!(mipmap.levels.len(), 2);
+    assert_eq!(mipmap.levels[0].dimensions(), (8, 8));
+    assert_eq!(mipmap.levels[1].dimensions(), (4, 4));
+    assert_eq!(mipmap.level, 2.0);
+
+    // Minifying the checkerboard gives a flat gray instead of aliased black or white pixels.
+    let matrix = Matrix::new().scale(0.25, 0.25);
+    let mut dst = GenericBuffer::from_pixel(4, 4, Rgba([0.0; 4]));
+    draw_image_transformed(
+        &mut dst,
+        &image,
+        [0.0, 0.0, 16.0, 16.0],
+        matrix,
+        &Src,
+        Mipmap::from_matrix(&image, &matrix),
+    );
+    for y in 0..4 {
+        for x in 0..4 {
+            let p = dst.get_pixel(x, y).0;
+            assert!(
+                (p[0] - 0.5).abs() < 1e-4 && (p[3] - 1.0).abs() < 1e-4,
+                "{x} {y} {p:?}"
+            );
+        }
+    }
+
+    // At the original size it falls back to bilinear sampling of the buffer.
+    let mipmap = Mipmap::new(&image, 1.0);
+    assert!(mipmap.levels.is_empty());
+    for pos in [(3.0, 5.0), (2.5, 7.25)] {
+        assert_eq!(
+            mipmap.interpolate(&image, Point::from(pos)).0,
+            Bilinear.interpolate(&image, Point::from(pos)).0
+        );
+    }
+}
@@ -1,13 +1,24 @@
 //! Collection of interpolation types.
 //!
-//! Currently, 2 types of interpolation are available.
-//! - NearestNeighbor
-//! - Bilinear
+//! Currently, 5 types of interpolation are available.
+//! - [`NearestNeighbor`]
+//! - [`Bilinear`]
+//! - [`Bicubic`]
+//! - [`Lanczos3`]
+//! - [`Mipmap`]
+
+mod bicubic;
+mod lanczos;
+mod mipmap;
 
 use crate::buffer::Buffer;
-use crate::pixel::Pixel;
+use crate::pixel::{Arithmetic, Pixel};
 use crate::point::Point;
 
+pub use bicubic::Bicubic;
+pub use lanczos::Lanczos3;
+pub use mipmap::Mipmap;
+
 pub trait Interpolation<P: Pixel, B: Buffer<P>> {
     fn interpolate(&self, buffer: &B, pos: Point) -> P;
 }
@@ -56,3 +67,68 @@ impl<P: Pixel, B: Buffer<P>> Interpolation<P, B> for Bilinear {
             )
     }
 }
+
+/// Get the pixel at (`x`, `y`) wrapping around the edges of the buffer.
+#[inline]
+fn wrapped_pixel<P: Pixel, B: Buffer<P>>(buffer: &B, x: i32, y: i32) -> &P {
+    let (width, height) = buffer.dimensions();
+    buffer.get_pixel(
+        x.rem_euclid(width as i32) as u32,
+        y.rem_euclid(height as i32) as u32,
+    )
+}
+
+/// Sum up the `N`x`N` pixels starting at (`x`, `y`) with separable weights.
+fn convolve<P, B, const N: usize>(buffer: &B, x: i32, y: i32, wx: &[f32; N], wy: &[f32; N]) -> P
+where
+    P: Pixel + Arithmetic,
+    B: Buffer<P>,
+{
+    let mut acc = P::zero();
+    for (j, wy) in wy.iter().enumerate() {
+        let mut row = P::zero();
+        for (i, wx) in wx.iter().enumerate() {
+            row = row + wrapped_pixel(buffer, x + i as i32, y + j as i32).clone() * *wx;
+        }
+        acc = acc + row * *wy;
+    }
+    acc
+}
+
+#[test]
+fn test() {
+    use crate::buffer::GenericBuffer;
+    use crate::pixel::Rgba;
+
+    let mut buffer = GenericBuffer::from_pixel(8, 8, Rgba([0.0, 0.0, 0.0, 1.0]));
+    buffer.put_pixel(3, 4, Rgba([1.0, 0.5, 0.25, 1.0]));
+
+    let check = |p: Rgba, q: [f32; 4]| {
+        assert!(p
+            .0
+            .iter()
+            .zip(q.iter())
+            .all(|(a, b)| (a - b).abs() < 0.0001));
+    };
+    // Every interpolation must pass through the sample points.
+    let pos = Point::from((3.0, 4.0));
+    check(
+        Bicubic::catmull_rom().interpolate(&buffer, pos),
+        [1.0, 0.5, 0.25, 1.0],
+    );
+    check(
+        Bicubic::mitchell().interpolate(&buffer, Point::from((6.0, 1.0))),
+        [0.0, 0.0, 0.0, 1.0],
+    );
+    check(Lanczos3.interpolate(&buffer, pos), [1.0, 0.5, 0.25, 1.0]);
+    check(
+        Mipmap::new(&buffer, 1.0).interpolate(&buffer, pos),
+        [1.0, 0.5, 0.25, 1.0],
+    );
+
+    // A half scale mipmap averages 2x2 pixels.
+    check(
+        Mipmap::new(&buffer, 0.5).interpolate(&buffer, Point::from((2.5, 4.5))),
+        [0.25, 0.125, 0.0625, 1.0],
+    );
+}
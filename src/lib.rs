@@ -7,6 +7,7 @@
 //! - 24 composition types
 //! - anti-aliasing (can be disabled)
 //! - path transformation: translation, scaling and rotation
+//! - text rendering with TrueType/OpenType fonts (`text` feature)
 //!
 //! ## Example
 //! Basic usage example is following:
@@ -64,27 +65,38 @@
 pub extern crate image;
 
 pub mod buffer;
+pub mod color_adjust;
 pub mod compositor;
 pub mod context;
 pub mod contrib;
 pub mod draw_image;
 pub mod fill_color;
 pub mod fill_rule;
+pub mod filter;
+pub mod homography;
 #[cfg(feature = "image-crate")]
 pub mod image_crate_adapter;
 pub mod interpolation;
+pub mod mask;
 pub mod matrix;
-pub(crate) mod models;
+pub mod models;
 pub mod path;
 pub mod path_builder;
 #[cfg(feature = "path-data-notation")]
 pub mod path_data_notation;
+pub mod path_effect;
 pub mod path_flatten;
+pub mod path_hit_test;
+pub mod path_measure;
 pub mod path_outline;
 pub mod path_segments;
+pub mod path_simplify;
 pub mod path_transform;
 pub mod pixel;
 pub mod point;
 pub mod primitives;
 pub mod rasterize;
+pub mod shadow;
+#[cfg(feature = "text")]
+pub mod text;
 pub mod writer;
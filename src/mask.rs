@@ -0,0 +1,159 @@
+//! [`Mask`] scales the coverage of drawing per pixel.
+//!
+//! # Examples
+//!
+//! ```
+//! use nanachi::{
+//!     buffer::Buffer, compositor, context::{Context, FillStyle}, fill_color, fill_rule::NonZero,
+//!     mask::Mask, pixel::Rgba, primitives,
+//! };
+//! use std::sync::Arc;
+//!
+//! let mut context = Context::from_pixel(100, 100, Rgba([1.0, 1.0, 1.0, 1.0]));
+//! let mask = Mask::from_path(100, 100, &primitives::circle(50.0, 50.0, 40.0), NonZero, 0.1);
+//! context.mask = Some(Arc::new(mask));
+//! let fill_style = FillStyle::new(
+//!     fill_color::Solid::new(Rgba([0.0, 0.0, 0.0, 1.0])),
+//!     compositor::SrcOver,
+//!     NonZero,
+//! );
+//! context.fill(&primitives::rect(0.0, 0.0, 100.0, 100.0), &fill_style);
+//! assert_eq!(context.image.get_pixel(50, 50).0, [0.0, 0.0, 0.0, 1.0]);
+//! assert_eq!(context.image.get_pixel(5, 5).0, [1.0, 1.0, 1.0, 1.0]);
+//! ```
+
+use crate::buffer::{Buffer, GenericBuffer};
+use crate::fill_rule::FillRule;
+use crate::path::Path;
+use crate::path_flatten::Flatten;
+use crate::path_segments::Segments;
+use crate::pixel::{Pixel, Rgba};
+use crate::rasterize::RasterizeBuffer;
+
+/// Per pixel coverage from 0 to 1. Pixels outside of the mask are masked out.
+#[derive(Clone)]
+pub struct Mask(pub GenericBuffer<f32>);
+
+impl Mask {
+    /// Create a mask filled with `value`.
+    pub fn new(width: u32, height: u32, value: f32) -> Mask {
+        Mask(GenericBuffer::from_pixel(width, height, value))
+    }
+
+    /// Create a mask from the anti-aliased coverage of the path.
+    pub fn from_path(
+        width: u32,
+        height: u32,
+        path: &Path,
+        fill_rule: impl FillRule,
+        flatten_tolerance: f32,
+    ) -> Mask {
+        let mut mask = GenericBuffer::from_pixel(width, height, 0.0);
+        let mut rasterizer = RasterizeBuffer::new(width, height);
+        let segments = Segments::new(Flatten::new(path.0.iter(), flatten_tolerance));
+        rasterizer.rasterize(
+            segments,
+            fill_rule,
+            &mut |x, y, v| mask.put_pixel(x, y, v),
+            false,
+        );
+        Mask(mask)
+    }
+
+    /// Create a mask with `f` applied to each pixel of the buffer.
+    pub fn from_fn<P: Pixel, B: Buffer<P>>(buffer: &B, f: impl Fn(&P) -> f32) -> Mask {
+        let (width, height) = buffer.dimensions();
+        let mut mask = GenericBuffer::from_pixel(width, height, 0.0);
+        for y in 0..height {
+            for x in 0..width {
+                mask.put_pixel(x, y, f(buffer.get_pixel(x, y)).clamp(0.0, 1.0));
+            }
+        }
+        Mask(mask)
+    }
+
+    /// Create a mask from the alpha channel of the buffer.
+    pub fn from_alpha<P: Pixel + Into<Rgba>, B: Buffer<P>>(buffer: &B) -> Mask {
+        Mask::from_fn(buffer, |p| p.clone().into().0[3])
+    }
+
+    /// Create a mask from the luminance of the buffer multiplied by the alpha like CSS `mask-mode: luminance`.
+    pub fn from_luminance<P: Pixel + Into<Rgba>, B: Buffer<P>>(buffer: &B) -> Mask {
+        Mask::from_fn(buffer, |p| {
+            let [r, g, b, a] = p.clone().into().0;
+            (0.2126 * r + 0.7152 * g + 0.0722 * b) * a
+        })
+    }
+
+    /// Coverage at (`x`, `y`). It is 0 outside of the mask.
+    pub fn get(&self, x: u32, y: u32) -> f32 {
+        let (width, height) = self.0.dimensions();
+        if x < width && y < height {
+            *self.0.get_pixel(x, y)
+        } else {
+            0.0
+        }
+    }
+
+    /// Invert the coverage.
+    pub fn invert(&mut self) {
+        for v in self.0.buffer.iter_mut() {
+            *v = 1.0 - *v;
+        }
+    }
+
+    /// Multiply the coverage of `other` to intersect masks.
+    pub fn intersect(&mut self, other: &Mask) {
+        let (width, height) = self.0.dimensions();
+        for y in 0..height {
+            for x in 0..width {
+                let v = self.0.get_pixel(x, y) * other.get(x, y);
+                self.0.put_pixel(x, y, v);
+            }
+        }
+    }
+}
+
+#[test]
+fn test() {
+    use crate::compositor::SrcOver;
+    use crate::context::{Context, FillStyle};
+    use crate::fill_color::Solid;
+    use crate::fill_rule::NonZero;
+    use crate::primitives;
+    use std::sync::Arc;
+
+    let mut mask = Mask::from_path(10, 10, &primitives::rect(2.0, 0.0, 4.0, 10.0), NonZero, 0.1);
+    assert_eq!(
+        (mask.get(1, 5), mask.get(3, 5), mask.get(6, 5)),
+        (0.0, 1.0, 0.0)
+    );
+    assert_eq!(mask.get(3, 10), 0.0);
+
+    let mut image = GenericBuffer::from_pixel(2, 1, Rgba([1.0, 1.0, 1.0, 0.5]));
+    image.put_pixel(1, 0, Rgba([1.0, 0.0, 0.0, 1.0]));
+    let alpha = Mask::from_alpha(&image);
+    assert_eq!((alpha.get(0, 0), alpha.get(1, 0)), (0.5, 1.0));
+    let luminance = Mask::from_luminance(&image);
+    assert!((luminance.get(0, 0) - 0.5).abs() < 1e-5);
+    assert!((luminance.get(1, 0) - 0.2126).abs() < 1e-5);
+
+    let mut other = Mask::new(10, 10, 0.5);
+    other.invert();
+    other.0.put_pixel(3, 5, 0.0);
+    mask.intersect(&other);
+    assert_eq!(
+        (mask.get(3, 4), mask.get(3, 5), mask.get(1, 4)),
+        (0.5, 0.0, 0.0)
+    );
+
+    // Masked out pixels are untouched and others are drawn with the coverage.
+    let white = Rgba([1.0, 1.0, 1.0, 1.0]);
+    let mut context = Context::from_pixel(10, 10, white);
+    context.mask = Some(Arc::new(mask));
+    let fill_style = FillStyle::new(Solid::new(Rgba([0.0, 0.0, 0.0, 1.0])), SrcOver, NonZero);
+    context.fill(&primitives::rect(0.0, 0.0, 10.0, 10.0), &fill_style);
+    assert_eq!(context.image.get_pixel(1, 4).0, white.0);
+    assert_eq!(context.image.get_pixel(3, 5).0, white.0);
+    assert_eq!(context.image.get_pixel(3, 4).0, [0.5, 0.5, 0.5, 1.0]);
+}
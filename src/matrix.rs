@@ -1,6 +1,10 @@
 //! Affine transformation
 
 use crate::point::Point;
+use std::f32::consts::{PI, TAU};
+
+#[cfg(feature = "path-data-notation")]
+pub use crate::path_data_notation::parse_transform;
 
 /// Matrix for affine transformation.
 #[derive(Debug, Copy, Clone, PartialEq)]
@@ -75,6 +79,32 @@ impl Matrix {
         .into()
     }
 
+    /// Create a Matrix that maps the vertices of `src` triangle to the vertices of `dst` triangle in order.
+    ///
+    /// Returns `None` if `src` is degenerate.
+    pub fn from_triangle_to_triangle(src: [Point; 3], dst: [Point; 3]) -> Option<Matrix> {
+        let basis = |t: [Point; 3]| {
+            let (u, v) = (t[1] - t[0], t[2] - t[0]);
+            Matrix([u.x(), v.x(), t[0].x(), u.y(), v.y(), t[0].y()])
+        };
+        Some(basis(src).try_inverse()?.then(&basis(dst)))
+    }
+
+    /// Return the determinant of the linear part.
+    pub fn determinant(&self) -> f32 {
+        self.0[0] * self.0[4] - self.0[1] * self.0[3]
+    }
+
+    /// Inverse the matrix. Returns `None` if the matrix is singular.
+    pub fn try_inverse(&self) -> Option<Matrix> {
+        let det = self.determinant();
+        if det == 0.0 || !det.is_finite() {
+            None
+        } else {
+            Some(self.inverse())
+        }
+    }
+
     /// Inverse the matrix
     /// Ideally, `matrix.inverse().inverse() == matrix`.
     pub fn inverse(&self) -> Matrix {
@@ -104,6 +134,41 @@ impl Matrix {
         ])
     }
 
+    /// Decompose the matrix into [`Decomposition`].
+    pub fn decompose(&self) -> Decomposition {
+        let s = &self.0;
+        let scale_x = s[0].hypot(s[3]);
+        let rotation = s[3].atan2(s[0]);
+        let (sin, cos) = rotation.sin_cos();
+        let b = cos * s[1] + sin * s[4];
+        let d = cos * s[4] - sin * s[1];
+        Decomposition {
+            translate: (s[2], s[5]),
+            rotation,
+            scale: (scale_x, d),
+            skew_x: if scale_x == 0.0 { 0.0 } else { b / scale_x },
+        }
+    }
+
+    /// Interpolate two matrices through their decompositions.
+    ///
+    /// The rotation goes the shorter way.
+    pub fn interpolate(a: &Matrix, b: &Matrix, t: f32) -> Matrix {
+        let (a, b) = (a.decompose(), b.decompose());
+        let lerp = |a: f32, b: f32| a + (b - a) * t;
+        let d_rotation = (b.rotation - a.rotation + PI).rem_euclid(TAU) - PI;
+        Decomposition {
+            translate: (
+                lerp(a.translate.0, b.translate.0),
+                lerp(a.translate.1, b.translate.1),
+            ),
+            rotation: a.rotation + d_rotation * t,
+            scale: (lerp(a.scale.0, b.scale.0), lerp(a.scale.1, b.scale.1)),
+            skew_x: lerp(a.skew_x, b.skew_x),
+        }
+        .to_matrix()
+    }
+
     /// Return whether it is unit matrix.
     pub fn is_unit(&self) -> bool {
         self == &Default::default()
@@ -122,6 +187,38 @@ impl Default for Matrix {
     }
 }
 
+/// Matrix product. `a * b` applies `b` first and then `a`, that is `b.then(&a)`.
+impl std::ops::Mul for Matrix {
+    type Output = Matrix;
+
+    fn mul(self, rhs: Matrix) -> Matrix {
+        rhs.then(&self)
+    }
+}
+
+/// Components of an affine transformation.
+///
+/// The matrix is composed of skewing, scaling, rotation and translation in this order:
+/// `Matrix::new().skew_x(skew_x).scale(scale.0, scale.1).rotate(rotation).translate(translate.0, translate.1)`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Decomposition {
+    pub translate: (f32, f32),
+    pub rotation: f32,
+    pub scale: (f32, f32),
+    pub skew_x: f32,
+}
+
+impl Decomposition {
+    /// Compose the [`Matrix`].
+    pub fn to_matrix(&self) -> Matrix {
+        Matrix::new()
+            .skew_x(self.skew_x)
+            .scale(self.scale.0, self.scale.1)
+            .rotate(self.rotation)
+            .translate(self.translate.0, self.translate.1)
+    }
+}
+
 #[test]
 fn test() {
     let am = Matrix::new()
@@ -168,4 +265,29 @@ fn test() {
             < 0.0001
     );
     assert_eq!(Matrix::new().apply((0.0f32, 0.0f32)), (0.0, 0.0));
+
+    let close = |a: Matrix, b: Matrix| {
+        a.0.iter()
+            .zip(b.0.iter())
+            .map(|(a, b)| (a - b).abs())
+            .sum::<f32>()
+            < 0.0001
+    };
+    let m = am.skew_x(0.3).rotate(2.5);
+    assert!(close(m.decompose().to_matrix(), m));
+    assert!(close(Matrix::interpolate(&am, &m, 0.0), am));
+    assert!(close(Matrix::interpolate(&am, &m, 1.0), m));
+    assert!(close(m * am, am.then(&m)));
+    assert_eq!(Matrix::new().scale(0.0, 1.0).try_inverse(), None);
+
+    let src = [
+        Point::from((0.0, 0.0)),
+        Point::from((1.0, 0.0)),
+        Point::from((0.0, 1.0)),
+    ];
+    let dst = src.map(|p| m.apply(p));
+    assert!(close(
+        Matrix::from_triangle_to_triangle(src, dst).unwrap(),
+        m
+    ));
 }
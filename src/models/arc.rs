@@ -1,6 +1,8 @@
+use super::{Ellipse, Segment};
 use crate::point::Point;
 use std::f32::consts::TAU;
 
+/// Circular arc from `angle1` to `angle2`. It is clockwise in the y-down coordinates if `angle1 < angle2`.
 #[derive(Debug, Clone)]
 pub struct Arc {
     pub center: Point,
@@ -10,6 +12,8 @@ pub struct Arc {
 }
 
 impl Arc {
+    /// Arc around `center` from `start` to `end` with increasing angles.
+    /// The radius is the distance from `center` to `start`.
     pub fn from_points(center: Point, start: Point, end: Point) -> Arc {
         let angle1 = (start - center).atan2().rem_euclid(TAU);
         let mut angle2 = (end - center).atan2().rem_euclid(TAU);
@@ -33,4 +37,103 @@ impl Arc {
         let a = a1.rem_euclid(TAU);
         [a, if a2 - a < 0.0 { a2 + TAU } else { a2 }]
     }
+
+    /// Position at the `angle`.
+    pub fn pos(&self, angle: f32) -> Point {
+        self.center + Point::from_angle(angle) * self.radius
+    }
+
+    /// Bounding box of the whole circle.
+    pub fn full_bound(&self) -> [f32; 4] {
+        let r = self.radius;
+        let c = self.center;
+        [c.x() - r, c.x() + r, c.y() - r, c.y() + r]
+    }
+
+    /// The angle at the parameter `t`.
+    pub fn angle_at(&self, t: f32) -> f32 {
+        self.angle1 + (self.angle2 - self.angle1) * t
+    }
+
+    pub fn to_ellipse(&self) -> Ellipse {
+        Ellipse {
+            center: self.center,
+            radius_x: self.radius,
+            radius_y: self.radius,
+            rotation: 0.0,
+            angle1: self.angle1,
+            angle2: self.angle2,
+        }
+    }
+}
+
+impl Segment for Arc {
+    fn eval(&self, t: f32) -> Point {
+        self.pos(self.angle_at(t))
+    }
+
+    fn derivative(&self, t: f32) -> Point {
+        let angle = self.angle_at(t);
+        Point::from((-angle.sin(), angle.cos())) * (self.radius * (self.angle2 - self.angle1))
+    }
+
+    fn split(&self, t: f32) -> (Arc, Arc) {
+        let angle = self.angle_at(t);
+        (
+            Arc {
+                angle2: angle,
+                ..self.clone()
+            },
+            Arc {
+                angle1: angle,
+                ..self.clone()
+            },
+        )
+    }
+
+    fn sub_segment(&self, t0: f32, t1: f32) -> Arc {
+        Arc {
+            angle1: self.angle_at(t0),
+            angle2: self.angle_at(t1),
+            ..self.clone()
+        }
+    }
+
+    fn bounds(&self) -> [f32; 4] {
+        self.to_ellipse().bounds()
+    }
+
+    fn length(&self, _tolerance: f32) -> f32 {
+        self.radius * (self.angle2 - self.angle1).abs()
+    }
+
+    fn nearest_point(&self, p: Point) -> (f32, Point) {
+        let sweep = self.angle2 - self.angle1;
+        let mut candidates = vec![0.0, 1.0];
+        if sweep != 0.0 && p != self.center {
+            // Parameter of the direction of `p` in the direction of the sweep.
+            let angle = ((p - self.center).atan2() - self.angle1) * sweep.signum();
+            let t = angle.rem_euclid(TAU) / sweep.abs();
+            if t <= 1.0 {
+                candidates.push(t);
+            }
+            if sweep.abs() > TAU {
+                candidates.push(t + TAU / sweep.abs());
+            }
+        }
+        candidates
+            .into_iter()
+            .filter(|t| (0.0..=1.0).contains(t))
+            .map(|t| (t, self.eval(t)))
+            .min_by(|a, b| (a.1 - p).norm().partial_cmp(&(b.1 - p).norm()).unwrap())
+            .unwrap()
+    }
+
+    fn reverse(&self) -> Arc {
+        Arc {
+            angle1: self.angle2,
+            angle2: self.angle1,
+            ..self.clone()
+        }
+    }
 }
@@ -1,5 +1,7 @@
+use super::{points_bound, Segment};
 use crate::point::Point;
 
+/// Cubic bezier curve.
 #[derive(Debug, Clone)]
 pub struct Cubic {
     pub start: Point,
@@ -7,3 +9,100 @@ pub struct Cubic {
     pub control1: Point,
     pub control2: Point,
 }
+
+impl Cubic {
+    pub fn pos(&self, t: f32) -> Point {
+        let it = 1.0 - t;
+        self.start * it.powi(3)
+            + self.control1 * (3.0 * it.powi(2) * t)
+            + self.control2 * (3.0 * it * t.powi(2))
+            + self.end * t.powi(3)
+    }
+
+    /// Parameters in `(0, 1)` where the derivative of the `axis` (0 for x, 1 for y) coordinate is zero.
+    pub fn extrema(&self, axis: usize) -> impl Iterator<Item = f32> {
+        let (p0, p1, p2, p3) = (
+            self.start.0[axis],
+            self.control1.0[axis],
+            self.control2.0[axis],
+            self.end.0[axis],
+        );
+        // Roots of the derivative divided by 3: `a t^2 + b t + c`.
+        let a = -p0 + 3.0 * p1 - 3.0 * p2 + p3;
+        let b = 2.0 * (p0 - 2.0 * p1 + p2);
+        let c = p1 - p0;
+        let mut roots = if a.abs() < 1e-6 {
+            [-c / b, f32::NAN]
+        } else {
+            let d = (b * b - 4.0 * a * c).sqrt();
+            [(-b + d) / (2.0 * a), (-b - d) / (2.0 * a)]
+        };
+        if roots[1] < roots[0] {
+            roots.swap(0, 1);
+        }
+        roots.into_iter().filter(|t| 0.0 < *t && *t < 1.0)
+    }
+
+    /// Bounding box of the end points and the control points.
+    pub fn control_bound(&self) -> [f32; 4] {
+        points_bound(&[self.start, self.control1, self.control2, self.end])
+    }
+}
+
+impl Segment for Cubic {
+    fn eval(&self, t: f32) -> Point {
+        self.pos(t)
+    }
+
+    fn derivative(&self, t: f32) -> Point {
+        let it = 1.0 - t;
+        ((self.control1 - self.start) * it.powi(2)
+            + (self.control2 - self.control1) * (2.0 * it * t)
+            + (self.end - self.control2) * t.powi(2))
+            * 3.0
+    }
+
+    fn split(&self, t: f32) -> (Cubic, Cubic) {
+        let p01 = self.start.lerp(self.control1, t);
+        let p12 = self.control1.lerp(self.control2, t);
+        let p23 = self.control2.lerp(self.end, t);
+        let p012 = p01.lerp(p12, t);
+        let p123 = p12.lerp(p23, t);
+        let middle = p012.lerp(p123, t);
+        (
+            Cubic {
+                start: self.start,
+                end: middle,
+                control1: p01,
+                control2: p012,
+            },
+            Cubic {
+                start: middle,
+                end: self.end,
+                control1: p123,
+                control2: p23,
+            },
+        )
+    }
+
+    fn bounds(&self) -> [f32; 4] {
+        let mut bound = points_bound(&[self.start, self.end]);
+        for i in 0..2 {
+            for t in self.extrema(i) {
+                let v = self.pos(t).0[i];
+                bound[i * 2] = bound[i * 2].min(v);
+                bound[i * 2 + 1] = bound[i * 2 + 1].max(v);
+            }
+        }
+        bound
+    }
+
+    fn reverse(&self) -> Cubic {
+        Cubic {
+            start: self.end,
+            end: self.start,
+            control1: self.control2,
+            control2: self.control1,
+        }
+    }
+}
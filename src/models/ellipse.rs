@@ -1,6 +1,8 @@
+use super::{points_bound, Segment};
 use crate::point::Point;
 use std::f32::consts::{FRAC_PI_2, PI, TAU};
 
+/// Elliptical arc from `angle1` to `angle2`. The ellipse is rotated by `rotation` around `center`.
 #[derive(Debug, Clone)]
 pub struct Ellipse {
     pub center: Point,
@@ -75,7 +77,8 @@ impl Ellipse {
         }
     }
 
-    pub fn bound(&self) -> [f32; 4] {
+    /// Bounding box of the whole ellipse as `[min_x, max_x, min_y, max_y]`.
+    pub fn full_bound(&self) -> [f32; 4] {
         let ux = self.radius_x * self.rotation.cos();
         let uy = self.radius_x * self.rotation.sin();
         let vx = self.radius_y * (self.rotation + FRAC_PI_2).cos();
@@ -90,12 +93,44 @@ impl Ellipse {
         ]
     }
 
+    /// Angles strictly between `angle1` and `angle2` where the derivative of the `axis` (0 for x, 1 for y)
+    /// coordinate is zero, in ascending order.
+    pub fn extrema(&self, axis: usize) -> Vec<f32> {
+        let (lo, hi) = if self.angle1 < self.angle2 {
+            (self.angle1, self.angle2)
+        } else {
+            (self.angle2, self.angle1)
+        };
+        let (sin, cos) = self.rotation.sin_cos();
+        // The angles repeat every PI.
+        let base = if axis == 0 {
+            (-self.radius_y * sin).atan2(self.radius_x * cos)
+        } else {
+            (self.radius_y * cos).atan2(self.radius_x * sin)
+        };
+        let mut angle = base + ((lo - base) / PI).floor() * PI;
+        let mut res = Vec::new();
+        while angle < hi {
+            if lo < angle {
+                res.push(angle);
+            }
+            angle += PI;
+        }
+        res
+    }
+
+    /// Position at the `angle`.
     pub fn pos(&self, angle: f32) -> Point {
         self.center
             + Point::from((self.radius_x * angle.cos(), self.radius_y * angle.sin()))
                 .rotate(self.rotation)
     }
 
+    /// The angle at the parameter `t`.
+    pub fn angle_at(&self, t: f32) -> f32 {
+        self.angle1 + (self.angle2 - self.angle1) * t
+    }
+
     pub fn angle_offset(&self) -> f32 {
         -((self.rotation - FRAC_PI_2).tan() * self.radius_y / self.radius_x).atan()
     }
@@ -110,3 +145,66 @@ impl Ellipse {
         [a, if a2 - a < 0.0 { a2 + TAU } else { a2 }]
     }
 }
+
+impl Segment for Ellipse {
+    fn eval(&self, t: f32) -> Point {
+        self.pos(self.angle_at(t))
+    }
+
+    fn derivative(&self, t: f32) -> Point {
+        let angle = self.angle_at(t);
+        Point::from((-self.radius_x * angle.sin(), self.radius_y * angle.cos()))
+            .rotate(self.rotation)
+            * (self.angle2 - self.angle1)
+    }
+
+    fn split(&self, t: f32) -> (Ellipse, Ellipse) {
+        let angle = self.angle_at(t);
+        (
+            Ellipse {
+                angle2: angle,
+                ..self.clone()
+            },
+            Ellipse {
+                angle1: angle,
+                ..self.clone()
+            },
+        )
+    }
+
+    fn sub_segment(&self, t0: f32, t1: f32) -> Ellipse {
+        Ellipse {
+            angle1: self.angle_at(t0),
+            angle2: self.angle_at(t1),
+            ..self.clone()
+        }
+    }
+
+    fn bounds(&self) -> [f32; 4] {
+        let (lo, hi) = if self.angle1 < self.angle2 {
+            (self.angle1, self.angle2)
+        } else {
+            (self.angle2, self.angle1)
+        };
+        if TAU <= hi - lo {
+            return self.full_bound();
+        }
+        let mut bound = points_bound(&[self.pos(lo), self.pos(hi)]);
+        for axis in 0..2 {
+            for angle in self.extrema(axis) {
+                let v = self.pos(angle).0[axis];
+                bound[axis * 2] = bound[axis * 2].min(v);
+                bound[axis * 2 + 1] = bound[axis * 2 + 1].max(v);
+            }
+        }
+        bound
+    }
+
+    fn reverse(&self) -> Ellipse {
+        Ellipse {
+            angle1: self.angle2,
+            angle2: self.angle1,
+            ..self.clone()
+        }
+    }
+}
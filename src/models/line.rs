@@ -1,4 +1,48 @@
+use super::{points_bound, Segment};
 use crate::point::Point;
 
+/// Line segment from `self.0[0]` to `self.0[1]`.
 #[derive(Debug, Clone)]
 pub struct Line(pub [Point; 2]);
+
+impl Segment for Line {
+    fn eval(&self, t: f32) -> Point {
+        self.0[0].lerp(self.0[1], t)
+    }
+
+    fn derivative(&self, _t: f32) -> Point {
+        self.0[1] - self.0[0]
+    }
+
+    fn split(&self, t: f32) -> (Line, Line) {
+        let p = self.eval(t);
+        (Line([self.0[0], p]), Line([p, self.0[1]]))
+    }
+
+    fn sub_segment(&self, t0: f32, t1: f32) -> Line {
+        Line([self.eval(t0), self.eval(t1)])
+    }
+
+    fn bounds(&self) -> [f32; 4] {
+        points_bound(&self.0)
+    }
+
+    fn length(&self, _tolerance: f32) -> f32 {
+        (self.0[1] - self.0[0]).norm()
+    }
+
+    fn nearest_point(&self, p: Point) -> (f32, Point) {
+        let v = self.0[1] - self.0[0];
+        let l = v.dot(&v);
+        let t = if l == 0.0 {
+            0.0
+        } else {
+            ((p - self.0[0]).dot(&v) / l).clamp(0.0, 1.0)
+        };
+        (t, self.eval(t))
+    }
+
+    fn reverse(&self) -> Line {
+        Line([self.0[1], self.0[0]])
+    }
+}
@@ -1,3 +1,24 @@
+//! Geometry models of path segments.
+//!
+//! Every segment type implements [`Segment`] whose parameter `t` goes from 0 at the start to 1 at the end.
+//! For [`Arc`] and [`Ellipse`], `t` is linear in the angle from `angle1` to `angle2`.
+//!
+//! # Examples
+//!
+//! ```
+//! use nanachi::{models::{Cubic, Segment}, point::Point};
+//! let cubic = Cubic {
+//!     start: Point([0.0, 0.0]),
+//!     end: Point([30.0, 0.0]),
+//!     control1: Point([10.0, 20.0]),
+//!     control2: Point([20.0, 20.0]),
+//! };
+//! let (left, right) = cubic.split(0.5);
+//! assert_eq!(left.end, right.start);
+//! let (t, _) = cubic.nearest_point(Point([15.0, 30.0]));
+//! assert!((t - 0.5).abs() < 1e-3);
+//! ```
+
 pub mod line;
 pub mod arc;
 pub mod ellipse;
@@ -9,3 +30,170 @@ pub use arc::Arc;
 pub use ellipse::Ellipse;
 pub use quad::Quad;
 pub use cubic::Cubic;
+
+use crate::point::Point;
+
+/// Common operations of the segment types.
+pub trait Segment: Sized {
+    /// Position at `t`.
+    fn eval(&self, t: f32) -> Point;
+
+    /// Derivative of [`Segment::eval`] by `t`.
+    fn derivative(&self, t: f32) -> Point;
+
+    /// Split into the parts `[0, t]` and `[t, 1]`.
+    fn split(&self, t: f32) -> (Self, Self);
+
+    /// The part from `t0` to `t1`. It is reversed if `t1 < t0`.
+    fn sub_segment(&self, t0: f32, t1: f32) -> Self {
+        if t1 < t0 {
+            return self.sub_segment(t1, t0).reverse();
+        }
+        let (head, _) = self.split(t1);
+        if t1 == 0.0 {
+            head
+        } else {
+            head.split(t0 / t1).1
+        }
+    }
+
+    /// Exact bounding box as `[min_x, max_x, min_y, max_y]`.
+    fn bounds(&self) -> [f32; 4];
+
+    /// Length of the segment. The error is about `tolerance` at most.
+    fn length(&self, tolerance: f32) -> f32 {
+        adaptive_length(self, tolerance, 0)
+    }
+
+    /// Parameter and position of the nearest point on the segment to `p`.
+    fn nearest_point(&self, p: Point) -> (f32, Point) {
+        const SAMPLES: usize = 32;
+        let distance = |t: f32| (self.eval(t) - p).norm();
+        let i = (0..=SAMPLES)
+            .min_by(|a, b| {
+                let d = |i: &usize| distance(*i as f32 / SAMPLES as f32);
+                d(a).partial_cmp(&d(b)).unwrap()
+            })
+            .unwrap();
+        // Golden section search around the nearest sample.
+        let r = (5.0f32.sqrt() - 1.0) / 2.0;
+        let mut lo = (i as f32 - 1.0).max(0.0) / SAMPLES as f32;
+        let mut hi = (i as f32 + 1.0).min(SAMPLES as f32) / SAMPLES as f32;
+        for _ in 0..32 {
+            let (m1, m2) = (hi - (hi - lo) * r, lo + (hi - lo) * r);
+            if distance(m1) < distance(m2) {
+                hi = m2;
+            } else {
+                lo = m1;
+            }
+        }
+        let t = (lo + hi) / 2.0;
+        (t, self.eval(t))
+    }
+
+    /// The segment in the opposite direction.
+    fn reverse(&self) -> Self;
+}
+
+/// Compare polylines of 2 and 4 pieces and subdivide until they agree.
+fn adaptive_length<S: Segment>(segment: &S, tolerance: f32, depth: u32) -> f32 {
+    let points: Vec<Point> = (0..=4).map(|i| segment.eval(i as f32 / 4.0)).collect();
+    let coarse = (points[2] - points[0]).norm() + (points[4] - points[2]).norm();
+    let fine: f32 = points.windows(2).map(|w| (w[1] - w[0]).norm()).sum();
+    if 16 <= depth || (1 <= depth && fine - coarse <= tolerance) {
+        // Richardson extrapolation of the polyline lengths.
+        fine + (fine - coarse) / 3.0
+    } else {
+        let (a, b) = segment.split(0.5);
+        adaptive_length(&a, tolerance / 2.0, depth + 1)
+            + adaptive_length(&b, tolerance / 2.0, depth + 1)
+    }
+}
+
+/// Bounding box of points as `[min_x, max_x, min_y, max_y]`.
+pub(crate) fn points_bound(points: &[Point]) -> [f32; 4] {
+    points.iter().fold(
+        [
+            f32::INFINITY,
+            f32::NEG_INFINITY,
+            f32::INFINITY,
+            f32::NEG_INFINITY,
+        ],
+        |b, p| {
+            [
+                b[0].min(p.x()),
+                b[1].max(p.x()),
+                b[2].min(p.y()),
+                b[3].max(p.y()),
+            ]
+        },
+    )
+}
+
+#[test]
+fn test() {
+    use std::f32::consts::PI;
+
+    fn check<S: Segment + Clone>(s: &S, length: f32) {
+        let close = |a: Point, b: Point| (a - b).norm() < 1e-3;
+        let (a, b) = s.split(0.3);
+        assert!(close(a.eval(1.0), s.eval(0.3)) && close(b.eval(0.0), s.eval(0.3)));
+        assert!(close(a.eval(0.5), s.eval(0.15)));
+        let sub = s.sub_segment(0.2, 0.6);
+        assert!(close(sub.eval(0.0), s.eval(0.2)) && close(sub.eval(1.0), s.eval(0.6)));
+        assert!(close(s.sub_segment(0.6, 0.2).eval(0.0), s.eval(0.6)));
+        assert!(close(s.reverse().eval(0.25), s.eval(0.75)));
+        assert!((s.length(1e-3) - length).abs() < 1e-2);
+        let d = (s.eval(0.5 + 1e-3) - s.eval(0.5 - 1e-3)) / 2e-3;
+        assert!(close(d / 100.0, s.derivative(0.5) / 100.0));
+        let (t, p) = s.nearest_point(s.eval(0.4) + s.derivative(0.4).rotate(PI / 2.0).unit() * 0.1);
+        assert!((t - 0.4).abs() < 1e-3 && close(p, s.eval(0.4)));
+        let b = s.bounds();
+        assert!((0..=10).all(|i| {
+            let p = s.eval(i as f32 / 10.0);
+            b[0] - 1e-3 <= p.x()
+                && p.x() <= b[1] + 1e-3
+                && b[2] - 1e-3 <= p.y()
+                && p.y() <= b[3] + 1e-3
+        }));
+    }
+
+    let p = |x: f32, y: f32| Point([x, y]);
+    check(&Line([p(0.0, 0.0), p(30.0, 40.0)]), 50.0);
+    let arc = Arc {
+        center: p(0.0, 0.0),
+        radius: 10.0,
+        angle1: PI,
+        angle2: 0.0,
+    };
+    check(&arc, 10.0 * PI);
+    let ellipse = arc.to_ellipse();
+    check(&ellipse, 10.0 * PI);
+    check(
+        &Quad {
+            start: p(0.0, 0.0),
+            end: p(20.0, 0.0),
+            control1: p(10.0, 0.0),
+        },
+        20.0,
+    );
+    check(
+        &Cubic {
+            start: p(0.0, 0.0),
+            end: p(30.0, 0.0),
+            control1: p(10.0, 0.0),
+            control2: p(20.0, 0.0),
+        },
+        30.0,
+    );
+    let cubic = Cubic {
+        start: p(0.0, 0.0),
+        end: p(30.0, 0.0),
+        control1: p(0.0, 30.0),
+        control2: p(30.0, 30.0),
+    };
+    let flattened: f32 = (0..10000)
+        .map(|i| (cubic.eval((i + 1) as f32 / 1e4) - cubic.eval(i as f32 / 1e4)).norm())
+        .sum();
+    check(&cubic, flattened);
+}
@@ -1,5 +1,7 @@
+use super::{points_bound, Segment};
 use crate::point::Point;
 
+/// Quadratic bezier curve.
 #[derive(Debug, Clone)]
 pub struct Quad {
     pub start: Point,
@@ -8,24 +10,17 @@ pub struct Quad {
 }
 
 impl Quad {
-    pub fn bound(&self) -> [f32; 4] {
-        let x_t = (self.end.y() - self.control1.y())
-            / (self.start.y() + self.end.y() - 2.0 * self.control1.y());
-        let y_t = (self.end.x() - self.control1.x())
-            / (self.start.x() + self.end.x() - 2.0 * self.control1.x());
-        let mut x_min = self.start.x().min(self.end.x());
-        let mut x_max = self.start.x().max(self.end.x());
-        if 0.0 <= y_t && y_t <= 1.0 {
-            x_min = x_min.min(self.pos(y_t).x());
-            x_max = x_max.max(self.pos(y_t).x());
-        }
-        let mut y_min = self.start.y().min(self.end.y());
-        let mut y_max = self.start.y().max(self.end.y());
-        if 0.0 <= x_t && x_t <= 1.0 {
-            y_min = y_min.min(self.pos(x_t).y());
-            y_max = y_max.max(self.pos(x_t).y());
-        }
-        [x_min, x_max, y_min, y_max]
+    /// Bounding box of the end points and the control point.
+    pub fn control_bound(&self) -> [f32; 4] {
+        points_bound(&[self.start, self.control1, self.end])
+    }
+
+    /// Parameter in `(0, 1)` where the derivative of the `axis` (0 for x, 1 for y) coordinate is zero.
+    pub fn extrema(&self, axis: usize) -> Option<f32> {
+        let (s, c, e) = (self.start.0[axis], self.control1.0[axis], self.end.0[axis]);
+        // The derivative `2 (1 - t) (c - s) + 2 t (e - c)` is zero at `t`.
+        let t = (s - c) / (s - 2.0 * c + e);
+        (0.0 < t && t < 1.0).then_some(t)
     }
 
     pub fn pos(&self, t: f32) -> Point {
@@ -33,6 +28,7 @@ impl Quad {
         self.start * it.powi(2) + self.control1 * t * it * 2.0 + self.end * t.powi(2)
     }
 
+    /// Same as [`Segment::split`].
     pub fn separate(&self, t: f32) -> (Quad, Quad) {
         debug_assert!(0.0 <= t && t <= 1.0);
         let middle = self.pos(t);
@@ -67,3 +63,42 @@ impl Quad {
         (q + s).cbrt() + (q - s).cbrt() + p
     }
 }
+
+impl Segment for Quad {
+    fn eval(&self, t: f32) -> Point {
+        self.pos(t)
+    }
+
+    fn derivative(&self, t: f32) -> Point {
+        ((self.control1 - self.start) * (1.0 - t) + (self.end - self.control1) * t) * 2.0
+    }
+
+    fn split(&self, t: f32) -> (Quad, Quad) {
+        self.separate(t)
+    }
+
+    fn bounds(&self) -> [f32; 4] {
+        let mut bound = [
+            self.start.x().min(self.end.x()),
+            self.start.x().max(self.end.x()),
+            self.start.y().min(self.end.y()),
+            self.start.y().max(self.end.y()),
+        ];
+        for i in 0..2 {
+            if let Some(t) = self.extrema(i) {
+                let v = self.pos(t).0[i];
+                bound[i * 2] = bound[i * 2].min(v);
+                bound[i * 2 + 1] = bound[i * 2 + 1].max(v);
+            }
+        }
+        bound
+    }
+
+    fn reverse(&self) -> Quad {
+        Quad {
+            start: self.end,
+            end: self.start,
+            control1: self.control1,
+        }
+    }
+}
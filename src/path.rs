@@ -1,7 +1,16 @@
 //! [`Path`] for filling and stroking.
 
-use crate::models::{Arc, Cubic, Ellipse, Line, Quad};
+use crate::fill_rule::FillRule;
+use crate::matrix::Matrix;
+use crate::models::{Arc, Cubic, Ellipse, Line, Quad, Segment};
+use crate::path_hit_test::{path_contains, path_stroke_contains};
+use crate::path_outline::{Cap, Join};
+use crate::path_simplify::{
+    catmull_rom, cubics_to_path, fit_cubic, simplify_rdp, simplify_visvalingam,
+};
+use crate::path_transform::path_transform;
 use crate::point::Point;
+use std::f32::consts::SQRT_2;
 
 #[derive(Debug, Clone)]
 pub enum PathItem {
@@ -17,32 +26,11 @@ pub enum PathItem {
 impl PathItem {
     pub fn flip(&self) -> PathItem {
         match self {
-            PathItem::Line(line) => PathItem::Line(Line([line.0[1], line.0[0]])),
-            PathItem::Arc(arc) => PathItem::Arc(Arc {
-                center: arc.center,
-                radius: arc.radius,
-                angle1: arc.angle2,
-                angle2: arc.angle1,
-            }),
-            PathItem::Ellipse(ellipse) => PathItem::Ellipse(Ellipse {
-                center: ellipse.center,
-                radius_x: ellipse.radius_x,
-                radius_y: ellipse.radius_y,
-                rotation: ellipse.rotation,
-                angle1: ellipse.angle2,
-                angle2: ellipse.angle1,
-            }),
-            PathItem::Quad(quad) => PathItem::Quad(Quad {
-                start: quad.end.clone(),
-                end: quad.start.clone(),
-                control1: quad.control1.clone(),
-            }),
-            PathItem::Cubic(cubic) => PathItem::Cubic(Cubic {
-                start: cubic.end.clone(),
-                end: cubic.start.clone(),
-                control1: cubic.control2.clone(),
-                control2: cubic.control1.clone(),
-            }),
+            PathItem::Line(line) => PathItem::Line(line.reverse()),
+            PathItem::Arc(arc) => PathItem::Arc(arc.reverse()),
+            PathItem::Ellipse(ellipse) => PathItem::Ellipse(ellipse.reverse()),
+            PathItem::Quad(quad) => PathItem::Quad(quad.reverse()),
+            PathItem::Cubic(cubic) => PathItem::Cubic(cubic.reverse()),
             PathItem::CloseAndJump => unreachable!(),
             PathItem::Jump => unreachable!(),
         }
@@ -107,6 +95,32 @@ impl PathItem {
         }
     }
 
+    /// Exact bounding box as `[min_x, max_x, min_y, max_y]`.
+    pub fn bound(&self) -> Option<[f32; 4]> {
+        match self {
+            PathItem::Line(line) => Some(line.bounds()),
+            PathItem::Arc(arc) => Some(arc.bounds()),
+            PathItem::Ellipse(ellipse) => Some(ellipse.bounds()),
+            PathItem::Quad(quad) => Some(quad.bounds()),
+            PathItem::Cubic(cubic) => Some(cubic.bounds()),
+            PathItem::CloseAndJump | PathItem::Jump => None,
+        }
+    }
+
+    /// Bounding box of the end points and the control points.
+    ///
+    /// It is cheaper than [`PathItem::bound`] and contains it. Arcs use the bounds of their whole circles.
+    pub fn control_bound(&self) -> Option<[f32; 4]> {
+        match self {
+            PathItem::Line(line) => Some(line.bounds()),
+            PathItem::Arc(arc) => Some(arc.full_bound()),
+            PathItem::Ellipse(ellipse) => Some(ellipse.full_bound()),
+            PathItem::Quad(quad) => Some(quad.control_bound()),
+            PathItem::Cubic(cubic) => Some(cubic.control_bound()),
+            PathItem::CloseAndJump | PathItem::Jump => None,
+        }
+    }
+
     pub fn is_jump(&self) -> bool {
         match self {
             PathItem::CloseAndJump | PathItem::Jump => true,
@@ -136,6 +150,50 @@ impl Path {
         self.0.extend_from_slice(rhs.0.as_slice());
     }
 
+    /// Exact bounding box of the path as `[min_x, max_x, min_y, max_y]`.
+    ///
+    /// Returns `None` if the path has no segments.
+    pub fn bounds(&self) -> Option<[f32; 4]> {
+        union_bounds(self.0.iter().filter_map(PathItem::bound))
+    }
+
+    /// Fast bounding box of the end points and the control points, which contains [`Path::bounds`].
+    pub fn control_bounds(&self) -> Option<[f32; 4]> {
+        union_bounds(self.0.iter().filter_map(PathItem::control_bound))
+    }
+
+    /// Exact bounding box of the path transformed with the [`Matrix`].
+    pub fn transformed_bounds(&self, matrix: &Matrix) -> Option<[f32; 4]> {
+        path_transform(self, matrix).bounds()
+    }
+
+    /// Bounding box of the stroke of the path.
+    ///
+    /// `width` is the full width of the stroke. The result may be slightly larger than the stroke
+    /// because joins and caps are accounted for at every point.
+    pub fn stroke_bounds(&self, width: f32, join: &Join, cap: &Cap) -> Option<[f32; 4]> {
+        let half = width.abs() / 2.0;
+        let mut margin = match cap {
+            Cap::Square => half * SQRT_2,
+            Cap::Round | Cap::Butt => half,
+        };
+        if let Join::Miter(limit) | Join::MiterClip(limit) | Join::Arcs(limit) = join {
+            margin = margin.max(limit * half);
+        }
+        self.bounds()
+            .map(|b| [b[0] - margin, b[1] + margin, b[2] - margin, b[3] + margin])
+    }
+
+    /// Whether the point is in the filled area of the path with the fill rule.
+    pub fn contains(&self, point: Point, fill_rule: impl FillRule) -> bool {
+        path_contains(self, point, fill_rule)
+    }
+
+    /// Whether the point is on the stroke of the path. `width` is the full width of the stroke.
+    pub fn stroke_contains(&self, point: Point, width: f32, join: &Join, cap: &Cap) -> bool {
+        path_stroke_contains(self, point, width, join, cap)
+    }
+
     /// Create [`Path`] from `Vec<Point>`.
     pub fn from_points(points: &Vec<Point>, close: bool) -> Path {
         let mut pis = Vec::new();
@@ -161,6 +219,68 @@ impl Path {
         Path(pis)
     }
 
+    /// Create [`Path`] as a Catmull-Rom spline through `points`. See [`catmull_rom`] for `alpha`.
+    pub fn from_catmull_rom(points: &[Point], close: bool, alpha: f32) -> Path {
+        cubics_to_path(catmull_rom(points, close, alpha), close)
+    }
+
+    /// Simplify runs of lines with the Ramer–Douglas–Peucker algorithm. Curves are kept as they are.
+    pub fn simplify(&self, tolerance: f32) -> Path {
+        self.map_line_runs(|points, _| lines(&simplify_rdp(points, tolerance)))
+    }
+
+    /// Simplify runs of lines with the Visvalingam–Whyatt algorithm. Curves are kept as they are.
+    pub fn simplify_visvalingam(&self, min_area: f32) -> Path {
+        self.map_line_runs(|points, _| lines(&simplify_visvalingam(points, min_area)))
+    }
+
+    /// Replace runs of lines with cubic Bézier curves which are within `tolerance` from the points.
+    pub fn fit_curves(&self, tolerance: f32) -> Path {
+        self.map_line_runs(|points, close| {
+            fit_cubic(points, tolerance, close)
+                .into_iter()
+                .map(PathItem::Cubic)
+                .collect()
+        })
+    }
+
+    /// Replace each run of connected lines with the items returned by `f`.
+    ///
+    /// The second argument of `f` is `true` if the run is a whole closed subpath.
+    pub(crate) fn map_line_runs(&self, f: impl Fn(&[Point], bool) -> Vec<PathItem>) -> Path {
+        let mut res = Vec::with_capacity(self.0.len());
+        let continuations = self.continuations();
+        for (i, (pis, closed)) in continuations.iter().enumerate() {
+            let mut points: Vec<Point> = Vec::new();
+            let whole = pis.iter().all(|pi| matches!(pi, PathItem::Line(_)));
+            for pi in pis.iter() {
+                match pi {
+                    PathItem::Line(Line([a, b])) if points.last() == Some(a) => points.push(*b),
+                    _ => {
+                        if 2 <= points.len() {
+                            res.extend(f(&points, false));
+                        }
+                        points.clear();
+                        if let PathItem::Line(Line([a, b])) = pi {
+                            points.extend([*a, *b]);
+                        } else {
+                            res.push(pi.clone());
+                        }
+                    }
+                }
+            }
+            if 2 <= points.len() {
+                res.extend(f(&points, *closed && whole));
+            }
+            if *closed {
+                res.push(PathItem::CloseAndJump);
+            } else if i + 1 < continuations.len() {
+                res.push(PathItem::Jump);
+            }
+        }
+        Path(res)
+    }
+
     /// Get [`Point`]s lists from a [`Path`] composed only lines.
     pub fn as_points_list(&self) -> Option<Vec<Vec<Point>>> {
         let mut vec = Vec::new();
@@ -240,3 +360,66 @@ impl Path {
         )
     }
 }
+
+fn lines(points: &[Point]) -> Vec<PathItem> {
+    points
+        .windows(2)
+        .map(|w| PathItem::Line(Line([w[0], w[1]])))
+        .collect()
+}
+
+fn union_bounds(bounds: impl Iterator<Item = [f32; 4]>) -> Option<[f32; 4]> {
+    bounds.reduce(|a, b| {
+        [
+            a[0].min(b[0]),
+            a[1].max(b[1]),
+            a[2].min(b[2]),
+            a[3].max(b[3]),
+        ]
+    })
+}
+
+#[test]
+fn test() {
+    use crate::{path_builder::PathBuilder, primitives};
+    use std::f32::consts::{FRAC_PI_2, PI};
+
+    let close = |a: [f32; 4], b: [f32; 4]| a.iter().zip(b).all(|(a, b)| (a - b).abs() < 1e-3);
+
+    let path = primitives::circle(10.0, 20.0, 5.0);
+    assert!(close(path.bounds().unwrap(), [5.0, 15.0, 15.0, 25.0]));
+
+    let mut pb = PathBuilder::new();
+    pb.arc(0.0, 0.0, 1.0, -FRAC_PI_2, PI);
+    let path = pb.end();
+    assert!(close(path.bounds().unwrap(), [-1.0, 1.0, -1.0, 1.0]));
+    let mut pb = PathBuilder::new();
+    pb.arc(0.0, 0.0, 1.0, 0.1, FRAC_PI_2);
+    assert!(close(
+        pb.end().bounds().unwrap(),
+        [0.0, 0.1f32.cos(), 0.1f32.sin(), 1.0]
+    ));
+
+    let mut pb = PathBuilder::new();
+    pb.move_to(0.0, 0.0);
+    pb.cubic(0.0, 10.0, 10.0, 10.0, 10.0, 0.0);
+    pb.quad(15.0, -10.0, 20.0, 0.0);
+    let path = pb.end();
+    assert!(close(path.bounds().unwrap(), [0.0, 20.0, -5.0, 7.5]));
+    assert!(close(
+        path.control_bounds().unwrap(),
+        [0.0, 20.0, -10.0, 10.0]
+    ));
+
+    let matrix = Matrix::new().rotate(FRAC_PI_2);
+    assert!(close(
+        path.transformed_bounds(&matrix).unwrap(),
+        [-7.5, 5.0, 0.0, 20.0]
+    ));
+    assert!(close(
+        path.stroke_bounds(2.0, &Join::Round, &Cap::Square).unwrap(),
+        [-SQRT_2, 20.0 + SQRT_2, -5.0 - SQRT_2, 7.5 + SQRT_2]
+    ));
+
+    assert!(Path::new(Vec::new()).bounds().is_none());
+}
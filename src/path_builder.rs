@@ -68,11 +68,14 @@ impl PathBuilder {
             angle1,
             angle2,
         });
-        if let Some(last_pos) = self.last_pos {
-            let left_point = arc.left_point();
-            if last_pos != left_point {
+        let left_point = arc.left_point();
+        match self.last_pos {
+            Some(last_pos) if last_pos != left_point => {
                 self.push(PathItem::Line(Line([last_pos, left_point])));
             }
+            Some(_) => {}
+            // The path starts at the start of the arc.
+            None => self.set_pos(left_point),
         }
         self.set_pos(arc.right_point());
         self.push(arc);
@@ -100,11 +103,14 @@ impl PathBuilder {
             angle1,
             angle2,
         });
-        if let Some(last_pos) = self.last_pos {
-            let left_point = ellipse.left_point();
-            if last_pos != left_point {
+        let left_point = ellipse.left_point();
+        match self.last_pos {
+            Some(last_pos) if last_pos != left_point => {
                 self.push(PathItem::Line(Line([last_pos, left_point])));
             }
+            Some(_) => {}
+            // The path starts at the start of the ellipse.
+            None => self.set_pos(left_point),
         }
         self.set_pos(ellipse.right_point());
         self.push(ellipse);
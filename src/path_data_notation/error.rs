@@ -0,0 +1,65 @@
+use std::fmt;
+
+/// Kind of [`ParseError`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    /// A character that does not fit the grammar.
+    UnexpectedChar,
+    /// The input ended in the middle of a command.
+    UnexpectedEnd,
+    /// A number that cannot be represented.
+    InvalidNumber,
+    /// The path data does not start with a moveto command.
+    MissingMoveTo,
+    /// Unknown transform function or wrong number of its arguments.
+    InvalidTransform,
+}
+
+/// What the parser expected at the error position.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Expected {
+    Command,
+    Number,
+    Flag,
+    /// A name of transform function.
+    Transform,
+    Char(char),
+}
+
+/// Error of path data and transform parsing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub kind: ErrorKind,
+    /// Byte offset in the source.
+    pub offset: usize,
+    pub expected: Option<Expected>,
+    /// Found character; `None` means the end of the input.
+    pub found: Option<char>,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            ErrorKind::UnexpectedChar => write!(f, "unexpected character")?,
+            ErrorKind::UnexpectedEnd => write!(f, "unexpected end of path data")?,
+            ErrorKind::InvalidNumber => write!(f, "invalid number")?,
+            ErrorKind::MissingMoveTo => write!(f, "path data must start with a moveto")?,
+            ErrorKind::InvalidTransform => write!(f, "invalid transform function")?,
+        }
+        if let Some(expected) = self.expected {
+            match expected {
+                Expected::Command => write!(f, ", expected command")?,
+                Expected::Number => write!(f, ", expected number")?,
+                Expected::Flag => write!(f, ", expected flag")?,
+                Expected::Transform => write!(f, ", expected transform function")?,
+                Expected::Char(c) => write!(f, ", expected {:?}", c)?,
+            }
+        }
+        if let Some(c) = self.found {
+            write!(f, ", found {:?}", c)?;
+        }
+        write!(f, " at {}", self.offset)
+    }
+}
+
+impl std::error::Error for ParseError {}
@@ -1,10 +1,16 @@
+//! Parser of [SVG path data](https://www.w3.org/TR/SVG2/paths.html#PathData) and SVG `transform` attribute.
+
+mod error;
 mod tokenize;
+mod transform;
 
 use crate::path::Path;
 use crate::path_builder::PathBuilder;
 use crate::point::Point;
-use std::{iter::Peekable, str::Chars};
-use tokenize::{Token, Tokenize};
+use tokenize::Tokenize;
+
+pub use error::{ErrorKind, Expected, ParseError};
+pub use transform::parse_transform;
 
 enum LastControlPoint {
     Quad(Point),
@@ -13,343 +19,253 @@ enum LastControlPoint {
 }
 
 /// Parse [SVG path notation](https://developer.mozilla.org/en-US/docs/Web/SVG/Tutorial/Paths).
-pub fn parse(str: &str) -> Result<Path, String> {
-    let mut chars = str.chars();
-    let mut tokens = Tokenize::new(&mut chars).peekable();
-    let mut pb = PathBuilder::new();
-    let mut last_control_point = LastControlPoint::None;
-    loop {
-        match tokens.next().unwrap()? {
-            Token::LargeM => {
-                let ns = parse_n_nums(&mut tokens, 2)?;
-                pb.move_to(ns[0], ns[1]);
-                while is_num_or_comma(&tokens.peek().unwrap().to_owned()?) {
-                    let ns = parse_n_nums(&mut tokens, 2)?;
-                    pb.line_to(ns[0], ns[1]);
-                }
-                last_control_point = LastControlPoint::None;
-            }
-            Token::SmallM => {
-                let ns = parse_n_nums(&mut tokens, 2)?;
-                let current = pb.current_pos().unwrap_or((0.0, 0.0));
-                pb.move_to(current.0 + ns[0], current.1 + ns[1]);
-                while is_num_or_comma(&tokens.peek().unwrap().to_owned()?) {
-                    let ns = parse_n_nums(&mut tokens, 2)?;
-                    let current = pb.current_pos().unwrap_or((0.0, 0.0));
-                    pb.line_to(current.0 + ns[0], current.1 + ns[1]);
-                }
-                last_control_point = LastControlPoint::None;
-            }
-            Token::LargeL => {
-                let ns = parse_n_nums(&mut tokens, 2)?;
-                pb.line_to(ns[0], ns[1]);
-                while is_num_or_comma(&tokens.peek().unwrap().to_owned()?) {
-                    let ns = parse_n_nums(&mut tokens, 2)?;
-                    pb.line_to(ns[0], ns[1]);
-                }
-                last_control_point = LastControlPoint::None;
-            }
-            Token::SmallL => {
-                let ns = parse_n_nums(&mut tokens, 2)?;
-                let current = pb.current_pos().unwrap_or((0.0, 0.0));
-                pb.line_to(current.0 + ns[0], current.1 + ns[1]);
-                while is_num_or_comma(&tokens.peek().unwrap().to_owned()?) {
-                    let ns = parse_n_nums(&mut tokens, 2)?;
-                    let current = pb.current_pos().unwrap_or((0.0, 0.0));
-                    pb.line_to(current.0 + ns[0], current.1 + ns[1]);
-                }
-                last_control_point = LastControlPoint::None;
-            }
-            Token::LargeH => {
-                let ns = parse_n_nums(&mut tokens, 1)?;
-                let current = pb.current_pos().unwrap_or((0.0, 0.0));
-                pb.line_to(ns[0], current.1);
-                while is_num_or_comma(&tokens.peek().unwrap().to_owned()?) {
-                    let ns = parse_n_nums(&mut tokens, 1)?;
-                    let current = pb.current_pos().unwrap_or((0.0, 0.0));
-                    pb.line_to(ns[0], current.1);
-                }
-                last_control_point = LastControlPoint::None;
-            }
-            Token::SmallH => {
-                let ns = parse_n_nums(&mut tokens, 1)?;
-                let current = pb.current_pos().unwrap_or((0.0, 0.0));
-                pb.line_to(current.0 + ns[0], current.1);
-                while is_num_or_comma(&tokens.peek().unwrap().to_owned()?) {
-                    let ns = parse_n_nums(&mut tokens, 1)?;
-                    let current = pb.current_pos().unwrap_or((0.0, 0.0));
-                    pb.line_to(current.0 + ns[0], current.1);
-                }
-                last_control_point = LastControlPoint::None;
-            }
-            Token::LargeV => {
-                let ns = parse_n_nums(&mut tokens, 1)?;
-                let current = pb.current_pos().unwrap_or((0.0, 0.0));
-                pb.line_to(current.0, ns[0]);
-                while is_num_or_comma(&tokens.peek().unwrap().to_owned()?) {
-                    let ns = parse_n_nums(&mut tokens, 1)?;
-                    let current = pb.current_pos().unwrap_or((0.0, 0.0));
-                    pb.line_to(current.0, ns[0]);
-                }
-                last_control_point = LastControlPoint::None;
+///
+/// # Examples
+///
+/// ```
+/// use nanachi::path_data_notation::{parse, ErrorKind};
+/// assert!(parse("M 10,20 l1e1-5 a1 1 0 00 10 10z").is_ok());
+/// assert_eq!(parse("M 10 20 L 30").unwrap_err().kind, ErrorKind::UnexpectedEnd);
+/// ```
+pub fn parse(str: &str) -> Result<Path, ParseError> {
+    let mut parser = Parser::new(str);
+    parser.parse()?;
+    Ok(parser.builder.end())
+}
+
+/// Parse SVG path notation and return the path of the valid prefix even if an error is found.
+///
+/// Like browsers do, the path is rendered up to the last complete segment before the error.
+pub fn parse_with_recovery(str: &str) -> (Path, Option<ParseError>) {
+    let mut parser = Parser::new(str);
+    let error = parser.parse().err();
+    (parser.builder.end(), error)
+}
+
+struct Parser<'a> {
+    tokens: Tokenize<'a>,
+    builder: PathBuilder,
+    current: Point,
+    subpath_start: Point,
+    /// Whether the current subpath is closed and a new one has not started.
+    closed: bool,
+    last_control_point: LastControlPoint,
+}
+
+impl<'a> Parser<'a> {
+    fn new(str: &'a str) -> Self {
+        Parser {
+            tokens: Tokenize::new(str),
+            builder: PathBuilder::new(),
+            current: Point::from((0.0, 0.0)),
+            subpath_start: Point::from((0.0, 0.0)),
+            closed: false,
+            last_control_point: LastControlPoint::None,
+        }
+    }
+
+    fn parse(&mut self) -> Result<(), ParseError> {
+        self.tokens.skip_wsp();
+        if self.tokens.is_end() {
+            return Ok(());
+        }
+        let offset = self.tokens.offset();
+        let command = self.tokens.command()?;
+        if command != 'M' && command != 'm' {
+            return Err(ParseError {
+                kind: ErrorKind::MissingMoveTo,
+                offset,
+                expected: Some(Expected::Command),
+                found: Some(command),
+            });
+        }
+        self.command(command)?;
+        loop {
+            self.tokens.skip_wsp();
+            if self.tokens.is_end() {
+                return Ok(());
             }
-            Token::SmallV => {
-                let ns = parse_n_nums(&mut tokens, 1)?;
-                let current = pb.current_pos().unwrap_or((0.0, 0.0));
-                pb.line_to(current.0, current.1 + ns[0]);
-                while is_num_or_comma(&tokens.peek().unwrap().to_owned()?) {
-                    let ns = parse_n_nums(&mut tokens, 1)?;
-                    let current = pb.current_pos().unwrap_or((0.0, 0.0));
-                    pb.line_to(current.0, current.1 + ns[0]);
-                }
-                last_control_point = LastControlPoint::None;
+            let command = self.tokens.command()?;
+            self.command(command)?;
+        }
+    }
+
+    /// Parse arguments of the command and repeat them while numbers follow.
+    fn command(&mut self, command: char) -> Result<(), ParseError> {
+        if command == 'Z' || command == 'z' {
+            self.builder.close();
+            self.current = self.subpath_start;
+            self.closed = true;
+            self.last_control_point = LastControlPoint::None;
+            return Ok(());
+        }
+        let relative = command.is_ascii_lowercase();
+        let mut command = command.to_ascii_uppercase();
+        loop {
+            self.tokens.skip_wsp();
+            self.segment(command, relative)?;
+            // Subsequent pairs of moveto are implicit lineto.
+            if command == 'M' {
+                command = 'L';
             }
-            Token::LargeQ => {
-                let mut ns = parse_n_nums(&mut tokens, 4)?;
-                pb.quad(ns[0], ns[1], ns[2], ns[3]);
-                while is_num_or_comma(&tokens.peek().unwrap().to_owned()?) {
-                    ns = parse_n_nums(&mut tokens, 4)?;
-                    pb.quad(ns[0], ns[1], ns[2], ns[3]);
+            let comma = self.tokens.skip_comma_wsp();
+            if !self.tokens.is_number_start() {
+                if comma {
+                    // A comma must be followed by an argument.
+                    self.tokens.number()?;
                 }
-                last_control_point = LastControlPoint::Quad(Point::from((ns[0], ns[1])));
+                return Ok(());
             }
-            Token::SmallQ => {
-                let mut ns = parse_n_nums(&mut tokens, 4)?;
-                let mut current = pb.current_pos().unwrap_or((0.0, 0.0));
-                pb.quad(
-                    current.0 + ns[0],
-                    current.1 + ns[1],
-                    current.0 + ns[2],
-                    current.1 + ns[3],
-                );
-                while is_num_or_comma(&tokens.peek().unwrap().to_owned()?) {
-                    ns = parse_n_nums(&mut tokens, 4)?;
-                    current = pb.current_pos().unwrap_or((0.0, 0.0));
-                    pb.quad(
-                        current.0 + ns[0],
-                        current.1 + ns[1],
-                        current.0 + ns[2],
-                        current.1 + ns[3],
-                    );
-                }
-                last_control_point =
-                    LastControlPoint::Quad(Point::from((current.0 + ns[0], current.1 + ns[1])));
+        }
+    }
+
+    fn segment(&mut self, command: char, relative: bool) -> Result<(), ParseError> {
+        let origin = if relative {
+            self.current
+        } else {
+            Point::from((0.0, 0.0))
+        };
+        if command != 'M' && self.closed {
+            // Drawing after closepath starts at the start of the closed subpath.
+            self.builder
+                .move_to(self.subpath_start.x(), self.subpath_start.y());
+        }
+        match command {
+            'M' => {
+                let p = origin + self.point()?;
+                self.builder.move_to(p.x(), p.y());
+                self.subpath_start = p;
+                self.end_segment(p, LastControlPoint::None);
             }
-            Token::LargeT => {
-                let mut ns = parse_n_nums(&mut tokens, 2)?;
-                let mut cp = quad_reflection_point(
-                    &last_control_point,
-                    pb.current_pos().unwrap_or((0.0, 0.0)).into(),
-                );
-                pb.quad(cp.x(), cp.y(), ns[0], ns[1]);
-                while is_num_or_comma(&tokens.peek().unwrap().to_owned()?) {
-                    cp = Point::from((ns[0], ns[1])) * 2.0 - cp;
-                    ns = parse_n_nums(&mut tokens, 2)?;
-                    pb.quad(cp.x(), cp.y(), ns[0], ns[1]);
-                }
-                last_control_point = LastControlPoint::Quad(cp);
+            'L' => {
+                let p = origin + self.point()?;
+                self.builder.line_to(p.x(), p.y());
+                self.end_segment(p, LastControlPoint::None);
             }
-            Token::SmallT => {
-                let mut ns = parse_n_nums(&mut tokens, 2)?;
-                let mut current = pb.current_pos().unwrap_or((0.0, 0.0)).into();
-                let mut cp = quad_reflection_point(&last_control_point, current);
-                pb.quad(cp.x(), cp.y(), current.x() + ns[0], current.y() + ns[1]);
-                while is_num_or_comma(&tokens.peek().unwrap().to_owned()?) {
-                    cp = (current + Point::from((ns[0], ns[1]))) * 2.0 - cp;
-                    current = current + Point::from((ns[0], ns[1]));
-                    ns = parse_n_nums(&mut tokens, 2)?;
-                    pb.quad(cp.x(), cp.y(), current.x() + ns[0], current.y() + ns[1]);
-                }
-                last_control_point = LastControlPoint::Quad(cp);
+            'H' => {
+                let x = origin.x() + self.tokens.number()?;
+                self.builder.line_to(x, self.current.y());
+                self.end_segment(Point::from((x, self.current.y())), LastControlPoint::None);
             }
-            Token::LargeC => {
-                let mut ns = parse_n_nums(&mut tokens, 6)?;
-                pb.cubic(ns[0], ns[1], ns[2], ns[3], ns[4], ns[5]);
-                while is_num_or_comma(&tokens.peek().unwrap().to_owned()?) {
-                    ns = parse_n_nums(&mut tokens, 6)?;
-                    pb.cubic(ns[0], ns[1], ns[2], ns[3], ns[4], ns[5]);
-                }
-                last_control_point = LastControlPoint::Cubic(Point::from((ns[2], ns[3])));
+            'V' => {
+                let y = origin.y() + self.tokens.number()?;
+                self.builder.line_to(self.current.x(), y);
+                self.end_segment(Point::from((self.current.x(), y)), LastControlPoint::None);
             }
-            Token::SmallC => {
-                let mut ns = parse_n_nums(&mut tokens, 6)?;
-                let mut current = pb.current_pos().unwrap_or((0.0, 0.0));
-                pb.cubic(
-                    current.0 + ns[0],
-                    current.1 + ns[1],
-                    current.0 + ns[2],
-                    current.1 + ns[3],
-                    current.0 + ns[4],
-                    current.1 + ns[5],
-                );
-                while is_num_or_comma(&tokens.peek().unwrap().to_owned()?) {
-                    ns = parse_n_nums(&mut tokens, 6)?;
-                    current = pb.current_pos().unwrap_or((0.0, 0.0));
-                    pb.cubic(
-                        current.0 + ns[0],
-                        current.1 + ns[1],
-                        current.0 + ns[2],
-                        current.1 + ns[3],
-                        current.0 + ns[4],
-                        current.1 + ns[5],
-                    );
-                }
-                last_control_point =
-                    LastControlPoint::Cubic(Point::from((current.0 + ns[2], current.1 + ns[3])));
+            'Q' => {
+                let c = origin + self.point()?;
+                self.comma_wsp();
+                let p = origin + self.point()?;
+                self.builder.quad(c.x(), c.y(), p.x(), p.y());
+                self.end_segment(p, LastControlPoint::Quad(c));
             }
-            Token::LargeS => {
-                let mut ns = parse_n_nums(&mut tokens, 4)?;
-                let mut cp = cubic_reflection_point(
-                    &last_control_point,
-                    pb.current_pos().unwrap_or((0.0, 0.0)).into(),
-                );
-                pb.cubic(cp.x(), cp.y(), ns[0], ns[1], ns[2], ns[3]);
-                while is_num_or_comma(&tokens.peek().unwrap().to_owned()?) {
-                    cp = Point::from((ns[2], ns[3])) * 2.0 - Point::from((ns[0], ns[1]));
-                    ns = parse_n_nums(&mut tokens, 4)?;
-                    pb.cubic(cp.x(), cp.y(), ns[0], ns[1], ns[2], ns[3]);
-                }
-                last_control_point = LastControlPoint::Quad(Point::from((ns[0], ns[1])));
+            'T' => {
+                let p = origin + self.point()?;
+                let c = match self.last_control_point {
+                    LastControlPoint::Quad(c) => self.current * 2.0 - c,
+                    _ => self.current,
+                };
+                self.builder.quad(c.x(), c.y(), p.x(), p.y());
+                self.end_segment(p, LastControlPoint::Quad(c));
             }
-            Token::SmallS => {
-                let mut ns = parse_n_nums(&mut tokens, 4)?;
-                let mut current = pb.current_pos().unwrap_or((0.0, 0.0)).into();
-                let mut cp = cubic_reflection_point(&last_control_point, current);
-                pb.cubic(
-                    cp.x(),
-                    cp.y(),
-                    current.x() + ns[0],
-                    current.y() + ns[1],
-                    current.x() + ns[2],
-                    current.y() + ns[3],
-                );
-                while is_num_or_comma(&tokens.peek().unwrap().to_owned()?) {
-                    cp = current + Point::from((ns[0], ns[1])) * 2.0 - Point::from((ns[2], ns[3]));
-                    current = current + Point::from((ns[2], ns[3]));
-                    ns = parse_n_nums(&mut tokens, 4)?;
-                    pb.cubic(
-                        cp.x(),
-                        cp.y(),
-                        current.x() + ns[0],
-                        current.y() + ns[1],
-                        current.x() + ns[2],
-                        current.y() + ns[3],
-                    );
-                }
-                last_control_point =
-                    LastControlPoint::Quad(Point::from((current.x() + ns[0], current.y() + ns[1])));
+            'C' => {
+                let c1 = origin + self.point()?;
+                self.comma_wsp();
+                let c2 = origin + self.point()?;
+                self.comma_wsp();
+                let p = origin + self.point()?;
+                self.builder
+                    .cubic(c1.x(), c1.y(), c2.x(), c2.y(), p.x(), p.y());
+                self.end_segment(p, LastControlPoint::Cubic(c2));
             }
-            Token::LargeA => {
-                let mut ns = parse_n_nums(&mut tokens, 7)?;
-                pb.ellipse_from_endpoint(
-                    ns[0],
-                    ns[1],
-                    ns[2].to_radians(),
-                    ns[3] != 0.0,
-                    ns[4] != 0.0,
-                    ns[5],
-                    ns[6],
-                );
-                while is_num_or_comma(&tokens.peek().unwrap().to_owned()?) {
-                    ns = parse_n_nums(&mut tokens, 7)?;
-                    pb.ellipse_from_endpoint(
-                        ns[0],
-                        ns[1],
-                        ns[2].to_radians(),
-                        ns[3] != 0.0,
-                        ns[4] != 0.0,
-                        ns[5],
-                        ns[6],
-                    );
-                }
-                last_control_point = LastControlPoint::None;
+            'S' => {
+                let c2 = origin + self.point()?;
+                self.comma_wsp();
+                let p = origin + self.point()?;
+                let c1 = match self.last_control_point {
+                    LastControlPoint::Cubic(c) => self.current * 2.0 - c,
+                    _ => self.current,
+                };
+                self.builder
+                    .cubic(c1.x(), c1.y(), c2.x(), c2.y(), p.x(), p.y());
+                self.end_segment(p, LastControlPoint::Cubic(c2));
             }
-            Token::SmallA => {
-                let mut ns = parse_n_nums(&mut tokens, 7)?;
-                let mut current: Point = pb.current_pos().unwrap_or((0.0, 0.0)).into();
-                current = current + Point::from((ns[5], ns[6]));
-                pb.ellipse_from_endpoint(
-                    ns[0],
-                    ns[1],
-                    ns[2].to_radians(),
-                    ns[3] != 0.0,
-                    ns[4] != 0.0,
-                    current.x(),
-                    current.y(),
-                );
-                while is_num_or_comma(&tokens.peek().unwrap().to_owned()?) {
-                    ns = parse_n_nums(&mut tokens, 7)?;
-                    current = current + Point::from((ns[5], ns[6]));
-                    pb.ellipse_from_endpoint(
-                        ns[0],
-                        ns[1],
-                        ns[2].to_radians(),
-                        ns[3] != 0.0,
-                        ns[4] != 0.0,
-                        current.x(),
-                        current.y(),
+            'A' => {
+                let radius_x = self.tokens.number()?;
+                self.comma_wsp();
+                let radius_y = self.tokens.number()?;
+                self.comma_wsp();
+                let rotation = self.tokens.number()?;
+                self.comma_wsp();
+                let large = self.tokens.flag()?;
+                self.comma_wsp();
+                let sweep = self.tokens.flag()?;
+                self.comma_wsp();
+                let p = origin + self.point()?;
+                // An arc whose endpoints are identical is omitted.
+                if p != self.current {
+                    self.builder.ellipse_from_endpoint(
+                        radius_x,
+                        radius_y,
+                        rotation.to_radians(),
+                        large,
+                        sweep,
+                        p.x(),
+                        p.y(),
                     );
                 }
-                last_control_point = LastControlPoint::None;
-            }
-            Token::LargeZ | Token::SmallZ => {
-                pb.close();
-                last_control_point = LastControlPoint::None;
-            }
-            Token::EOS => {
-                break;
-            }
-            token => {
-                return Err(format!("Unexpected token: {:?}", token));
+                self.end_segment(p, LastControlPoint::None);
             }
+            _ => unreachable!(),
         }
+        Ok(())
     }
-    Ok(pb.end())
-}
 
-fn parse_n_nums(tokens: &mut Peekable<Tokenize<Chars>>, n: usize) -> Result<Vec<f32>, String> {
-    let mut v = Vec::new();
-    for _ in 0..n {
-        skip_comma(tokens)?;
-        match tokens.next().unwrap()? {
-            Token::Num(n) => {
-                v.push(n);
-            }
-            token => {
-                return Err(format!("Expects num, but found: {:?}", token));
-            }
-        };
+    fn end_segment(&mut self, p: Point, last_control_point: LastControlPoint) {
+        self.current = p;
+        self.closed = false;
+        self.last_control_point = last_control_point;
     }
-    Ok(v)
-}
 
-fn is_num_or_comma(token: &Token) -> bool {
-    match token {
-        Token::Num(_) | Token::Comma => true,
-        _ => false,
+    fn point(&mut self) -> Result<Point, ParseError> {
+        let x = self.tokens.number()?;
+        self.comma_wsp();
+        let y = self.tokens.number()?;
+        Ok(Point::from((x, y)))
     }
-}
 
-fn skip_comma(tokens: &mut Peekable<Tokenize<Chars>>) -> Result<(), String> {
-    match tokens.peek().unwrap().to_owned()? {
-        Token::Comma => {
-            tokens.next().unwrap()?;
-        }
-        _ => {}
+    fn comma_wsp(&mut self) {
+        self.tokens.skip_comma_wsp();
     }
-    Ok(())
 }
 
-fn quad_reflection_point(lcp: &LastControlPoint, pos: Point) -> Point {
-    match lcp {
-        LastControlPoint::Quad(p) => pos * 2.0 - *p,
-        _ => pos,
-    }
-}
+#[test]
+fn test() {
+    let (path, error) = parse_with_recovery("M 0 0 L 10 0 L 10 10 L 5");
+    assert_eq!(path.0.len(), 2);
+    assert_eq!(
+        error,
+        Some(ParseError {
+            kind: ErrorKind::UnexpectedEnd,
+            offset: 24,
+            expected: Some(Expected::Number),
+            found: None,
+        })
+    );
 
-fn cubic_reflection_point(lcp: &LastControlPoint, pos: Point) -> Point {
-    match lcp {
-        LastControlPoint::Cubic(p) => pos * 2.0 - *p,
-        _ => pos,
-    }
+    let error = parse("L 10 10").unwrap_err();
+    assert_eq!(error.kind, ErrorKind::MissingMoveTo);
+
+    let error = parse("M 0 0 L 10 10,").unwrap_err();
+    assert_eq!(error.offset, 14);
+
+    let error = parse("M 0 0 X").unwrap_err();
+    assert_eq!((error.offset, error.found), (6, Some('X')));
+
+    // Drawing after closepath starts from the start of the subpath.
+    let path = parse("M 1 1 h 10 v 10 z l 5 5").unwrap();
+    assert_eq!(path.0.last().unwrap().left_point(), Point::from((1.0, 1.0)));
+
+    assert!(parse("").unwrap().0.is_empty());
+    assert!(parse("m-1-1+2+2.5.5.5").is_ok());
 }
@@ -1,208 +1,188 @@
-use std::{iter::Peekable, num::ParseFloatError};
-
-#[derive(Debug, Clone, PartialEq)]
-pub enum Token {
-    LargeM,
-    SmallM,
-    LargeL,
-    SmallL,
-    LargeH,
-    SmallH,
-    LargeV,
-    SmallV,
-    LargeZ,
-    SmallZ,
-    LargeC,
-    SmallC,
-    LargeS,
-    SmallS,
-    LargeQ,
-    SmallQ,
-    LargeT,
-    SmallT,
-    LargeA,
-    SmallA,
-    Num(f32),
-    Comma,
-    EOS,
-}
+use super::error::{ErrorKind, Expected, ParseError};
 
-pub struct Tokenize<'a, T: Iterator<Item = char>> {
-    chars: Peekable<&'a mut T>,
-    ended: bool,
+/// Scanner of the path data.
+///
+/// Numbers and flags are context dependent in the grammar (e.g. `a1 1 0 00 10 10`),
+/// so the parser asks the scanner for what it expects next.
+pub struct Tokenize<'a> {
+    src: &'a str,
+    pos: usize,
 }
 
-impl<'a, T: Iterator<Item = char>> Tokenize<'a, T> {
-    pub fn new(it: &'a mut T) -> Tokenize<'a, T> {
-        Tokenize {
-            chars: it.peekable(),
-            ended: false,
+impl<'a> Tokenize<'a> {
+    pub fn new(src: &'a str) -> Tokenize<'a> {
+        Tokenize { src, pos: 0 }
+    }
+
+    /// Current byte offset.
+    pub fn offset(&self) -> usize {
+        self.pos
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.src[self.pos..].chars().next()
+    }
+
+    fn peek_byte(&self, n: usize) -> Option<u8> {
+        self.src.as_bytes().get(self.pos + n).copied()
+    }
+
+    pub fn is_end(&self) -> bool {
+        self.pos == self.src.len()
+    }
+
+    /// Skip white spaces.
+    pub fn skip_wsp(&mut self) {
+        while let Some(b' ' | b'\t' | b'\n' | b'\x0C' | b'\r') = self.peek_byte(0) {
+            self.pos += 1;
         }
     }
 
-    pub fn try_parse_num(&mut self) -> Option<Result<f32, String>> {
-        let mut str = if let Some('-') = self.chars.peek() {
-            self.chars.next();
-            "-"
+    /// Skip white spaces and at most one comma.
+    /// Returns `true` if a comma is skipped.
+    pub fn skip_comma_wsp(&mut self) -> bool {
+        self.skip_wsp();
+        if self.peek_byte(0) == Some(b',') {
+            self.pos += 1;
+            self.skip_wsp();
+            true
         } else {
-            ""
-        }
-        .to_string();
-        let mut dot = false;
-        loop {
-            match self.chars.peek().copied() {
-                Some(c) if c.is_numeric() || c == '.' => {
-                    if c == '.' {
-                        if dot {
-                            break;
-                        }
-                        dot = true;
-                    }
-                    self.chars.next();
-                    str.push(c);
-                }
-                _ => {
-                    break;
-                }
-            }
+            false
         }
-        if str.is_empty() {
-            None
-        } else {
+    }
+
+    /// Whether a number starts at the current position.
+    pub fn is_number_start(&self) -> bool {
+        matches!(self.peek_byte(0), Some(b'0'..=b'9' | b'.' | b'+' | b'-'))
+    }
+
+    /// Read a command letter.
+    pub fn command(&mut self) -> Result<char, ParseError> {
+        match self.peek() {
             Some(
-                str.parse()
-                    .map_err(|e: ParseFloatError| format!("{}: {:?}", e, str)),
-            )
+                c @ ('M' | 'm' | 'Z' | 'z' | 'L' | 'l' | 'H' | 'h' | 'V' | 'v' | 'C' | 'c' | 'S'
+                | 's' | 'Q' | 'q' | 'T' | 't' | 'A' | 'a'),
+            ) => {
+                self.pos += 1;
+                Ok(c)
+            }
+            found => Err(self.unexpected(Expected::Command, found)),
         }
     }
-}
 
-impl<'a, T: Iterator<Item = char>> Iterator for Tokenize<'a, T> {
-    type Item = Result<Token, String>;
+    /// Read a number.
+    pub fn number(&mut self) -> Result<f32, ParseError> {
+        let start = self.pos;
+        let digits = |s: &mut Self| {
+            let p = s.pos;
+            while let Some(b'0'..=b'9') = s.peek_byte(0) {
+                s.pos += 1;
+            }
+            s.pos - p
+        };
 
-    fn next(&mut self) -> Option<Self::Item> {
-        while let Some(c) = self.chars.peek() {
-            if c.is_ascii_whitespace() {
-                self.chars.next();
-            } else {
-                break;
+        if let Some(b'+' | b'-') = self.peek_byte(0) {
+            self.pos += 1;
+        }
+        let mut n = digits(self);
+        if self.peek_byte(0) == Some(b'.')
+            && (n != 0 || self.peek_byte(1).is_some_and(|b| b.is_ascii_digit()))
+        {
+            self.pos += 1;
+            n += digits(self);
+        }
+        if n == 0 {
+            self.pos = start;
+            return Err(self.unexpected(Expected::Number, self.peek()));
+        }
+        if let Some(b'e' | b'E') = self.peek_byte(0) {
+            let sign = matches!(self.peek_byte(1), Some(b'+' | b'-')) as usize;
+            if self.peek_byte(1 + sign).is_some_and(|b| b.is_ascii_digit()) {
+                self.pos += 1 + sign;
+                digits(self);
             }
         }
 
-        if let None = self.chars.peek() {
-            if self.ended {
-                return None;
-            } else {
-                self.ended = true;
-                return Some(Ok(Token::EOS));
-            }
+        let str = &self.src[start..self.pos];
+        match str.parse::<f32>() {
+            Ok(n) if n.is_finite() => Ok(n),
+            _ => Err(ParseError {
+                kind: ErrorKind::InvalidNumber,
+                offset: start,
+                expected: Some(Expected::Number),
+                found: str.chars().next(),
+            }),
         }
+    }
 
-        match self.try_parse_num() {
-            Some(Ok(n)) => {
-                return Some(Ok(Token::Num(n)));
-            }
-            Some(Err(string)) => {
-                return Some(Err(string));
-            }
-            None => {}
+    /// Read an arc flag which is a single `0` or `1`.
+    pub fn flag(&mut self) -> Result<bool, ParseError> {
+        match self.peek_byte(0) {
+            Some(b'0') => {
+                self.pos += 1;
+                Ok(false)
+            }
+            Some(b'1') => {
+                self.pos += 1;
+                Ok(true)
+            }
+            _ => Err(self.unexpected(Expected::Flag, self.peek())),
         }
+    }
 
-        match self.chars.peek() {
-            Some(',') => {
-                self.chars.next();
-                Some(Ok(Token::Comma))
-            }
-            Some('M') => {
-                self.chars.next();
-                Some(Ok(Token::LargeM))
-            }
-            Some('m') => {
-                self.chars.next();
-                Some(Ok(Token::SmallM))
-            }
-            Some('L') => {
-                self.chars.next();
-                Some(Ok(Token::LargeL))
-            }
-            Some('l') => {
-                self.chars.next();
-                Some(Ok(Token::SmallL))
-            }
-            Some('H') => {
-                self.chars.next();
-                Some(Ok(Token::LargeH))
-            }
-            Some('h') => {
-                self.chars.next();
-                Some(Ok(Token::SmallH))
-            }
-            Some('V') => {
-                self.chars.next();
-                Some(Ok(Token::LargeV))
-            }
-            Some('v') => {
-                self.chars.next();
-                Some(Ok(Token::SmallV))
-            }
-            Some('Z') => {
-                self.chars.next();
-                Some(Ok(Token::LargeZ))
-            }
-            Some('z') => {
-                self.chars.next();
-                Some(Ok(Token::SmallZ))
-            }
-            Some('C') => {
-                self.chars.next();
-                Some(Ok(Token::LargeC))
-            }
-            Some('c') => {
-                self.chars.next();
-                Some(Ok(Token::SmallC))
-            }
-            Some('S') => {
-                self.chars.next();
-                Some(Ok(Token::LargeS))
-            }
-            Some('s') => {
-                self.chars.next();
-                Some(Ok(Token::SmallS))
-            }
-            Some('Q') => {
-                self.chars.next();
-                Some(Ok(Token::LargeQ))
-            }
-            Some('q') => {
-                self.chars.next();
-                Some(Ok(Token::SmallQ))
-            }
-            Some('T') => {
-                self.chars.next();
-                Some(Ok(Token::LargeT))
-            }
-            Some('t') => {
-                self.chars.next();
-                Some(Ok(Token::SmallT))
-            }
-            Some('A') => {
-                self.chars.next();
-                Some(Ok(Token::LargeA))
-            }
-            Some('a') => {
-                self.chars.next();
-                Some(Ok(Token::SmallA))
-            }
-            Some(c) => Some(Err(format!("Unexpected char: {}", c))),
-            None => None,
+    /// Read a name of a function such as `translate`.
+    pub fn name(&mut self) -> Result<&'a str, ParseError> {
+        let start = self.pos;
+        while let Some(b'a'..=b'z' | b'A'..=b'Z') = self.peek_byte(0) {
+            self.pos += 1;
+        }
+        if start == self.pos {
+            Err(self.unexpected(Expected::Transform, self.peek()))
+        } else {
+            Ok(&self.src[start..self.pos])
+        }
+    }
+
+    /// Read the character `c`.
+    pub fn char(&mut self, c: char) -> Result<(), ParseError> {
+        match self.peek() {
+            Some(found) if found == c => {
+                self.pos += c.len_utf8();
+                Ok(())
+            }
+            found => Err(self.unexpected(Expected::Char(c), found)),
+        }
+    }
+
+    fn unexpected(&self, expected: Expected, found: Option<char>) -> ParseError {
+        ParseError {
+            kind: if found.is_some() {
+                ErrorKind::UnexpectedChar
+            } else {
+                ErrorKind::UnexpectedEnd
+            },
+            offset: self.pos,
+            expected: Some(expected),
+            found,
         }
     }
 }
 
 #[test]
 fn test() {
-    let src = "M 10 20 30.0,40.5.123";
-    let mut chars = src.chars();
-    let tokens = Tokenize::new(&mut chars);
-    dbg!(tokens.collect::<Vec<_>>());
+    let mut tokens = Tokenize::new("10 -20.5.123 +1e-2 1E+3 .5e1x");
+    let mut ns = Vec::new();
+    while tokens.is_number_start() {
+        ns.push(tokens.number().unwrap());
+        tokens.skip_comma_wsp();
+    }
+    assert_eq!(ns, vec![10.0, -20.5, 0.123, 0.01, 1000.0, 5.0]);
+    assert_eq!(tokens.offset(), 28);
+    assert_eq!(tokens.command().unwrap_err().found, Some('x'));
+
+    let mut tokens = Tokenize::new("00 1");
+    assert_eq!(tokens.flag(), Ok(false));
+    assert_eq!(tokens.flag(), Ok(false));
+    tokens.skip_comma_wsp();
+    assert_eq!(tokens.flag(), Ok(true));
 }
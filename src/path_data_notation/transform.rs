@@ -0,0 +1,100 @@
+use super::error::{ErrorKind, ParseError};
+use super::tokenize::Tokenize;
+use crate::matrix::Matrix;
+
+/// Parse [SVG transform attribute](https://developer.mozilla.org/en-US/docs/Web/SVG/Attribute/transform).
+///
+/// It is also available as `matrix::parse_transform`.
+///
+/// # Examples
+///
+/// ```
+/// use nanachi::{matrix::Matrix, path_data_notation::parse_transform};
+/// assert_eq!(
+///     parse_transform("translate(10 20) scale(2)").unwrap(),
+///     Matrix::new().scale(2.0, 2.0).translate(10.0, 20.0)
+/// );
+/// ```
+pub fn parse_transform(str: &str) -> Result<Matrix, ParseError> {
+    let mut tokens = Tokenize::new(str);
+    let mut matrix = Matrix::new();
+    tokens.skip_wsp();
+    while !tokens.is_end() {
+        let offset = tokens.offset();
+        let name = tokens.name()?;
+        tokens.skip_wsp();
+        tokens.char('(')?;
+        tokens.skip_wsp();
+        let mut args = Vec::with_capacity(6);
+        if tokens.is_number_start() {
+            args.push(tokens.number()?);
+            // A comma must be followed by another argument.
+            while tokens.skip_comma_wsp() || tokens.is_number_start() {
+                args.push(tokens.number()?);
+            }
+        }
+        tokens.char(')')?;
+        let m = match (name, args.as_slice()) {
+            ("matrix", &[a, b, c, d, e, f]) => Matrix([a, c, e, b, d, f]),
+            ("translate", &[x]) => Matrix::new().translate(x, 0.0),
+            ("translate", &[x, y]) => Matrix::new().translate(x, y),
+            ("scale", &[x]) => Matrix::new().scale(x, x),
+            ("scale", &[x, y]) => Matrix::new().scale(x, y),
+            ("rotate", &[a]) => Matrix::new().rotate(a.to_radians()),
+            ("rotate", &[a, x, y]) => Matrix::new()
+                .translate(-x, -y)
+                .rotate(a.to_radians())
+                .translate(x, y),
+            ("skewX", &[a]) => Matrix::new().skew_x(a.to_radians().tan()),
+            ("skewY", &[a]) => Matrix::new().skew_y(a.to_radians().tan()),
+            _ => {
+                return Err(ParseError {
+                    kind: ErrorKind::InvalidTransform,
+                    offset,
+                    expected: None,
+                    found: name.chars().next(),
+                })
+            }
+        };
+        // The rightmost transform is applied first.
+        matrix = m.then(&matrix);
+        tokens.skip_comma_wsp();
+    }
+    Ok(matrix)
+}
+
+#[test]
+fn test() {
+    use super::error::Expected;
+
+    let parse = |str| parse_transform(str).unwrap();
+    assert_eq!(parse(""), Matrix::new());
+    assert_eq!(
+        parse("matrix(1, 2, 3, 4, 5, 6)"),
+        Matrix([1.0, 3.0, 5.0, 2.0, 4.0, 6.0])
+    );
+    assert_eq!(parse("translate(10)"), Matrix::new().translate(10.0, 0.0));
+    assert_eq!(
+        parse(" scale(2,3) , translate( 10 20 )"),
+        Matrix::new().translate(10.0, 20.0).scale(2.0, 3.0)
+    );
+    assert_eq!(
+        parse("rotate(90 10 0)"),
+        Matrix::new()
+            .translate(-10.0, 0.0)
+            .rotate(90f32.to_radians())
+            .translate(10.0, 0.0)
+    );
+
+    // A trailing or doubled comma is not allowed in the arguments.
+    let error = |str| parse_transform(str).unwrap_err();
+    for str in ["rotate(30,)", "rotate(30 , )", "translate(1,,2)"] {
+        assert_eq!(error(str).kind, ErrorKind::UnexpectedChar, "{str}");
+        assert_eq!(error(str).expected, Some(Expected::Number), "{str}");
+    }
+    assert_eq!(error("rotate(,30)").expected, Some(Expected::Char(')')));
+    assert_eq!(error("rotate(30").kind, ErrorKind::UnexpectedEnd);
+    let e = error("scale(1) skewX(1 2)");
+    assert_eq!((e.kind, e.offset), (ErrorKind::InvalidTransform, 9));
+    assert_eq!(error("shear(1)").kind, ErrorKind::InvalidTransform);
+}
@@ -0,0 +1,328 @@
+//! Path effects which transform a [`Path`] before filling or stroking.
+//!
+//! # Examples
+//!
+//! ```
+//! use nanachi::{path_effect::{Corner, Discrete, PathEffect}, primitives::rect};
+//! let effect = Corner::new(5.0).then(Discrete::new(4.0, 1.0, 42));
+//! let path = effect.apply(&rect(10.0, 10.0, 100.0, 50.0));
+//! ```
+
+use crate::matrix::Matrix;
+use crate::models::Line;
+use crate::path::{Path, PathItem};
+use crate::path_builder::PathBuilder;
+use crate::path_measure::PathMeasure;
+use crate::path_transform::path_transform;
+use crate::point::Point;
+
+/// A transform from [`Path`] to [`Path`].
+pub trait PathEffect {
+    fn apply(&self, path: &Path) -> Path;
+
+    /// Apply `self` and then `next`.
+    fn then<E: PathEffect>(self, next: E) -> Compose<Self, E>
+    where
+        Self: Sized,
+    {
+        Compose(self, next)
+    }
+}
+
+/// Composition of two path effects made by [`PathEffect::then`].
+#[derive(Debug, Clone)]
+pub struct Compose<A: PathEffect, B: PathEffect>(pub A, pub B);
+
+impl<A: PathEffect, B: PathEffect> PathEffect for Compose<A, B> {
+    fn apply(&self, path: &Path) -> Path {
+        self.1.apply(&self.0.apply(path))
+    }
+}
+
+/// Round the corners between lines with arcs of `radius`.
+///
+/// The radius is reduced at a corner whose lines are too short. Curves are kept as they are.
+#[derive(Debug, Clone)]
+pub struct Corner {
+    pub radius: f32,
+}
+
+impl Corner {
+    pub fn new(radius: f32) -> Corner {
+        Corner { radius }
+    }
+
+    /// Arc at the corner `b` between `a` and `c`. Returns `None` if the corner is straight.
+    fn arc(&self, a: Point, b: Point, c: Point) -> Option<(Point, f32, f32, f32)> {
+        let (v1, v2) = (b - a, c - b);
+        let (u1, u2) = (v1.unit(), v2.unit());
+        let cross = u1.cross(&u2);
+        if cross.abs() < 1e-6 {
+            return None;
+        }
+        let turn = u1.dot(&u2).clamp(-1.0, 1.0).acos();
+        // Distance from the corner to the tangent points.
+        let tan = (turn / 2.0).tan();
+        let d = (self.radius * tan)
+            .min(v1.norm() / 2.0)
+            .min(v2.norm() / 2.0);
+        let radius = d / tan;
+        let normal = if 0.0 < cross {
+            Point::from((-u1.y(), u1.x()))
+        } else {
+            Point::from((u1.y(), -u1.x()))
+        };
+        let center = b - u1 * d + normal * radius;
+        let angle1 = (normal * -1.0).atan2();
+        Some((center, radius, angle1, angle1 + turn.copysign(cross)))
+    }
+
+    fn round(&self, points: &[Point], close: bool) -> Vec<PathItem> {
+        let mut pb = PathBuilder::new();
+        if close {
+            // `points` ends with the first point.
+            let n = points.len() - 1;
+            let corners: Vec<_> = (0..n)
+                .map(|i| {
+                    (
+                        points[i],
+                        self.arc(points[(i + n - 1) % n], points[i], points[i + 1]),
+                    )
+                })
+                .collect();
+            let mut first = None;
+            for (p, arc) in corners {
+                match arc {
+                    Some((center, radius, angle1, angle2)) => {
+                        if first.is_none() {
+                            first = Some(center + Point::from_angle(angle1) * radius);
+                            pb.move_to(first.unwrap().x(), first.unwrap().y());
+                        }
+                        pb.arc(center.x(), center.y(), radius, angle1, angle2);
+                    }
+                    None if first.is_none() => {
+                        first = Some(p);
+                        pb.move_to(p.x(), p.y());
+                    }
+                    None => pb.line_to(p.x(), p.y()),
+                }
+            }
+            let first = first.unwrap();
+            pb.line_to(first.x(), first.y());
+        } else {
+            pb.move_to(points[0].x(), points[0].y());
+            for w in points.windows(3) {
+                match self.arc(w[0], w[1], w[2]) {
+                    Some((center, radius, angle1, angle2)) => {
+                        pb.arc(center.x(), center.y(), radius, angle1, angle2)
+                    }
+                    None => pb.line_to(w[1].x(), w[1].y()),
+                }
+            }
+            let last = points[points.len() - 1];
+            pb.line_to(last.x(), last.y());
+        }
+        pb.end().0
+    }
+}
+
+impl PathEffect for Corner {
+    fn apply(&self, path: &Path) -> Path {
+        path.map_line_runs(|points, close| {
+            let mut points = points.to_vec();
+            points.dedup();
+            if points.len() < 3 {
+                points
+                    .windows(2)
+                    .map(|w| PathItem::Line(Line([w[0], w[1]])))
+                    .collect()
+            } else {
+                self.round(&points, close)
+            }
+        })
+    }
+}
+
+/// Break the path into lines of about `segment_length` and displace their ends randomly
+/// up to `deviation` in the normal direction. It gives a sketchy look.
+///
+/// The same `seed` gives the same result.
+#[derive(Debug, Clone)]
+pub struct Discrete {
+    pub segment_length: f32,
+    pub deviation: f32,
+    pub seed: u64,
+}
+
+impl Discrete {
+    pub fn new(segment_length: f32, deviation: f32, seed: u64) -> Discrete {
+        Discrete {
+            segment_length,
+            deviation,
+            seed,
+        }
+    }
+}
+
+impl PathEffect for Discrete {
+    fn apply(&self, path: &Path) -> Path {
+        let mut rng = Pcg32::new(self.seed);
+        let mut res = Path::new(Vec::new());
+        for (pis, closed) in path.continuations() {
+            let measure = PathMeasure::new(&Path::new(pis.to_vec()), self.segment_length / 8.0);
+            let length = measure.length();
+            if length == 0.0 {
+                continue;
+            }
+            let n = (length / self.segment_length).ceil().max(1.0) as usize;
+            let mut points: Vec<Point> = (0..=n)
+                .filter_map(|i| measure.position_and_tangent_at(length * i as f32 / n as f32))
+                .map(|(p, tangent)| {
+                    let normal = Point::from((-tangent.y(), tangent.x()));
+                    p + normal * (self.deviation * (rng.next_f32() * 2.0 - 1.0))
+                })
+                .collect();
+            if points.len() < 2 {
+                continue;
+            }
+            if closed {
+                let first = points[0];
+                *points.last_mut().unwrap() = first;
+            }
+            res.merge(&Path::from_points(&points, closed));
+        }
+        res
+    }
+}
+
+/// Stamp `shape` repeatedly along the path every `advance`, starting at `phase`.
+///
+/// The origin of `shape` is placed on the path. If `rotate` is `true`, the x axis of `shape` follows the tangent.
+/// Nothing is stamped if `advance` is not positive.
+#[derive(Debug, Clone)]
+pub struct Stamp {
+    pub shape: Path,
+    pub advance: f32,
+    pub phase: f32,
+    pub rotate: bool,
+    /// Tolerance for flattening the path.
+    pub tolerance: f32,
+}
+
+impl Stamp {
+    pub fn new(shape: Path, advance: f32) -> Stamp {
+        Stamp {
+            shape,
+            advance,
+            phase: 0.0,
+            rotate: true,
+            tolerance: 0.1,
+        }
+    }
+}
+
+impl PathEffect for Stamp {
+    fn apply(&self, path: &Path) -> Path {
+        let mut res = Path::new(Vec::new());
+        if self.advance <= 0.0 || self.advance.is_nan() {
+            return res;
+        }
+        for (pis, _) in path.continuations() {
+            let measure = PathMeasure::new(&Path::new(pis.to_vec()), self.tolerance);
+            let mut distance = self.phase.rem_euclid(self.advance);
+            while distance <= measure.length() {
+                if let Some((p, tangent)) = measure.position_and_tangent_at(distance) {
+                    let angle = if self.rotate { tangent.atan2() } else { 0.0 };
+                    let matrix = Matrix::new().rotate(angle).translate(p.x(), p.y());
+                    res.merge(&path_transform(&self.shape, &matrix));
+                }
+                distance += self.advance;
+            }
+        }
+        res
+    }
+}
+
+/// Minimal PCG32 random number generator for reproducible effects.
+struct Pcg32 {
+    state: u64,
+}
+
+impl Pcg32 {
+    const MULTIPLIER: u64 = 6364136223846793005;
+    const INCREMENT: u64 = 1442695040888963407;
+
+    fn new(seed: u64) -> Pcg32 {
+        let mut rng = Pcg32 { state: 0 };
+        rng.next_u32();
+        rng.state = rng.state.wrapping_add(seed);
+        rng.next_u32();
+        rng
+    }
+
+    fn next_u32(&mut self) -> u32 {
+        let old = self.state;
+        self.state = old
+            .wrapping_mul(Self::MULTIPLIER)
+            .wrapping_add(Self::INCREMENT);
+        let xorshifted = (((old >> 18) ^ old) >> 27) as u32;
+        xorshifted.rotate_right((old >> 59) as u32)
+    }
+
+    /// Uniform random number in `[0, 1)`.
+    fn next_f32(&mut self) -> f32 {
+        (self.next_u32() >> 8) as f32 / (1u32 << 24) as f32
+    }
+}
+
+#[test]
+fn test() {
+    use crate::primitives::rect;
+    use std::f32::consts::PI;
+
+    let path = Corner::new(2.0).apply(&rect(0.0, 0.0, 10.0, 10.0));
+    let arcs: Vec<_> = path
+        .0
+        .iter()
+        .filter_map(|pi| match pi {
+            PathItem::Arc(arc) => Some(arc),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(arcs.len(), 4);
+    for arc in arcs {
+        assert!((arc.radius - 2.0).abs() < 1e-4);
+        assert!(((arc.angle2 - arc.angle1).abs() - PI / 2.0).abs() < 1e-4);
+    }
+    let bounds = path.bounds().unwrap();
+    assert!(bounds
+        .iter()
+        .zip([0.0, 10.0, 0.0, 10.0])
+        .all(|(a, b)| (a - b).abs() < 1e-4));
+
+    let effect = Discrete::new(2.0, 1.0, 7);
+    let a = effect.apply(&rect(0.0, 0.0, 10.0, 10.0));
+    let b = effect.apply(&rect(0.0, 0.0, 10.0, 10.0));
+    assert_eq!(a.0.len(), 21);
+    assert_eq!(format!("{:?}", a), format!("{:?}", b));
+
+    let stamp = Stamp::new(rect(-1.0, -1.0, 2.0, 2.0), 5.0);
+    let path = Path::from_points(&vec![(0.0, 0.0).into(), (20.0, 0.0).into()], false);
+    assert_eq!(stamp.apply(&path).continuations().len(), 5);
+    let stamp = Stamp::new(rect(-1.0, -1.0, 2.0, 2.0), 0.0);
+    assert!(stamp.apply(&path).0.is_empty());
+
+    // A closed run starting at a straight vertex has no zero-length line.
+    let points = [
+        (5.0, 0.0),
+        (10.0, 0.0),
+        (10.0, 10.0),
+        (0.0, 10.0),
+        (0.0, 0.0),
+        (5.0, 0.0),
+    ];
+    let path = Path::from_points(&points.iter().map(|&p| p.into()).collect(), true);
+    let path = Corner::new(2.0).apply(&path);
+    assert!(path.0.iter().all(|pi| pi.is_jump() || !pi.is_zero()));
+    assert_eq!(path.0[0].left_point(), Point::from((5.0, 0.0)));
+}
@@ -0,0 +1,476 @@
+//! Hit testing of paths and strokes.
+
+use crate::fill_rule::FillRule;
+use crate::models::Line;
+use crate::models::{Ellipse, Segment};
+use crate::path::{Path, PathItem};
+use crate::path_outline::{join_region, Cap, Join};
+use crate::point::Point;
+use std::f32::consts::TAU;
+
+/// Number of the bisection steps for finding a parameter on a curve.
+const BISECTION_STEPS: usize = 32;
+/// Number of samples for finding the feet of perpendiculars on an elliptic arc.
+const SAMPLES: usize = 32;
+
+/// Winding number of the path around the point.
+///
+/// The sign follows the rasterizer: a segment going downward contributes `-1` to the points on its right.
+/// Unclosed subpaths are closed with a straight line like filling does.
+pub fn path_winding(path: &Path, point: Point) -> i32 {
+    let mut winding = 0;
+    for (pis, closed) in path.continuations() {
+        let (first, last) = match (pis.first(), pis.last()) {
+            (Some(first), Some(last)) => (first, last),
+            _ => continue,
+        };
+        // Each item starts exactly at the end of the previous one not to count a vertex twice.
+        let mut start = if closed {
+            last.right_point()
+        } else {
+            first.left_point()
+        };
+        for pi in pis {
+            winding += item_winding(pi, start, point);
+            start = pi.right_point();
+        }
+        if !closed {
+            let end = first.left_point();
+            winding += piece_winding(&|t| start.lerp(end, t), 0.0, 1.0, point);
+        }
+    }
+    winding
+}
+
+/// Whether the point is in the filled area of the path. It matches [`Context::fill`](crate::context::Context::fill).
+pub fn path_contains(path: &Path, point: Point, fill_rule: impl FillRule) -> bool {
+    0.5 < fill_rule.apply(path_winding(path, point) as f32)
+}
+
+/// Whether the point is on the stroke of the path.
+///
+/// `width` is the full width of the stroke. It matches the outline of [`path_outline`](crate::path_outline::path_outline)
+/// with the half of `width`.
+pub fn path_stroke_contains(path: &Path, point: Point, width: f32, join: &Join, cap: &Cap) -> bool {
+    let half = width.abs() / 2.0;
+    for (pis, closed) in path.continuations() {
+        let pis: Vec<&PathItem> = pis.iter().filter(|pi| !pi.is_zero()).collect();
+        let (first, last) = match (pis.first(), pis.last()) {
+            (Some(first), Some(last)) => (*first, *last),
+            _ => continue,
+        };
+        if pis.iter().any(|pi| body_contains(pi, point, half)) {
+            return true;
+        }
+        let joins = if closed { pis.len() } else { pis.len() - 1 };
+        for i in 0..joins {
+            let (a, b) = (pis[i], pis[(i + 1) % pis.len()]);
+            if join_contains(join, a, b, point, half) {
+                return true;
+            }
+        }
+        if !closed
+            && (cap_contains(
+                cap,
+                first.left_point(),
+                tangent(first, 0.0) * -1.0,
+                point,
+                half,
+            ) || cap_contains(cap, last.right_point(), tangent(last, 1.0), point, half))
+        {
+            return true;
+        }
+    }
+    false
+}
+
+fn to_ellipse(pi: &PathItem) -> Option<Ellipse> {
+    match pi {
+        PathItem::Arc(arc) => Some(arc.to_ellipse()),
+        PathItem::Ellipse(ellipse) => Some(ellipse.clone()),
+        _ => None,
+    }
+}
+
+/// Position on the path item at `t` in `[0, 1]`.
+fn pos(pi: &PathItem, t: f32) -> Point {
+    if t == 0.0 {
+        return pi.left_point();
+    }
+    if t == 1.0 {
+        return pi.right_point();
+    }
+    match pi {
+        PathItem::Line(line) => line.eval(t),
+        PathItem::Arc(arc) => arc.eval(t),
+        PathItem::Ellipse(ellipse) => ellipse.eval(t),
+        PathItem::Quad(quad) => quad.eval(t),
+        PathItem::Cubic(cubic) => cubic.eval(t),
+        PathItem::CloseAndJump | PathItem::Jump => unreachable!(),
+    }
+}
+
+/// Derivative of [`pos`] by `t`.
+fn derivative(pi: &PathItem, t: f32) -> Point {
+    match pi {
+        PathItem::Line(line) => line.derivative(t),
+        PathItem::Arc(arc) => arc.derivative(t),
+        PathItem::Ellipse(ellipse) => ellipse.derivative(t),
+        PathItem::Quad(quad) => quad.derivative(t),
+        PathItem::Cubic(cubic) => cubic.derivative(t),
+        PathItem::CloseAndJump | PathItem::Jump => unreachable!(),
+    }
+}
+
+/// Unit tangent at `t`. A vanishing derivative at a control point is replaced with the nearby chord.
+fn tangent(pi: &PathItem, t: f32) -> Point {
+    let d = derivative(pi, t);
+    if 1e-6 < d.norm() {
+        d.unit()
+    } else if t < 0.5 {
+        (pos(pi, 0.01) - pos(pi, 0.0)).unit()
+    } else {
+        (pos(pi, 1.0) - pos(pi, 0.99)).unit()
+    }
+}
+
+/// Parameters in `(0, 1)` that split the path item into y-monotonic pieces, in ascending order.
+fn y_monotonic_breaks(pi: &PathItem) -> Vec<f32> {
+    match pi {
+        PathItem::Line(_) => Vec::new(),
+        PathItem::Arc(_) | PathItem::Ellipse(_) => {
+            let e = to_ellipse(pi).unwrap();
+            let mut ts: Vec<f32> = e
+                .extrema(1)
+                .into_iter()
+                .map(|angle| (angle - e.angle1) / (e.angle2 - e.angle1))
+                .collect();
+            ts.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            ts
+        }
+        PathItem::Quad(quad) => quad.extrema(1).into_iter().collect(),
+        PathItem::Cubic(cubic) => cubic.extrema(1).collect(),
+        PathItem::CloseAndJump | PathItem::Jump => unreachable!(),
+    }
+}
+
+fn item_winding(pi: &PathItem, start: Point, point: Point) -> i32 {
+    let f = |t: f32| if t == 0.0 { start } else { pos(pi, t) };
+    let mut ts = vec![0.0];
+    ts.extend(y_monotonic_breaks(pi));
+    ts.push(1.0);
+    ts.windows(2)
+        .map(|w| piece_winding(&f, w[0], w[1], point))
+        .sum()
+}
+
+/// Winding of a y-monotonic piece between `t0` and `t1`.
+fn piece_winding(f: &dyn Fn(f32) -> Point, t0: f32, t1: f32, point: Point) -> i32 {
+    let (p0, p1) = (f(t0), f(t1));
+    let above = p0.y() <= point.y();
+    if above == (p1.y() <= point.y()) {
+        return 0;
+    }
+    let (mut lo, mut hi) = (t0, t1);
+    for _ in 0..BISECTION_STEPS {
+        let mid = (lo + hi) / 2.0;
+        if (f(mid).y() <= point.y()) == above {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+    if f((lo + hi) / 2.0).x() < point.x() {
+        if p0.y() < p1.y() {
+            -1
+        } else {
+            1
+        }
+    } else {
+        0
+    }
+}
+
+/// Whether the point is within `half` along the normals of the path item.
+fn body_contains(pi: &PathItem, point: Point, half: f32) -> bool {
+    match pi {
+        PathItem::Line(line) => {
+            let v = line.0[1] - line.0[0];
+            let length = v.norm();
+            let u = v / length;
+            let d = point - line.0[0];
+            let s = d.dot(&u);
+            0.0 <= s && s <= length && d.cross(&u).abs() <= half
+        }
+        PathItem::Arc(arc) => {
+            let d = point - arc.center;
+            if half < (d.norm() - arc.radius).abs() {
+                return false;
+            }
+            let (lo, hi) = if arc.angle1 < arc.angle2 {
+                (arc.angle1, arc.angle2)
+            } else {
+                (arc.angle2, arc.angle1)
+            };
+            lo + (d.atan2() - lo).rem_euclid(TAU) <= hi
+        }
+        _ => perpendicular_feet(pi, point)
+            .into_iter()
+            .any(|t| (point - pos(pi, t)).norm() <= half),
+    }
+}
+
+/// Parameters of the feet of perpendiculars from the point to a curve.
+///
+/// They are the roots of `(point - pos(t)) . pos'(t)`, which is a polynomial on bezier curves.
+/// Elliptic arcs are sampled instead, so two feet within a sample interval can be missed on them.
+fn perpendicular_feet(pi: &PathItem, point: Point) -> Vec<f32> {
+    // Coefficients of `pos(t)` in ascending powers.
+    let c = match pi {
+        PathItem::Quad(q) => [
+            q.start,
+            (q.control1 - q.start) * 2.0,
+            q.start - q.control1 * 2.0 + q.end,
+            Point([0.0, 0.0]),
+        ],
+        PathItem::Cubic(c) => [
+            c.start,
+            (c.control1 - c.start) * 3.0,
+            (c.start - c.control1 * 2.0 + c.control2) * 3.0,
+            c.end - c.start + (c.control1 - c.control2) * 3.0,
+        ],
+        _ => return sampled_roots(&|t| (point - pos(pi, t)).dot(&derivative(pi, t))),
+    };
+    let q = [point - c[0], c[1] * -1.0, c[2] * -1.0, c[3] * -1.0];
+    let d = [c[1], c[2] * 2.0, c[3] * 3.0];
+    let mut f = [0.0; 6];
+    for (i, q) in q.iter().enumerate() {
+        for (j, d) in d.iter().enumerate() {
+            f[i + j] += q.dot(d) as f64;
+        }
+    }
+    polynomial_roots(&f).into_iter().map(|t| t as f32).collect()
+}
+
+/// Roots in `[0, 1]` of the polynomial with coefficients in ascending powers.
+///
+/// The roots of the derivative split `[0, 1]` into monotonic intervals, each of which has at most one root.
+fn polynomial_roots(p: &[f64]) -> Vec<f64> {
+    if p.len() <= 1 {
+        return Vec::new();
+    }
+    let eval = |t: f64| p.iter().rev().fold(0.0, |acc, c| acc * t + c);
+    let derivative: Vec<f64> = p
+        .iter()
+        .enumerate()
+        .skip(1)
+        .map(|(i, c)| c * i as f64)
+        .collect();
+    let mut breaks = vec![0.0];
+    breaks.extend(polynomial_roots(&derivative));
+    breaks.push(1.0);
+    let mut roots = Vec::new();
+    for w in breaks.windows(2) {
+        let (mut lo, mut hi) = (w[0], w[1]);
+        let flo = eval(lo);
+        if 0.0 < flo * eval(hi) {
+            continue;
+        }
+        for _ in 0..BISECTION_STEPS {
+            let mid = (lo + hi) / 2.0;
+            if (eval(mid) <= 0.0) == (flo <= 0.0) {
+                lo = mid;
+            } else {
+                hi = mid;
+            }
+        }
+        roots.push((lo + hi) / 2.0);
+    }
+    roots
+}
+
+/// Roots in `[0, 1]` of the function found by the sign changes between samples.
+fn sampled_roots(f: &dyn Fn(f32) -> f32) -> Vec<f32> {
+    let mut roots = Vec::new();
+    let mut t0 = 0.0;
+    let mut f0 = f(t0);
+    for i in 1..=SAMPLES {
+        let t1 = i as f32 / SAMPLES as f32;
+        let f1 = f(t1);
+        if f0 * f1 <= 0.0 {
+            let (mut lo, mut hi) = (t0, t1);
+            for _ in 0..BISECTION_STEPS {
+                let mid = (lo + hi) / 2.0;
+                if (f(mid) <= 0.0) == (f0 <= 0.0) {
+                    lo = mid;
+                } else {
+                    hi = mid;
+                }
+            }
+            roots.push((lo + hi) / 2.0);
+        }
+        t0 = t1;
+        f0 = f1;
+    }
+    roots
+}
+
+/// Whether the point is in the join between `a` and `b`. It uses the same geometry as the outline.
+fn join_contains(join: &Join, a: &PathItem, b: &PathItem, point: Point, half: f32) -> bool {
+    let center = b.left_point();
+    if let Join::Round = join {
+        return (point - center).norm() <= half;
+    }
+    let (a, b) = (join_edge(a, false), join_edge(b, true));
+    [
+        join_region(join, &a, &b, half),
+        join_region(join, &b.flip(), &a.flip(), half),
+    ]
+    .iter()
+    .flatten()
+    .any(|region| path_winding(region, point) != 0)
+}
+
+/// The path item itself, or its tangent line at the joint if the outline can't offset it.
+fn join_edge(pi: &PathItem, starts_at_joint: bool) -> PathItem {
+    match pi {
+        PathItem::Cubic(_) if starts_at_joint => {
+            let p = pi.left_point();
+            PathItem::Line(Line([p, p + tangent(pi, 0.0)]))
+        }
+        PathItem::Cubic(_) => {
+            let p = pi.right_point();
+            PathItem::Line(Line([p - tangent(pi, 1.0), p]))
+        }
+        _ => pi.clone(),
+    }
+}
+
+/// Whether the point is in the cap at `end` whose outward direction is `u`.
+fn cap_contains(cap: &Cap, end: Point, u: Point, point: Point, half: f32) -> bool {
+    let d = point - end;
+    match cap {
+        Cap::Round => d.norm() <= half,
+        Cap::Butt => false,
+        Cap::Square => {
+            let s = d.dot(&u);
+            0.0 <= s && s <= half && d.cross(&u).abs() <= half
+        }
+    }
+}
+
+#[test]
+fn test() {
+    use crate::fill_rule::{EvenOdd, InverseNonZero, NonZero};
+    use crate::path_builder::PathBuilder;
+    use crate::primitives;
+
+    let p = |x: f32, y: f32| Point::from((x, y));
+
+    let circle = primitives::circle(0.0, 0.0, 10.0);
+    assert!(circle.contains(p(9.9, 0.5), NonZero));
+    assert!(!circle.contains(p(7.2, 7.2), NonZero));
+    assert!(circle.contains(p(7.2, 7.2), InverseNonZero));
+    assert_eq!(path_winding(&circle, p(0.0, 0.0)).abs(), 1);
+
+    let mut donut = primitives::circle(0.0, 0.0, 10.0);
+    donut.merge(&primitives::circle(0.0, 0.0, 5.0));
+    assert!(donut.contains(p(1.0, 1.0), NonZero));
+    assert!(!donut.contains(p(1.0, 1.0), EvenOdd));
+    assert!(donut.contains(p(7.0, 0.0), EvenOdd));
+
+    // The control points are far outside of the curve.
+    let mut pb = PathBuilder::new();
+    pb.move_to(0.0, 0.0);
+    pb.cubic(0.0, 20.0, 20.0, 20.0, 20.0, 0.0);
+    let bump = pb.end();
+    assert!(bump.contains(p(10.0, 14.9), NonZero));
+    assert!(!bump.contains(p(10.0, 15.1), NonZero));
+    assert!(bump.stroke_contains(p(10.0, 16.0), 2.5, &Join::Bevel, &Cap::Butt));
+    assert!(!bump.stroke_contains(p(10.0, 17.0), 2.5, &Join::Bevel, &Cap::Butt));
+    assert!(!bump.stroke_contains(p(0.0, -1.0), 4.0, &Join::Bevel, &Cap::Butt));
+    assert!(bump.stroke_contains(p(0.0, -1.0), 4.0, &Join::Bevel, &Cap::Square));
+    assert!(bump.stroke_contains(p(0.0, -1.0), 4.0, &Join::Bevel, &Cap::Round));
+
+    let square = primitives::rect(0.0, 0.0, 10.0, 10.0);
+    assert!(square.stroke_contains(p(5.0, 0.9), 2.0, &Join::Bevel, &Cap::Butt));
+    assert!(!square.stroke_contains(p(5.0, 1.1), 2.0, &Join::Bevel, &Cap::Butt));
+    assert!(square.stroke_contains(p(-0.6, -0.3), 2.0, &Join::Bevel, &Cap::Butt));
+    assert!(!square.stroke_contains(p(-0.9, -0.9), 2.0, &Join::Bevel, &Cap::Butt));
+    assert!(square.stroke_contains(p(-0.9, -0.9), 2.0, &Join::Miter(2.0), &Cap::Butt));
+    assert!(!square.stroke_contains(p(-0.9, -0.9), 2.0, &Join::Miter(1.0), &Cap::Butt));
+    assert!(!square.stroke_contains(p(-0.9, -0.9), 2.0, &Join::Round, &Cap::Butt));
+
+    // Sharp joints agree with the outline for every join type.
+    let mut pb = PathBuilder::new();
+    pb.move_to(0.0, 0.0);
+    pb.line_to(10.0, 0.0);
+    pb.line_to(0.0, 3.0);
+    let sharp = pb.end();
+    let mut pb = PathBuilder::new();
+    pb.move_to(0.0, 10.0);
+    pb.arc(
+        10.0,
+        10.0,
+        10.0,
+        std::f32::consts::PI,
+        1.5 * std::f32::consts::PI,
+    );
+    pb.line_to(0.0, 2.0);
+    let curved = pb.end();
+    for path in [sharp, curved] {
+        for join in [
+            Join::Round,
+            Join::Bevel,
+            Join::Miter(4.0),
+            Join::Miter(20.0),
+            Join::MiterClip(2.0),
+            Join::Arcs(2.0),
+            Join::Arcs(20.0),
+        ] {
+            let outline = crate::path_outline::path_outline(&path, 1.0, &join, &Cap::Butt);
+            for i in 0..80 {
+                for j in 0..60 {
+                    let q = p(i as f32 * 0.2 - 2.0 + 0.0123, j as f32 * 0.2 - 2.0 + 0.0371);
+                    assert_eq!(
+                        path.stroke_contains(q, 2.0, &join, &Cap::Butt),
+                        outline.contains(q, NonZero),
+                        "{:?} {:?}",
+                        join,
+                        q
+                    );
+                }
+            }
+        }
+    }
+
+    // A cubic with a tiny loop at its top. Near the loop, feet of perpendiculars gather
+    // within a short range of the parameter, and the stroke is its distance field.
+    let mut pb = PathBuilder::new();
+    pb.move_to(0.0, 0.0);
+    pb.cubic(1001.0, 500.0, -1.0, 500.0, 1000.0, 0.0);
+    let looped = pb.end();
+    let distance = |q: Point| {
+        (0..=10000)
+            .map(|i| {
+                let b = pos(&looped.0[0], i as f32 / 10000.0);
+                (q - b).norm()
+            })
+            .fold(f32::MAX, f32::min)
+    };
+    for i in 0..30 {
+        for j in 0..30 {
+            let q = p(497.0 + i as f32 * 0.2, 372.0 + j as f32 * 0.2);
+            let d = distance(q);
+            if (d - 1.0).abs() < 0.01 {
+                continue;
+            }
+            assert_eq!(
+                looped.stroke_contains(q, 2.0, &Join::Bevel, &Cap::Butt),
+                d <= 1.0,
+                "{:?}",
+                q
+            );
+        }
+    }
+}
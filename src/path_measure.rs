@@ -0,0 +1,220 @@
+//! Measure lengths of a path.
+
+use crate::models::Line;
+use crate::path::{Path, PathItem};
+use crate::path_flatten::Flatten;
+use crate::point::Point;
+
+/// A flattened contour with cumulative lengths.
+#[derive(Debug, Clone)]
+struct Contour {
+    points: Vec<Point>,
+    /// `lengths[i]` is the length from `points[0]` to `points[i]`.
+    lengths: Vec<f32>,
+}
+
+impl Contour {
+    fn length(&self) -> f32 {
+        *self.lengths.last().unwrap()
+    }
+
+    /// Index of the segment that contains `distance` and the rate in it.
+    fn locate(&self, distance: f32) -> (usize, f32) {
+        let i = self
+            .lengths
+            .partition_point(|l| *l < distance)
+            .clamp(1, self.points.len() - 1);
+        let (l1, l2) = (self.lengths[i - 1], self.lengths[i]);
+        (i, ((distance - l1) / (l2 - l1)).clamp(0.0, 1.0))
+    }
+}
+
+/// Measures lengths, positions and tangents along a [`Path`].
+///
+/// The path is flattened once with the given tolerance and all measurements are made on the flattened cache.
+/// Contours are concatenated in order, so a distance runs through the first contour and then the next one.
+///
+/// # Examples
+///
+/// ```
+/// use nanachi::{path_measure::PathMeasure, primitives::rect};
+/// let measure = PathMeasure::new(&rect(0.0, 0.0, 100.0, 50.0), 0.1);
+/// assert_eq!(measure.length(), 300.0);
+/// let (pos, tangent) = measure.position_and_tangent_at(75.0).unwrap();
+/// assert_eq!((pos.x(), pos.y()), (25.0, 50.0));
+/// assert_eq!((tangent.x(), tangent.y()), (1.0, 0.0));
+/// ```
+#[derive(Debug, Clone)]
+pub struct PathMeasure {
+    contours: Vec<Contour>,
+}
+
+impl PathMeasure {
+    /// Create [`PathMeasure`].
+    ///
+    /// `tolerance` is the tolerance for flattening curves.
+    pub fn new(path: &Path, tolerance: f32) -> PathMeasure {
+        let mut contours = Vec::new();
+        let mut points: Vec<Point> = Vec::new();
+        let mut push = |points: &mut Vec<Point>| {
+            if 2 <= points.len() {
+                let mut lengths = Vec::with_capacity(points.len());
+                let mut length = 0.0;
+                lengths.push(length);
+                for w in points.windows(2) {
+                    length += (w[1] - w[0]).norm();
+                    lengths.push(length);
+                }
+                contours.push(Contour {
+                    points: std::mem::take(points),
+                    lengths,
+                });
+            }
+            points.clear();
+        };
+        for pi in Flatten::new(path.0.iter(), tolerance) {
+            match pi {
+                PathItem::Line(Line([a, b])) => {
+                    if points.last() != Some(&a) {
+                        push(&mut points);
+                        points.push(a);
+                    }
+                    if a != b {
+                        points.push(b);
+                    }
+                }
+                PathItem::CloseAndJump | PathItem::Jump => push(&mut points),
+                _ => unreachable!(),
+            }
+        }
+        push(&mut points);
+        PathMeasure { contours }
+    }
+
+    /// Total length of the path.
+    pub fn length(&self) -> f32 {
+        self.contours.iter().map(|c| c.length()).sum()
+    }
+
+    /// Lengths of each contour.
+    pub fn contour_lengths(&self) -> Vec<f32> {
+        self.contours.iter().map(|c| c.length()).collect()
+    }
+
+    fn contour_at(&self, mut distance: f32) -> Option<(&Contour, f32)> {
+        for contour in self.contours.iter() {
+            if distance <= contour.length() {
+                return Some((contour, distance));
+            }
+            distance -= contour.length();
+        }
+        None
+    }
+
+    /// Return the position and the unit tangent vector at the `distance` from the start.
+    ///
+    /// Returns `None` if `distance` is out of the path.
+    pub fn position_and_tangent_at(&self, distance: f32) -> Option<(Point, Point)> {
+        if distance < 0.0 {
+            return None;
+        }
+        let (contour, distance) = self.contour_at(distance)?;
+        let (i, t) = contour.locate(distance);
+        let (a, b) = (contour.points[i - 1], contour.points[i]);
+        Some((a.lerp(b, t), (b - a).unit()))
+    }
+
+    /// Extract the part of the path between `start` and `end` distances.
+    ///
+    /// The result is a polyline of the flattened cache; curves are not kept as curves.
+    pub fn sub_path(&self, start: f32, end: f32) -> Path {
+        let mut pis = Vec::new();
+        let mut offset = 0.0;
+        for contour in self.contours.iter() {
+            let (s, e) = (start - offset, end - offset);
+            offset += contour.length();
+            if e <= 0.0 || contour.length() <= s || e <= s {
+                continue;
+            }
+            let (i, t) = contour.locate(s.max(0.0));
+            let (j, u) = contour.locate(e.min(contour.length()));
+            let mut last = contour.points[i - 1].lerp(contour.points[i], t);
+            for p in contour.points[i..j].iter() {
+                if last != *p {
+                    pis.push(PathItem::Line(Line([last, *p])));
+                }
+                last = *p;
+            }
+            let p = contour.points[j - 1].lerp(contour.points[j], u);
+            if last != p {
+                pis.push(PathItem::Line(Line([last, p])));
+            }
+            pis.push(PathItem::Jump);
+        }
+        pis.pop();
+        Path::new(pis)
+    }
+}
+
+#[test]
+fn test() {
+    use crate::primitives::{arc, rect};
+    use std::f32::consts::PI;
+
+    let close = |a: f32, b: f32| (a - b).abs() < 1e-3;
+    let close_point = |a: Point, b: (f32, f32)| close(a.x(), b.0) && close(a.y(), b.1);
+
+    // A line.
+    let measure = PathMeasure::new(
+        &Path::from_points(&vec![Point([0.0, 0.0]), Point([30.0, 40.0])], false),
+        0.1,
+    );
+    assert_eq!(measure.length(), 50.0);
+    let (pos, tangent) = measure.position_and_tangent_at(25.0).unwrap();
+    assert!(close_point(pos, (15.0, 20.0)) && close_point(tangent, (0.6, 0.8)));
+
+    // An arc is measured within the flattening tolerance.
+    let measure = PathMeasure::new(&arc(0.0, 0.0, 10.0, 0.0, PI), 0.01);
+    assert!((measure.length() - 10.0 * PI).abs() < 0.05);
+    let (pos, tangent) = measure.position_and_tangent_at(5.0 * PI).unwrap();
+    assert!((pos.x().abs() < 0.05) && (pos.y().abs() - 10.0).abs() < 0.05);
+    assert!((tangent.x().abs() - 1.0).abs() < 0.01);
+
+    // Two contours: a 10x10 square drawn counterclockwise on screen from the origin and a line of the length 5.
+    let mut path = rect(0.0, 0.0, 10.0, 10.0);
+    path.merge(&Path::from_points(
+        &vec![Point([20.0, 0.0]), Point([20.0, 5.0])],
+        false,
+    ));
+    let measure = PathMeasure::new(&path, 0.1);
+    assert_eq!(measure.contour_lengths(), vec![40.0, 5.0]);
+    assert_eq!(measure.length(), 45.0);
+
+    // Distances run through the first contour and then the second one.
+    let (pos, tangent) = measure.position_and_tangent_at(15.0).unwrap();
+    assert!(close_point(pos, (5.0, 10.0)) && close_point(tangent, (1.0, 0.0)));
+    let (pos, tangent) = measure.position_and_tangent_at(42.0).unwrap();
+    assert!(close_point(pos, (20.0, 2.0)) && close_point(tangent, (0.0, 1.0)));
+
+    // The boundary belongs to the end of the first contour.
+    let (pos, tangent) = measure.position_and_tangent_at(40.0).unwrap();
+    assert!(close_point(pos, (0.0, 0.0)) && close_point(tangent, (-1.0, 0.0)));
+    let (pos, _) = measure.position_and_tangent_at(0.0).unwrap();
+    assert!(close_point(pos, (0.0, 0.0)));
+    let (pos, _) = measure.position_and_tangent_at(45.0).unwrap();
+    assert!(close_point(pos, (20.0, 5.0)));
+    assert!(measure.position_and_tangent_at(-0.1).is_none());
+    assert!(measure.position_and_tangent_at(45.1).is_none());
+
+    // Sub paths are clamped to the path and split at contour boundaries.
+    let lengths = |path: &Path| PathMeasure::new(path, 0.1).contour_lengths();
+    assert_eq!(lengths(&measure.sub_path(0.0, 45.0)), vec![40.0, 5.0]);
+    assert_eq!(lengths(&measure.sub_path(-10.0, 100.0)), vec![40.0, 5.0]);
+    assert_eq!(lengths(&measure.sub_path(35.0, 43.0)), vec![5.0, 3.0]);
+    let sub = measure.sub_path(5.0, 15.0);
+    assert_eq!(sub.bounds(), Some([0.0, 5.0, 5.0, 10.0]));
+    assert!(measure.sub_path(0.0, 0.0).0.is_empty());
+    assert!(measure.sub_path(45.0, 45.0).0.is_empty());
+    assert!(measure.sub_path(50.0, 60.0).0.is_empty());
+    assert!(measure.sub_path(20.0, 10.0).0.is_empty());
+}
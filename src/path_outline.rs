@@ -2,14 +2,27 @@
 
 use crate::models::{Arc, Ellipse, Line, Quad};
 use crate::path::{Path, PathItem};
+use crate::path_flatten::Flatten;
 use crate::point::Point;
+use std::f32::consts::{PI, TAU};
 
 /// Join types for [`path_outline`]
+///
+/// The limits of miter joins are ratios of the miter length to the stroke width like SVG `stroke-miterlimit`.
 #[derive(Debug, Clone)]
 pub enum Join {
     Round,
     Bevel,
+    /// Miter join which falls back to bevel if the miter exceeds the limit.
+    ///
+    /// The limit used to be the distance from the joint to the miter tip.
+    /// It is now the ratio to the stroke width, so a limit of distance `d` on a stroke of `width` becomes `d / width`.
     Miter(f32),
+    /// Miter join which is clipped at the limit instead of falling back to bevel (SVG 2 `miter-clip`).
+    MiterClip(f32),
+    /// Outer edges are extended with arcs of their curvatures until they meet (SVG 2 `arcs`).
+    /// Straight edges are extended as lines. It is clipped like [`Join::MiterClip`] at the limit.
+    Arcs(f32),
 }
 
 /// Cap types for [`path_outline`]
@@ -20,80 +33,239 @@ pub enum Cap {
     Square,
 }
 
+/// Alignment of the stroke to closed subpaths for [`path_outline_aligned`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StrokeAlign {
+    Center,
+    Inside,
+    Outside,
+}
+
 /// Create a path that is outline of given path.
 pub fn path_outline(path: &Path, width: f32, join: &Join, cap: &Cap) -> Path {
     assert_ne!(width, 0.0);
     let mut res = Vec::with_capacity(path.0.len() * 4);
-    let mut tmp = Vec::with_capacity(4);
     for (pis, closed) in path.continuations() {
         if closed {
-            // outer
-            let mut it = pis.iter().filter(|pi| !pi.is_zero());
-            let m = res.len();
-            if let Some(pi) = it.next() {
-                path_item_offset(&mut res, pi, width);
-            } else {
-                continue;
-            }
-            let first = res[m].left_point();
-            for pi in it {
-                path_item_offset(&mut tmp, pi, width);
-                let s = res.last().unwrap().right_point();
-                add_join(&mut res, join, pi.left_point(), s, tmp[0].left_point());
-                res.extend(tmp.drain(..));
-            }
-            let s = res.last().unwrap().right_point();
-            add_join(&mut res, join, pis[0].left_point(), s, first);
-            res.push(PathItem::CloseAndJump);
+            add_closed_outline(&mut res, pis, width, width, join);
+        } else {
+            add_open_outline(&mut res, pis, width, join, cap);
+        }
+    }
+    Path(res)
+}
 
-            // inner
-            let mut it = pis
-                .iter()
-                .rev()
-                .filter(|pi| !pi.is_zero())
-                .map(|pi| pi.flip());
-            let m = res.len();
-            path_item_offset(&mut res, &it.next().unwrap(), width);
-            let first = res[m].left_point();
-            for pi in it {
-                path_item_offset(&mut tmp, &pi, width);
-                let s = res.last().unwrap().right_point();
-                add_join(&mut res, join, pi.left_point(), s, tmp[0].left_point());
-                res.extend(tmp.drain(..));
+/// Create a path that is outline of given path with the stroke alignment.
+///
+/// Unlike [`path_outline`], `width` is the full width of the stroke.
+/// [`StrokeAlign::Inside`] and [`StrokeAlign::Outside`] put the whole stroke on one side of closed subpaths.
+/// Open subpaths are always centered.
+pub fn path_outline_aligned(
+    path: &Path,
+    width: f32,
+    align: StrokeAlign,
+    join: &Join,
+    cap: &Cap,
+) -> Path {
+    assert_ne!(width, 0.0);
+    let half = width / 2.0;
+    let mut res = Vec::with_capacity(path.0.len() * 4);
+    for (pis, closed) in path.continuations() {
+        if !closed {
+            add_open_outline(&mut res, pis, half, join, cap);
+            continue;
+        }
+        let (left, right) = match align {
+            StrokeAlign::Center => (half, half),
+            _ => {
+                // In the y-down coordinates, the inside of a subpath with positive area is on the right side.
+                let inside_is_right = 0.0 < signed_area(pis);
+                if (align == StrokeAlign::Inside) == inside_is_right {
+                    (0.0, width)
+                } else {
+                    (width, 0.0)
+                }
             }
-            let s = res.last().unwrap().right_point();
-            add_join(&mut res, join, pis[0].left_point(), s, first);
+        };
+        add_closed_outline(&mut res, pis, left, right, join);
+    }
+    Path(res)
+}
+
+/// Outline of a closed subpath with offsets `left` and `right` of the path direction.
+fn add_closed_outline(
+    res: &mut Vec<PathItem>,
+    pis: &[PathItem],
+    left: f32,
+    right: f32,
+    join: &Join,
+) {
+    if pis.iter().all(|pi| pi.is_zero()) {
+        return;
+    }
+    let center = pis[0].left_point();
+    // outer
+    let it = pis.iter().filter(|pi| !pi.is_zero()).cloned();
+    let trim_inner = left != right;
+    add_offset_loop(res, it, center, left, join, trim_inner);
+    // inner
+    let it = pis
+        .iter()
+        .rev()
+        .filter(|pi| !pi.is_zero())
+        .map(|pi| pi.flip());
+    add_offset_loop(res, it, center, right, join, trim_inner);
+}
+
+fn add_offset_loop(
+    res: &mut Vec<PathItem>,
+    mut it: impl Iterator<Item = PathItem>,
+    center: Point,
+    width: f32,
+    join: &Join,
+    trim_inner: bool,
+) {
+    let m = res.len();
+    if let Some(pi) = it.next() {
+        path_item_offset(res, &pi, width);
+    } else {
+        return;
+    }
+    let first = res[m].clone();
+    let mut tmp = Vec::with_capacity(4);
+    for pi in it {
+        path_item_offset(&mut tmp, &pi, width);
+        add_join(res, join, pi.left_point(), &tmp[0], trim_inner);
+        res.append(&mut tmp);
+    }
+    add_join(res, join, center, &first, trim_inner);
+    res.push(PathItem::CloseAndJump);
+}
+
+fn add_open_outline(res: &mut Vec<PathItem>, pis: &[PathItem], width: f32, join: &Join, cap: &Cap) {
+    let mut it = pis.iter().filter(|pi| !pi.is_zero());
+    let m = res.len();
+    if let Some(pi) = it.next() {
+        path_item_offset(res, pi, width);
+    } else {
+        return;
+    }
+    let first = res[m].left_point();
+    let mut tmp = Vec::with_capacity(4);
+    for pi in it {
+        path_item_offset(&mut tmp, pi, width);
+        add_join(res, join, pi.left_point(), &tmp[0], false);
+        res.append(&mut tmp);
+    }
+    let mut it = pis
+        .iter()
+        .rev()
+        .filter(|pi| !pi.is_zero())
+        .map(|pi| pi.flip());
+    path_item_offset(&mut tmp, &it.next().unwrap(), width);
+    let s = res.last().unwrap().right_point();
+    add_cap(res, cap, s, tmp[0].left_point());
+    res.append(&mut tmp);
+    for pi in it {
+        path_item_offset(&mut tmp, &pi, width);
+        add_join(res, join, pi.left_point(), &tmp[0], false);
+        res.append(&mut tmp);
+    }
+    let s = res.last().unwrap().right_point();
+    add_cap(res, cap, s, first);
+    res.push(PathItem::CloseAndJump);
+}
+
+pub fn path_offset(path: &Path, width: f32, join: &Join) -> Path {
+    let mut res = Vec::with_capacity(path.0.len() * 2);
+    for (pis, closed) in path.continuations() {
+        if !closed {
+            todo!("path_offset not supports unclosed path");
+        }
+        let it = pis.iter().filter(|pi| !pi.is_zero()).cloned();
+        add_offset_loop(&mut res, it, pis[0].left_point(), width, join, false);
+    }
+    Path(res)
+}
+
+/// Positions of width stops for [`path_outline_variable`]. Widths are linearly interpolated between stops.
+#[derive(Debug, Clone)]
+pub enum WidthStops {
+    /// `(distance along the subpath, width)` pairs.
+    Length(Vec<(f32, f32)>),
+    /// `(t, width)` pairs where `t` is the fraction of the length of the subpath from 0 to 1.
+    Normalized(Vec<(f32, f32)>),
+}
+
+impl WidthStops {
+    fn width_at(&self, distance: f32, length: f32) -> f32 {
+        let (stops, x) = match self {
+            WidthStops::Length(stops) => (stops, distance),
+            WidthStops::Normalized(stops) => (stops, distance / length.max(f32::EPSILON)),
+        };
+        let i = stops.partition_point(|(t, _)| *t < x);
+        match (stops.get(i.wrapping_sub(1)), stops.get(i)) {
+            (Some((t1, w1)), Some((t2, w2))) => w1 + (w2 - w1) * ((x - t1) / (t2 - t1)),
+            (Some((_, w)), None) | (None, Some((_, w))) => *w,
+            (None, None) => 0.0,
+        }
+    }
+}
+
+/// Create a path that is the outline of a stroke whose width varies along the path.
+///
+/// Unlike [`path_outline`], the widths in `stops` are the full widths of the stroke.
+/// The path is flattened with `tolerance` and `join` and `cap` are applied like [`path_outline`].
+pub fn path_outline_variable(
+    path: &Path,
+    stops: &WidthStops,
+    join: &Join,
+    cap: &Cap,
+    tolerance: f32,
+) -> Path {
+    let mut res = Vec::new();
+    for (pis, closed) in path.continuations() {
+        let mut points = flattened_points(pis, tolerance);
+        if closed && 2 <= points.len() && points[0] == points[points.len() - 1] {
+            points.pop();
+        }
+        if points.len() < 2 {
+            continue;
+        }
+        let mut distance = 0.0;
+        let mut distances = vec![0.0];
+        for w in points.windows(2) {
+            distance += (w[1] - w[0]).norm();
+            distances.push(distance);
+        }
+        let length = if closed {
+            distance + (points[0] - points[points.len() - 1]).norm()
+        } else {
+            distance
+        };
+        let halves: Vec<f32> = distances
+            .iter()
+            .map(|d| stops.width_at(*d, length) / 2.0)
+            .collect();
+
+        let mut points_rev = points.clone();
+        points_rev.reverse();
+        let mut halves_rev = halves.clone();
+        halves_rev.reverse();
+        if closed {
+            polyline_offset(&mut res, &points, &halves, true, join);
+            res.push(PathItem::CloseAndJump);
+            polyline_offset(&mut res, &points_rev, &halves_rev, true, join);
             res.push(PathItem::CloseAndJump);
         } else {
-            let mut it = pis.iter().filter(|pi| !pi.is_zero());
             let m = res.len();
-            if let Some(pi) = it.next() {
-                path_item_offset(&mut res, pi, width);
-            } else {
-                continue;
-            }
+            polyline_offset(&mut res, &points, &halves, false, join);
             let first = res[m].left_point();
-            for pi in it {
-                path_item_offset(&mut tmp, pi, width);
-                let s = res.last().unwrap().right_point();
-                add_join(&mut res, join, pi.left_point(), s, tmp[0].left_point());
-                res.extend(tmp.drain(..));
-            }
-            let mut it = pis
-                .iter()
-                .rev()
-                .filter(|pi| !pi.is_zero())
-                .map(|pi| pi.flip());
-            path_item_offset(&mut tmp, &it.next().unwrap(), width);
+            let mut tmp = Vec::new();
+            polyline_offset(&mut tmp, &points_rev, &halves_rev, false, join);
             let s = res.last().unwrap().right_point();
             add_cap(&mut res, cap, s, tmp[0].left_point());
-            res.extend(tmp.drain(..));
-            for pi in it {
-                path_item_offset(&mut tmp, &pi, width);
-                let s = res.last().unwrap().right_point();
-                add_join(&mut res, join, pi.left_point(), s, tmp[0].left_point());
-                res.extend(tmp.drain(..));
-            }
+            res.extend(tmp);
             let s = res.last().unwrap().right_point();
             add_cap(&mut res, cap, s, first);
             res.push(PathItem::CloseAndJump);
@@ -102,68 +274,440 @@ pub fn path_outline(path: &Path, width: f32, join: &Join, cap: &Cap) -> Path {
     Path(res)
 }
 
-pub fn path_offset(path: &Path, width: f32, join: &Join) -> Path {
-    let mut res = Vec::with_capacity(path.0.len() * 2);
-    let mut tmp = Vec::with_capacity(4);
-    for (pis, closed) in path.continuations() {
-        if !closed {
-            todo!("path_offset not supports unclosed path");
+/// Offset lines of a polyline to the left side by `halves` at each point, connected with joins.
+fn polyline_offset(
+    res: &mut Vec<PathItem>,
+    points: &[Point],
+    halves: &[f32],
+    closed: bool,
+    join: &Join,
+) {
+    let n = points.len();
+    let segments = if closed { n } else { n - 1 };
+    let offset = |i: usize| {
+        let (j, k) = (i % n, (i + 1) % n);
+        let u = (points[k] - points[j]).unit();
+        let d = Point::from((u.y(), -u.x()));
+        Line([points[j] + d * halves[j], points[k] + d * halves[k]])
+    };
+    let first = offset(0);
+    res.push(PathItem::Line(first.clone()));
+    for (i, point) in points.iter().enumerate().take(segments).skip(1) {
+        let line = offset(i);
+        let line = PathItem::Line(line);
+        add_join(res, join, *point, &line, false);
+        res.push(line);
+    }
+    if closed {
+        add_join(res, join, points[0], &PathItem::Line(first), false);
+    }
+}
+
+fn flattened_points(pis: &[PathItem], tolerance: f32) -> Vec<Point> {
+    let mut points: Vec<Point> = Vec::new();
+    for pi in Flatten::new(pis.iter(), tolerance) {
+        if let PathItem::Line(Line([a, b])) = pi {
+            if points.is_empty() {
+                points.push(a);
+            }
+            if points.last() != Some(&b) {
+                points.push(b);
+            }
         }
-        let mut it = pis.iter().filter(|pi| !pi.is_zero());
-        let m = res.len();
-        if let Some(pi) = it.next() {
-            path_item_offset(&mut res, pi, width);
-        } else {
-            continue;
+    }
+    points
+}
+
+/// Pen shapes for [`path_outline_calligraphic`].
+#[derive(Debug, Clone)]
+pub enum Pen {
+    /// An elliptical pen rotated by `angle`.
+    Ellipse {
+        radius_x: f32,
+        radius_y: f32,
+        angle: f32,
+    },
+    /// A flat nib of `width` rotated by `angle`.
+    Nib { width: f32, angle: f32 },
+}
+
+impl Pen {
+    /// Vertices of the convex pen polygon centered at the origin.
+    fn polygon(&self, tolerance: f32) -> Vec<Point> {
+        match self {
+            Pen::Ellipse {
+                radius_x,
+                radius_y,
+                angle,
+            } => {
+                let r = radius_x.max(*radius_y).max(tolerance);
+                let n = (TAU / (1.0 - tolerance / r).max(-1.0).acos())
+                    .ceil()
+                    .clamp(8.0, 256.0) as usize;
+                (0..n)
+                    .map(|i| {
+                        let a = i as f32 / n as f32 * TAU;
+                        Point::from((radius_x * a.cos(), radius_y * a.sin())).rotate(*angle)
+                    })
+                    .collect()
+            }
+            Pen::Nib { width, angle } => {
+                let v = Point::from_angle(*angle) * (width / 2.0);
+                vec![v, v * -1.0]
+            }
         }
-        let first = res[m].left_point();
-        for pi in it {
-            path_item_offset(&mut tmp, pi, width);
-            let s = res.last().unwrap().right_point();
-            add_join(&mut res, join, pi.left_point(), s, tmp[0].left_point());
-            res.extend(tmp.drain(..));
+    }
+}
+
+/// Create a fillable path traced by a calligraphic pen moving along the path.
+///
+/// The result is the union of the convex hulls of the pen at both ends of each flattened line,
+/// all in the same direction, so it should be filled with [`NonZero`](crate::fill_rule::NonZero).
+pub fn path_outline_calligraphic(path: &Path, pen: &Pen, tolerance: f32) -> Path {
+    let pen = pen.polygon(tolerance);
+    let mut res = Vec::new();
+    for (pis, _) in path.continuations() {
+        let points = flattened_points(pis, tolerance);
+        for w in points.windows(2) {
+            let mut hull_points: Vec<Point> =
+                pen.iter().flat_map(|v| [w[0] + *v, w[1] + *v]).collect();
+            let hull = convex_hull(&mut hull_points);
+            if 3 <= hull.len() {
+                let mut hull = hull;
+                hull.push(hull[0]);
+                res.extend(Path::from_points(&hull, true).0);
+            }
         }
-        let s = res.last().unwrap().right_point();
-        add_join(&mut res, join, pis[0].left_point(), s, first);
-        res.push(PathItem::CloseAndJump);
     }
     Path(res)
 }
 
-fn add_join(pis: &mut Vec<PathItem>, join: &Join, center: Point, start: Point, end: Point) {
+/// Signed area of a closed subpath. It is positive if the subpath is clockwise in the y-down coordinates.
+fn signed_area(pis: &[PathItem]) -> f32 {
+    let bounds = match Path::new(pis.to_vec()).bounds() {
+        Some(b) => b,
+        None => return 0.0,
+    };
+    let tolerance = ((bounds[1] - bounds[0]).max(bounds[3] - bounds[2]) / 100.0).max(1e-3);
+    let points = flattened_points(pis, tolerance);
+    points
+        .iter()
+        .zip(points.iter().cycle().skip(1))
+        .map(|(a, b)| a.cross(b))
+        .sum::<f32>()
+        / 2.0
+}
+
+/// Convex hull with Andrew's monotone chain algorithm. The hull is counterclockwise in the y-up coordinates.
+fn convex_hull(points: &mut [Point]) -> Vec<Point> {
+    points.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+    let mut hull: Vec<Point> = Vec::with_capacity(points.len() + 1);
+    for pass in 0..2 {
+        let start = hull.len();
+        let it: Box<dyn Iterator<Item = &Point>> = if pass == 0 {
+            Box::new(points.iter())
+        } else {
+            Box::new(points.iter().rev())
+        };
+        for p in it {
+            while start + 2 <= hull.len()
+                && (hull[hull.len() - 1] - hull[hull.len() - 2]).cross(&(*p - hull[hull.len() - 2]))
+                    <= 0.0
+            {
+                hull.pop();
+            }
+            hull.push(*p);
+        }
+        hull.pop();
+    }
+    hull
+}
+
+/// Add a join between the last item of `pis` and `next` around `center`.
+///
+/// An inner turn is joined with a bevel, or trimmed with [`add_inner_join`] if `trim_inner` is `true`.
+fn add_join(
+    pis: &mut Vec<PathItem>,
+    join: &Join,
+    center: Point,
+    next: &PathItem,
+    trim_inner: bool,
+) {
+    let prev = pis.last().unwrap().clone();
+    let (start, end) = (prev.right_point(), next.left_point());
     if start == end {
         return;
     }
-    let mut bevel = || {
-        pis.push(PathItem::Line(Line([start, end])));
-    };
+    if point_is_right_side_of_line(start - center, end - center) {
+        if trim_inner {
+            add_inner_join(pis, &prev, next);
+        } else {
+            pis.push(PathItem::Line(Line([start, end])));
+        }
+        return;
+    }
     match join {
         Join::Round => {
-            if point_is_right_side_of_line(start - center, end - center) {
-                bevel();
-            } else {
-                pis.push(PathItem::Arc(Arc::from_points(center, start, end)));
-            }
+            pis.push(PathItem::Arc(Arc::from_points(center, start, end)));
         }
         Join::Bevel => {
-            bevel();
+            pis.push(PathItem::Line(Line([start, end])));
         }
-        Join::Miter(limit) => {
-            if point_is_right_side_of_line(start - center, end - center) {
-                bevel();
-            } else {
-                let p = intersect_line_and_line(
-                    start,
-                    start + Point::from((start.y() - center.y(), center.x() - start.x())),
-                    end,
-                    end + Point::from((center.y() - end.y(), end.x() - center.x())),
-                );
-                if ((p - center).norm() as f32) < *limit {
-                    pis.push(PathItem::Line(Line([start, p])));
-                    pis.push(PathItem::Line(Line([p, end])));
-                } else {
-                    bevel();
+        Join::Miter(limit) => add_miter(pis, center, start, end, *limit, false),
+        Join::MiterClip(limit) => add_miter(pis, center, start, end, *limit, true),
+        Join::Arcs(limit) => {
+            if !add_arcs(pis, center, &prev, next, *limit) {
+                add_miter(pis, center, start, end, *limit, true);
+            }
+        }
+    }
+}
+
+/// The outer join between `prev` and `next` of the offset `width`, as a closed path around the joint.
+///
+/// Returns `None` if the turn is to the other side. It is used by hit testing to match the outline.
+pub(crate) fn join_region(
+    join: &Join,
+    prev: &PathItem,
+    next: &PathItem,
+    width: f32,
+) -> Option<Path> {
+    let center = next.left_point();
+    let mut pis = Vec::with_capacity(4);
+    path_item_offset(&mut pis, prev, width);
+    let mut next_offset = Vec::with_capacity(2);
+    path_item_offset(&mut next_offset, next, width);
+    let (start, end) = (
+        pis.last().unwrap().right_point(),
+        next_offset[0].left_point(),
+    );
+    if start == end || point_is_right_side_of_line(start - center, end - center) {
+        return None;
+    }
+    let m = pis.len();
+    add_join(&mut pis, join, center, &next_offset[0], false);
+    let mut res = vec![PathItem::Line(Line([center, start]))];
+    res.extend(pis.drain(m..));
+    res.push(PathItem::Line(Line([end, center])));
+    res.push(PathItem::CloseAndJump);
+    Some(Path(res))
+}
+
+/// Join on the inner side of the turn.
+///
+/// Lines are trimmed at their intersection so that one-sided strokes don't overshoot the adjacent edges.
+/// Centered strokes don't need it since the other side covers the overshoot.
+/// The trimmed part of `next` is cancelled by a line going back on it.
+fn add_inner_join(pis: &mut Vec<PathItem>, prev: &PathItem, next: &PathItem) {
+    let (start, end) = (prev.right_point(), next.left_point());
+    if let (PathItem::Line(Line([a, _])), PathItem::Line(Line([_, b]))) = (prev, next) {
+        let p = intersect_line_and_line(*a, start, end, *b);
+        let within = |p0: Point, p1: Point| {
+            let t = (p - p0).dot(&(p1 - p0));
+            0.0 <= t && t <= (p1 - p0).dot(&(p1 - p0))
+        };
+        if within(*a, start) && within(end, *b) {
+            *pis.last_mut().unwrap() = PathItem::Line(Line([*a, p]));
+            pis.push(PathItem::Line(Line([p, end])));
+            return;
+        }
+    }
+    pis.push(PathItem::Line(Line([start, end])));
+}
+
+fn add_miter(
+    pis: &mut Vec<PathItem>,
+    center: Point,
+    start: Point,
+    end: Point,
+    limit: f32,
+    clip: bool,
+) {
+    let (v1, v2) = (start - center, end - center);
+    let half = v1.norm().max(v2.norm());
+    let p = intersect_line_and_line(
+        start,
+        start + Point::from((v1.y(), -v1.x())),
+        end,
+        end + Point::from((v2.y(), -v2.x())),
+    );
+    // The ratio of the miter length to the stroke width is `|p - center| / half`.
+    if (p - center).norm() <= limit * half {
+        pis.push(PathItem::Line(Line([start, p])));
+        pis.push(PathItem::Line(Line([p, end])));
+        return;
+    }
+    if clip {
+        let (n1, n2) = (v1.unit(), v2.unit());
+        // Forward directions of the edges.
+        let (u1, u2) = (
+            Point::from((-n1.y(), n1.x())),
+            Point::from((-n2.y(), n2.x())),
+        );
+        let b = if (n1 + n2).norm() < 1e-6 {
+            u1
+        } else {
+            (n1 + n2).unit()
+        };
+        let l = limit * half;
+        let (d1, d2) = (v1.dot(&b), v2.dot(&b));
+        if d1.max(d2) < l {
+            let p1 = start + u1 * ((l - d1) / u1.dot(&b));
+            let p2 = end + u2 * ((l - d2) / u2.dot(&b));
+            pis.push(PathItem::Line(Line([start, p1])));
+            pis.push(PathItem::Line(Line([p1, p2])));
+            pis.push(PathItem::Line(Line([p2, end])));
+            return;
+        }
+    }
+    pis.push(PathItem::Line(Line([start, end])));
+}
+
+/// Add an `arcs` join. Returns `false` if the extended edges don't meet within the limit.
+fn add_arcs(
+    pis: &mut Vec<PathItem>,
+    center: Point,
+    prev: &PathItem,
+    next: &PathItem,
+    limit: f32,
+) -> bool {
+    let (start, end) = (prev.right_point(), next.left_point());
+    let half = (start - center).norm().max((end - center).norm());
+    let e1 = JoinEdge::new(prev, start, center);
+    let e2 = JoinEdge::new(next, end, center);
+    let best = e1
+        .intersections(&e2)
+        .into_iter()
+        .filter(|p| (*p - center).norm() <= limit * half)
+        .filter_map(|p| Some((p, e1.extend(start, p)?, e2.extend(p, end)?)))
+        .min_by(|a, b| {
+            let d = |p: Point| (p - start).norm() + (p - end).norm();
+            d(a.0).partial_cmp(&d(b.0)).unwrap()
+        });
+    if let Some((_, a, b)) = best {
+        pis.push(a);
+        pis.push(b);
+        true
+    } else {
+        false
+    }
+}
+
+/// An outer edge of the stroke extended beyond a join.
+enum JoinEdge {
+    Line {
+        point: Point,
+        direction: Point,
+    },
+    Circle {
+        center: Point,
+        radius: f32,
+        sign: f32,
+    },
+}
+
+impl JoinEdge {
+    fn new(pi: &PathItem, point: Point, center: Point) -> JoinEdge {
+        match pi {
+            PathItem::Arc(arc) if 0.0 < arc.radius => JoinEdge::Circle {
+                center: arc.center,
+                radius: arc.radius,
+                sign: (arc.angle2 - arc.angle1).signum(),
+            },
+            PathItem::Line(Line([a, b])) => JoinEdge::Line {
+                point,
+                direction: *b - *a,
+            },
+            _ => {
+                let n = point - center;
+                JoinEdge::Line {
+                    point,
+                    direction: Point::from((-n.y(), n.x())),
+                }
+            }
+        }
+    }
+
+    fn intersections(&self, other: &JoinEdge) -> Vec<Point> {
+        use JoinEdge::*;
+        match (self, other) {
+            (
+                Line {
+                    point: p1,
+                    direction: d1,
+                },
+                Line {
+                    point: p2,
+                    direction: d2,
+                },
+            ) => {
+                let det = d1.cross(d2);
+                if det.abs() < 1e-9 {
+                    return vec![];
+                }
+                vec![*p1 + *d1 * ((*p2 - *p1).cross(d2) / det)]
+            }
+            (Line { point, direction }, Circle { center, radius, .. })
+            | (Circle { center, radius, .. }, Line { point, direction }) => {
+                let f = *point - *center;
+                let a = direction.dot(direction);
+                let b = 2.0 * f.dot(direction);
+                let c = f.dot(&f) - radius * radius;
+                let disc = b * b - 4.0 * a * c;
+                if disc < 0.0 || a == 0.0 {
+                    return vec![];
+                }
+                let sq = disc.sqrt();
+                vec![
+                    *point + *direction * ((-b - sq) / (2.0 * a)),
+                    *point + *direction * ((-b + sq) / (2.0 * a)),
+                ]
+            }
+            (
+                Circle {
+                    center: c1,
+                    radius: r1,
+                    ..
+                },
+                Circle {
+                    center: c2,
+                    radius: r2,
+                    ..
+                },
+            ) => {
+                let v = *c2 - *c1;
+                let d = v.norm();
+                if d == 0.0 || r1 + r2 < d || d < (r1 - r2).abs() {
+                    return vec![];
                 }
+                let a = (r1 * r1 - r2 * r2 + d * d) / (2.0 * d);
+                let h = (r1 * r1 - a * a).max(0.0).sqrt();
+                let m = *c1 + v * (a / d);
+                let n = Point::from((-v.y(), v.x())) * (h / d);
+                vec![m + n, m - n]
+            }
+        }
+    }
+
+    /// The part of the edge from `from` to `to` in its direction.
+    fn extend(&self, from: Point, to: Point) -> Option<PathItem> {
+        match self {
+            JoinEdge::Line { direction, .. } => {
+                (-1e-4 <= (to - from).dot(direction)).then_some(PathItem::Line(Line([from, to])))
+            }
+            JoinEdge::Circle {
+                center,
+                radius,
+                sign,
+            } => {
+                let angle1 = (from - *center).atan2();
+                let delta = ((to - *center).atan2() - angle1 + PI).rem_euclid(TAU) - PI;
+                (-1e-4 <= delta * sign).then_some(PathItem::Arc(Arc {
+                    center: *center,
+                    radius: *radius,
+                    angle1,
+                    angle2: angle1 + delta,
+                }))
             }
         }
     }
@@ -286,3 +830,105 @@ fn intersect_line_and_line(p1: Point, p2: Point, p3: Point, p4: Point) -> Point
 fn point_is_right_side_of_line(p1: Point, p2: Point) -> bool {
     p1.x() * p2.y() < p1.y() * p2.x()
 }
+
+#[test]
+fn test() {
+    use crate::fill_rule::NonZero;
+    use crate::primitives::rect;
+
+    let p = |x: f32, y: f32| Point::from((x, y));
+    let contains = |path: &Path, x: f32, y: f32| path.contains(p(x, y), NonZero);
+
+    let corner = Path::from_points(&vec![p(0.0, 0.0), p(10.0, 0.0), p(10.0, 10.0)], false);
+    let outline = |join: Join| path_outline(&corner, 1.0, &join, &Cap::Butt);
+    assert!(contains(&outline(Join::Miter(1.5)), 10.9, -0.9));
+    assert!(!contains(&outline(Join::Miter(1.4)), 10.9, -0.9));
+    assert!(contains(&outline(Join::MiterClip(1.2)), 10.8, -0.8));
+    assert!(!contains(&outline(Join::MiterClip(1.2)), 10.9, -0.9));
+    assert!(contains(&outline(Join::Arcs(1.5)), 10.9, -0.9));
+
+    for square in [
+        rect(0.0, 0.0, 10.0, 10.0),
+        rect(0.0, 0.0, 10.0, 10.0).flip(),
+    ] {
+        let outline =
+            |align| path_outline_aligned(&square, 2.0, align, &Join::Miter(4.0), &Cap::Butt);
+        let inside = outline(StrokeAlign::Inside);
+        assert!(contains(&inside, 1.5, 5.0));
+        assert!(!contains(&inside, -0.5, 5.0));
+        assert!(!contains(&inside, 5.0, 5.0));
+        let outside = outline(StrokeAlign::Outside);
+        assert!(contains(&outside, -1.5, -1.5));
+        assert!(!contains(&outside, 0.5, 5.0));
+        let center = outline(StrokeAlign::Center);
+        assert!(contains(&center, -0.5, 5.0) && contains(&center, 0.5, 5.0));
+        // Inner corners of centered strokes are plain bevels crossing each other, not trimmed.
+        let bevels = center
+            .0
+            .iter()
+            .filter(|pi| match pi {
+                PathItem::Line(Line([a, b])) => {
+                    (a.x() - b.x()).abs() == 1.0 && (a.y() - b.y()).abs() == 1.0
+                }
+                _ => false,
+            })
+            .count();
+        assert_eq!(bevels, 4);
+        assert_eq!(center.0.len(), 22);
+    }
+
+    // Constant widths match the fixed width outline, and widths are interpolated along the path.
+    let polyline = Path::from_points(&vec![p(0.0, 0.0), p(10.0, 0.0), p(10.0, 10.0)], false);
+    let constant = WidthStops::Normalized(vec![(0.0, 4.0), (1.0, 4.0)]);
+    let variable = path_outline_variable(&polyline, &constant, &Join::Miter(4.0), &Cap::Butt, 0.1);
+    let fixed = path_outline(&polyline, 2.0, &Join::Miter(4.0), &Cap::Butt);
+    for i in 0..32 {
+        for j in 0..32 {
+            let (x, y) = (i as f32 * 0.5 - 3.0 + 0.013, j as f32 * 0.5 - 3.0 + 0.037);
+            assert_eq!(
+                contains(&variable, x, y),
+                contains(&fixed, x, y),
+                "{} {}",
+                x,
+                y
+            );
+        }
+    }
+    let tapered = WidthStops::Length(vec![(0.0, 0.0), (20.0, 4.0)]);
+    let variable = path_outline_variable(&polyline, &tapered, &Join::Bevel, &Cap::Butt, 0.1);
+    assert!(contains(&variable, 5.0, 0.4) && !contains(&variable, 5.0, 0.6));
+    assert!(contains(&variable, 11.4, 5.0) && !contains(&variable, 11.6, 5.0));
+
+    // The hull of a square and its inner points is the square.
+    let mut points = vec![
+        p(0.0, 0.0),
+        p(1.0, 1.0),
+        p(2.0, 0.0),
+        p(1.0, 0.5),
+        p(2.0, 2.0),
+        p(0.0, 2.0),
+    ];
+    let mut hull = convex_hull(&mut points);
+    hull.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+    assert_eq!(
+        hull,
+        vec![p(0.0, 0.0), p(0.0, 2.0), p(2.0, 0.0), p(2.0, 2.0)]
+    );
+
+    // A wide pen makes horizontal strokes thin and vertical strokes thick.
+    let pen = Pen::Ellipse {
+        radius_x: 3.0,
+        radius_y: 1.0,
+        angle: 0.0,
+    };
+    let calligraphic = path_outline_calligraphic(&polyline, &pen, 0.01);
+    assert!(contains(&calligraphic, 5.0, 0.9) && !contains(&calligraphic, 5.0, 1.1));
+    assert!(contains(&calligraphic, 12.9, 5.0) && !contains(&calligraphic, 13.1, 5.0));
+    let nib = Pen::Nib {
+        width: 4.0,
+        angle: 0.0,
+    };
+    let calligraphic = path_outline_calligraphic(&polyline, &nib, 0.01);
+    assert!(!contains(&calligraphic, 5.0, 0.1));
+    assert!(contains(&calligraphic, 11.9, 5.0) && !contains(&calligraphic, 12.1, 5.0));
+}
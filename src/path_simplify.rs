@@ -0,0 +1,339 @@
+//! Polyline simplification, cubic Bézier fitting and Catmull-Rom splines.
+
+use crate::models::{Cubic, Segment};
+use crate::path::{Path, PathItem};
+use crate::point::Point;
+
+/// Simplify a polyline with the Ramer–Douglas–Peucker algorithm.
+///
+/// Points closer than `tolerance` to the simplified polyline are removed. The end points are always kept.
+pub fn simplify_rdp(points: &[Point], tolerance: f32) -> Vec<Point> {
+    if points.len() <= 2 {
+        return points.to_vec();
+    }
+    let mut keep = vec![false; points.len()];
+    keep[0] = true;
+    keep[points.len() - 1] = true;
+    let mut stack = vec![(0, points.len() - 1)];
+    while let Some((first, last)) = stack.pop() {
+        let (mut max, mut index) = (0.0, first);
+        for i in first + 1..last {
+            let d = segment_distance(points[i], points[first], points[last]);
+            if max < d {
+                max = d;
+                index = i;
+            }
+        }
+        if tolerance < max {
+            keep[index] = true;
+            stack.push((first, index));
+            stack.push((index, last));
+        }
+    }
+    points
+        .iter()
+        .zip(keep)
+        .filter_map(|(p, keep)| keep.then_some(*p))
+        .collect()
+}
+
+/// Simplify a polyline with the Visvalingam–Whyatt algorithm.
+///
+/// The point which makes the smallest triangle with its neighbors is removed repeatedly
+/// while the area is less than `min_area`. The end points are always kept.
+pub fn simplify_visvalingam(points: &[Point], min_area: f32) -> Vec<Point> {
+    let mut points = points.to_vec();
+    let area =
+        |ps: &[Point], i: usize| (ps[i] - ps[i - 1]).cross(&(ps[i + 1] - ps[i - 1])).abs() / 2.0;
+    let mut areas: Vec<f32> = (1..points.len().saturating_sub(1))
+        .map(|i| area(&points, i))
+        .collect();
+    while let Some((i, a)) = areas
+        .iter()
+        .enumerate()
+        .min_by(|a, b| a.1.partial_cmp(b.1).unwrap())
+    {
+        if min_area <= *a {
+            break;
+        }
+        // `areas[i]` is the area of `points[i + 1]`.
+        points.remove(i + 1);
+        areas.remove(i);
+        if 0 < i {
+            areas[i - 1] = area(&points, i);
+        }
+        if i < areas.len() {
+            areas[i] = area(&points, i + 1);
+        }
+    }
+    points
+}
+
+/// Fit cubic Bézier curves to points with Schneider's algorithm.
+///
+/// The curves are split until all points are within `tolerance` from them.
+/// If `close` is `true`, the tangents at the first and last points are made continuous.
+pub fn fit_cubic(points: &[Point], tolerance: f32, close: bool) -> Vec<Cubic> {
+    let mut points = points.to_vec();
+    points.dedup();
+    let mut res = Vec::new();
+    let n = points.len();
+    if n < 2 {
+        return res;
+    }
+    let (tangent1, tangent2) = if close && 3 <= n && points[0] == points[n - 1] {
+        let t = (points[1] - points[n - 2]).unit();
+        (t, t * -1.0)
+    } else {
+        (
+            (points[1] - points[0]).unit(),
+            (points[n - 2] - points[n - 1]).unit(),
+        )
+    };
+    fit_cubic_rec(&points, tangent1, tangent2, tolerance.powi(2), &mut res);
+    res
+}
+
+fn fit_cubic_rec(
+    points: &[Point],
+    tangent1: Point,
+    tangent2: Point,
+    error: f32,
+    res: &mut Vec<Cubic>,
+) {
+    let n = points.len();
+    if n == 2 {
+        let d = (points[1] - points[0]).norm() / 3.0;
+        res.push(Cubic {
+            start: points[0],
+            end: points[1],
+            control1: points[0] + tangent1 * d,
+            control2: points[1] + tangent2 * d,
+        });
+        return;
+    }
+
+    let mut u = chord_length_parameterize(points);
+    let mut cubic = generate_bezier(points, &u, tangent1, tangent2);
+    let (mut max, mut split) = max_error(points, &cubic, &u);
+    if max < error {
+        res.push(cubic);
+        return;
+    }
+    // Improve the parameters if the error is not too large.
+    if max < error * 4.0 {
+        for _ in 0..4 {
+            u = reparameterize(points, &u, &cubic);
+            cubic = generate_bezier(points, &u, tangent1, tangent2);
+            (max, split) = max_error(points, &cubic, &u);
+            if max < error {
+                res.push(cubic);
+                return;
+            }
+        }
+    }
+
+    let center = (points[split - 1] - points[split + 1]).unit();
+    fit_cubic_rec(&points[..=split], tangent1, center, error, res);
+    fit_cubic_rec(&points[split..], center * -1.0, tangent2, error, res);
+}
+
+fn chord_length_parameterize(points: &[Point]) -> Vec<f32> {
+    let mut u = Vec::with_capacity(points.len());
+    u.push(0.0);
+    for i in 1..points.len() {
+        u.push(u[i - 1] + (points[i] - points[i - 1]).norm());
+    }
+    let total = u[u.len() - 1];
+    u.iter().map(|x| x / total).collect()
+}
+
+/// Least-squares fitting of the control points along the fixed tangents.
+fn generate_bezier(points: &[Point], u: &[f32], tangent1: Point, tangent2: Point) -> Cubic {
+    let (first, last) = (points[0], points[points.len() - 1]);
+    let mut c = [[0.0; 2]; 2];
+    let mut x = [0.0; 2];
+    for (p, &t) in points.iter().zip(u) {
+        let it = 1.0 - t;
+        let a1 = tangent1 * (3.0 * it * it * t);
+        let a2 = tangent2 * (3.0 * it * t * t);
+        c[0][0] += a1.dot(&a1);
+        c[0][1] += a1.dot(&a2);
+        c[1][1] += a2.dot(&a2);
+        let tmp = *p - (first * (it * it * (1.0 + 2.0 * t)) + last * (t * t * (3.0 - 2.0 * t)));
+        x[0] += a1.dot(&tmp);
+        x[1] += a2.dot(&tmp);
+    }
+    c[1][0] = c[0][1];
+    let det = c[0][0] * c[1][1] - c[1][0] * c[0][1];
+    let (alpha1, alpha2) = if det == 0.0 {
+        (0.0, 0.0)
+    } else {
+        (
+            (x[0] * c[1][1] - x[1] * c[0][1]) / det,
+            (c[0][0] * x[1] - c[1][0] * x[0]) / det,
+        )
+    };
+    // Fall back to the heuristic when the solution is degenerate
+    // or the control points projected on the chord are out of order.
+    let chord = last - first;
+    let segment = chord.norm();
+    let epsilon = 1e-6 * segment;
+    let (alpha1, alpha2) = if alpha1 < epsilon
+        || alpha2 < epsilon
+        || segment.powi(2) < (tangent1 * alpha1 - tangent2 * alpha2).dot(&chord)
+    {
+        (segment / 3.0, segment / 3.0)
+    } else {
+        (alpha1, alpha2)
+    };
+    Cubic {
+        start: first,
+        end: last,
+        control1: first + tangent1 * alpha1,
+        control2: last + tangent2 * alpha2,
+    }
+}
+
+/// Maximum squared distance between the points and the curve, and the index of the farthest point.
+fn max_error(points: &[Point], cubic: &Cubic, u: &[f32]) -> (f32, usize) {
+    let mut max = 0.0;
+    let mut split = points.len() / 2;
+    for i in 1..points.len() - 1 {
+        let v = cubic.pos(u[i]) - points[i];
+        let d = v.dot(&v);
+        if max <= d {
+            max = d;
+            split = i;
+        }
+    }
+    (max, split)
+}
+
+/// Newton-Raphson iteration for the closest parameters on the curve.
+fn reparameterize(points: &[Point], u: &[f32], cubic: &Cubic) -> Vec<f32> {
+    let q2 = |t: f32| {
+        let p = [cubic.start, cubic.control1, cubic.control2, cubic.end];
+        let d2 = [
+            (p[2] - p[1] * 2.0 + p[0]) * 6.0,
+            (p[3] - p[2] * 2.0 + p[1]) * 6.0,
+        ];
+        d2[0] * (1.0 - t) + d2[1] * t
+    };
+    points
+        .iter()
+        .zip(u)
+        .map(|(p, &t)| {
+            let d = cubic.pos(t) - *p;
+            let d1 = cubic.derivative(t);
+            let numerator = d.dot(&d1);
+            let denominator = d1.dot(&d1) + d.dot(&q2(t));
+            if denominator == 0.0 {
+                t
+            } else {
+                (t - numerator / denominator).clamp(0.0, 1.0)
+            }
+        })
+        .collect()
+}
+
+/// Create cubic Bézier curves of a Catmull-Rom spline passing through all points.
+///
+/// `alpha` selects the parameterization: `0.0` for uniform, `0.5` for centripetal and `1.0` for chordal.
+/// The centripetal one has no cusps and self-intersections within a segment.
+pub fn catmull_rom(points: &[Point], close: bool, alpha: f32) -> Vec<Cubic> {
+    let mut points = points.to_vec();
+    points.dedup();
+    if close && 2 <= points.len() && points[0] == points[points.len() - 1] {
+        points.pop();
+    }
+    let n = points.len();
+    if n < 2 {
+        return Vec::new();
+    }
+    let get = |i: isize| -> Point {
+        if close {
+            points[i.rem_euclid(n as isize) as usize]
+        } else if i < 0 {
+            // Mirror the end points.
+            points[0] * 2.0 - points[1]
+        } else if n as isize <= i {
+            points[n - 1] * 2.0 - points[n - 2]
+        } else {
+            points[i as usize]
+        }
+    };
+    let segments = if close { n } else { n - 1 };
+    (0..segments as isize)
+        .map(|i| {
+            let (p0, p1, p2, p3) = (get(i - 1), get(i), get(i + 1), get(i + 2));
+            let knot = |a: Point, b: Point| (b - a).norm().powf(alpha).max(1e-6);
+            let (d1, d2, d3) = (knot(p0, p1), knot(p1, p2), knot(p2, p3));
+            // Tangents at `p1` and `p2` scaled to the parameter range of this segment.
+            let m1 = ((p1 - p0) / d1 - (p2 - p0) / (d1 + d2) + (p2 - p1) / d2) * d2;
+            let m2 = ((p2 - p1) / d2 - (p3 - p1) / (d2 + d3) + (p3 - p2) / d3) * d2;
+            Cubic {
+                start: p1,
+                end: p2,
+                control1: p1 + m1 / 3.0,
+                control2: p2 - m2 / 3.0,
+            }
+        })
+        .collect()
+}
+
+/// Create a [`Path`] from cubic Bézier curves.
+pub fn cubics_to_path(cubics: Vec<Cubic>, close: bool) -> Path {
+    let mut pis: Vec<PathItem> = cubics.into_iter().map(PathItem::Cubic).collect();
+    if close && !pis.is_empty() {
+        pis.push(PathItem::CloseAndJump);
+    }
+    Path::new(pis)
+}
+
+fn segment_distance(p: Point, a: Point, b: Point) -> f32 {
+    let v = b - a;
+    let l = v.dot(&v);
+    if l == 0.0 {
+        return (p - a).norm();
+    }
+    let t = ((p - a).dot(&v) / l).clamp(0.0, 1.0);
+    (p - (a + v * t)).norm()
+}
+
+#[test]
+fn test() {
+    let line: Vec<Point> = (0..=10).map(|i| Point::from((i as f32, 0.0))).collect();
+    assert_eq!(simplify_rdp(&line, 0.1).len(), 2);
+    assert_eq!(simplify_visvalingam(&line, 0.1).len(), 2);
+
+    let zigzag: Vec<Point> = (0..=10)
+        .map(|i| Point::from((i as f32, (i % 2) as f32)))
+        .collect();
+    assert_eq!(simplify_rdp(&zigzag, 0.5).len(), 11);
+    assert_eq!(simplify_visvalingam(&zigzag, 0.5).len(), 11);
+    assert!(simplify_visvalingam(&zigzag, 1.5).len() < 11);
+
+    let arc: Vec<Point> = (0..=100)
+        .map(|i| Point::from_angle(i as f32 / 100.0 * std::f32::consts::PI) * 100.0)
+        .collect();
+    let cubics = fit_cubic(&arc, 0.5, false);
+    assert!(cubics.len() < 10);
+    for p in arc.iter() {
+        let d = cubics
+            .iter()
+            .flat_map(|c| (0..=200).map(move |i| (c.pos(i as f32 / 200.0) - *p).norm()))
+            .fold(f32::INFINITY, f32::min);
+        assert!(d < 0.6, "{}", d);
+    }
+
+    let square = [
+        Point::from((0.0, 0.0)),
+        Point::from((1.0, 0.0)),
+        Point::from((1.0, 1.0)),
+        Point::from((0.0, 1.0)),
+    ];
+    let cubics = catmull_rom(&square, true, 0.5);
+    assert_eq!(cubics.len(), 4);
+    assert_eq!(cubics[3].end, square[0]);
+}
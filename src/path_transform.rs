@@ -1,7 +1,8 @@
 //! Path transformation.
 
+use crate::homography::Homography;
 use crate::matrix::Matrix;
-use crate::models::{Cubic, Ellipse, Line, Quad};
+use crate::models::{Cubic, Ellipse, Line, Quad, Segment};
 use crate::path::{Path, PathItem};
 use crate::point::Point;
 use std::f32::consts::TAU;
@@ -48,6 +49,70 @@ pub fn path_transform(path: &Path, matrix: &Matrix) -> Path {
     }
 }
 
+/// Transform a path with the [`Homography`].
+///
+/// Projection does not preserve curves, so curves are adaptively subdivided into lines
+/// until the error in the destination is under `tolerance`.
+pub fn path_project(path: &Path, homography: &Homography, tolerance: f32) -> Path {
+    let mut pis = Vec::with_capacity(path.0.len());
+    for pi in path.0.iter() {
+        let f = |t: f32| -> Point {
+            homography.apply(match pi {
+                PathItem::Arc(arc) => arc.eval(t),
+                PathItem::Ellipse(ellipse) => ellipse.eval(t),
+                PathItem::Quad(quad) => quad.eval(t),
+                PathItem::Cubic(cubic) => cubic.eval(t),
+                _ => unreachable!(),
+            })
+        };
+        match pi {
+            PathItem::Line(line) => pis.push(PathItem::Line(Line([
+                homography.apply(line.0[0]),
+                homography.apply(line.0[1]),
+            ]))),
+            PathItem::CloseAndJump | PathItem::Jump => pis.push(pi.clone()),
+            _ => {
+                let (p0, p1) = (f(0.0), f(1.0));
+                subdivide(&mut pis, &f, (0.0, p0), (1.0, p1), tolerance, 0);
+            }
+        }
+    }
+    if homography.is_direct() {
+        Path::new(pis)
+    } else {
+        Path::new(pis).flip()
+    }
+}
+
+fn subdivide(
+    pis: &mut Vec<PathItem>,
+    f: &impl Fn(f32) -> Point,
+    (t0, p0): (f32, Point),
+    (t1, p1): (f32, Point),
+    tolerance: f32,
+    depth: usize,
+) {
+    let t = (t0 + t1) / 2.0;
+    let p = f(t);
+    let error = distance_to_segment(p, p0, p1);
+    if depth < 16 && (depth < 2 || tolerance < error) {
+        subdivide(pis, f, (t0, p0), (t, p), tolerance, depth + 1);
+        subdivide(pis, f, (t, p), (t1, p1), tolerance, depth + 1);
+    } else if p0 != p1 {
+        pis.push(PathItem::Line(Line([p0, p1])));
+    }
+}
+
+fn distance_to_segment(p: Point, a: Point, b: Point) -> f32 {
+    let v = b - a;
+    let l = v.dot(&v);
+    if l == 0.0 {
+        return (p - a).norm();
+    }
+    let t = ((p - a).dot(&v) / l).clamp(0.0, 1.0);
+    (p - a.lerp(b, t)).norm()
+}
+
 fn transform_ellipse(ellipse: &Ellipse, matrix: &Matrix) -> Ellipse {
     let matrix = Matrix::new()
         .scale(ellipse.radius_x, ellipse.radius_y)
@@ -19,9 +19,15 @@ pub trait Arithmetic:
     fn zero() -> Self;
 }
 
-impl Into<Rgba> for PremultipliedRgba {
-    fn into(self) -> Rgba {
-        let [r, g, b, a] = self.0;
+/// Channel-wise minimum and maximum of pixels.
+pub trait MinMax {
+    fn min(&self, rhs: &Self) -> Self;
+    fn max(&self, rhs: &Self) -> Self;
+}
+
+impl From<PremultipliedRgba> for Rgba {
+    fn from(p: PremultipliedRgba) -> Rgba {
+        let [r, g, b, a] = p.0;
         if a != 0.0 {
             Rgba([r / a, g / a, b / a, a])
         } else {
@@ -30,9 +36,9 @@ impl Into<Rgba> for PremultipliedRgba {
     }
 }
 
-impl Into<PremultipliedRgba> for Rgba {
-    fn into(self) -> PremultipliedRgba {
-        let [r, g, b, a] = self.0;
+impl From<Rgba> for PremultipliedRgba {
+    fn from(p: Rgba) -> PremultipliedRgba {
+        let [r, g, b, a] = p.0;
         PremultipliedRgba([r * a, g * a, b * a, a])
     }
 }
@@ -42,3 +48,19 @@ impl Pixel for f32 {
         self + (rhs - self) * rate
     }
 }
+
+impl Arithmetic for f32 {
+    fn zero() -> Self {
+        0.0
+    }
+}
+
+impl MinMax for f32 {
+    fn min(&self, rhs: &Self) -> Self {
+        f32::min(*self, *rhs)
+    }
+
+    fn max(&self, rhs: &Self) -> Self {
+        f32::max(*self, *rhs)
+    }
+}
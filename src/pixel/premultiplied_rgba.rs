@@ -75,3 +75,23 @@ impl Arithmetic for PremultipliedRgba {
         PremultipliedRgba([0.0, 0.0, 0.0, 0.0])
     }
 }
+
+impl MinMax for PremultipliedRgba {
+    fn min(&self, rhs: &Self) -> Self {
+        PremultipliedRgba([
+            self.0[0].min(rhs.0[0]),
+            self.0[1].min(rhs.0[1]),
+            self.0[2].min(rhs.0[2]),
+            self.0[3].min(rhs.0[3]),
+        ])
+    }
+
+    fn max(&self, rhs: &Self) -> Self {
+        PremultipliedRgba([
+            self.0[0].max(rhs.0[0]),
+            self.0[1].max(rhs.0[1]),
+            self.0[2].max(rhs.0[2]),
+            self.0[3].max(rhs.0[3]),
+        ])
+    }
+}
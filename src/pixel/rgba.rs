@@ -72,3 +72,23 @@ impl Arithmetic for Rgba {
         Rgba([0.0, 0.0, 0.0, 0.0])
     }
 }
+
+impl MinMax for Rgba {
+    fn min(&self, rhs: &Self) -> Self {
+        Rgba([
+            self.0[0].min(rhs.0[0]),
+            self.0[1].min(rhs.0[1]),
+            self.0[2].min(rhs.0[2]),
+            self.0[3].min(rhs.0[3]),
+        ])
+    }
+
+    fn max(&self, rhs: &Self) -> Self {
+        Rgba([
+            self.0[0].max(rhs.0[0]),
+            self.0[1].max(rhs.0[1]),
+            self.0[2].max(rhs.0[2]),
+            self.0[3].max(rhs.0[3]),
+        ])
+    }
+}
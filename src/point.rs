@@ -21,6 +21,11 @@ impl Point {
         self.x() * rhs.x() + self.y() * rhs.y()
     }
 
+    /// The z component of the cross product.
+    pub fn cross(&self, rhs: &Self) -> f32 {
+        self.x() * rhs.y() - self.y() * rhs.x()
+    }
+
     pub fn lerp(self, rhs: Self, v: f32) -> Self {
         self + (rhs - self) * v
     }
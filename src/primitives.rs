@@ -1,11 +1,12 @@
 //! Primitive shape generators.
 
 use crate::{
-    models::Arc,
+    models::{Arc, Ellipse},
     path::{Path, PathItem},
+    path_builder::PathBuilder,
     point::Point,
 };
-use std::f32::consts::TAU;
+use std::f32::consts::{FRAC_PI_2, PI, TAU};
 
 /// Create a circle.
 pub fn circle(x: f32, y: f32, radius: f32) -> Path {
@@ -51,3 +52,245 @@ pub fn ngon(x: f32, y: f32, n: usize, size: f32) -> Path {
         true,
     )
 }
+
+/// Create an ellipse.
+pub fn ellipse(x: f32, y: f32, radius_x: f32, radius_y: f32) -> Path {
+    Path::new(vec![
+        PathItem::Ellipse(Ellipse {
+            center: Point([x, y]),
+            radius_x,
+            radius_y,
+            rotation: 0.0,
+            angle1: 0.0,
+            angle2: TAU,
+        }),
+        PathItem::CloseAndJump,
+    ])
+}
+
+/// Create a rectangle with rounded corners.
+///
+/// `radii` are for top-left, top-right, bottom-right and bottom-left corners.
+/// Like CSS `border-radius`, all radii are scaled down if adjacent radii overlap.
+pub fn rounded_rect(x: f32, y: f32, width: f32, height: f32, radii: [f32; 4]) -> Path {
+    let [tl, tr, br, bl] = radii.map(|r| r.max(0.0));
+    let f = [
+        width / (tl + tr),
+        height / (tr + br),
+        width / (br + bl),
+        height / (bl + tl),
+    ]
+    .iter()
+    .fold(1.0f32, |a, b| a.min(*b));
+    let [tl, tr, br, bl] = [tl * f, tr * f, br * f, bl * f];
+    let mut pb = PathBuilder::new();
+    pb.move_to(x + tl, y);
+    pb.arc(x + width - tr, y + tr, tr, -FRAC_PI_2, 0.0);
+    pb.arc(x + width - br, y + height - br, br, 0.0, FRAC_PI_2);
+    pb.arc(x + bl, y + height - bl, bl, FRAC_PI_2, PI);
+    pb.arc(x + tl, y + tl, tl, PI, PI + FRAC_PI_2);
+    pb.close();
+    pb.end()
+}
+
+/// Create an open arc from `angle1` to `angle2`.
+pub fn arc(x: f32, y: f32, radius: f32, angle1: f32, angle2: f32) -> Path {
+    Path::new(vec![PathItem::Arc(Arc {
+        center: Point([x, y]),
+        radius,
+        angle1,
+        angle2,
+    })])
+}
+
+/// Create a pie (circular sector) from `angle1` to `angle2`.
+pub fn pie(x: f32, y: f32, radius: f32, angle1: f32, angle2: f32) -> Path {
+    let mut pb = PathBuilder::new();
+    pb.move_to(x, y);
+    pb.arc(x, y, radius, angle1, angle2);
+    pb.close();
+    pb.end()
+}
+
+/// Create a segment of a donut (annulus sector) from `angle1` to `angle2`.
+///
+/// If the angle covers the whole circle, the donut is made of two circles with opposite directions.
+pub fn donut(
+    x: f32,
+    y: f32,
+    inner_radius: f32,
+    outer_radius: f32,
+    angle1: f32,
+    angle2: f32,
+) -> Path {
+    let mut pb = PathBuilder::new();
+    if TAU <= (angle2 - angle1).abs() {
+        pb.arc(x, y, outer_radius, angle1, angle1 + TAU);
+        pb.close();
+        pb.arc(x, y, inner_radius, angle1 + TAU, angle1);
+    } else {
+        pb.arc(x, y, outer_radius, angle1, angle2);
+        pb.arc(x, y, inner_radius, angle2, angle1);
+    }
+    pb.close();
+    pb.end()
+}
+
+/// Create a star with `n` points.
+pub fn star(x: f32, y: f32, n: usize, outer_radius: f32, inner_radius: f32) -> Path {
+    assert!(2 <= n);
+    let center = Point([x, y]);
+    Path::from_points(
+        &(0..=n * 2)
+            .map(|i| {
+                let r = if i % 2 == 0 {
+                    outer_radius
+                } else {
+                    inner_radius
+                };
+                center + Point([0.0, -r]).rotate((i % (n * 2)) as f32 / (n * 2) as f32 * TAU)
+            })
+            .collect::<Vec<_>>(),
+        true,
+    )
+}
+
+/// Create a superellipse `|x / radius_x|^n + |y / radius_y|^n = 1`.
+///
+/// Each quadrant is approximated with a cubic bezier curve which passes the exact point at 45 degrees.
+/// `n = 2` makes an ellipse and `n = 4` makes a squircle.
+pub fn superellipse(x: f32, y: f32, radius_x: f32, radius_y: f32, n: f32) -> Path {
+    let k = (2.0f32.powf(-1.0 / n) - 0.5) * 8.0 / 3.0;
+    let (rx, ry) = (radius_x, radius_y);
+    let mut pb = PathBuilder::new();
+    pb.move_to(x + rx, y);
+    pb.cubic(x + rx, y + ry * k, x + rx * k, y + ry, x, y + ry);
+    pb.cubic(x - rx * k, y + ry, x - rx, y + ry * k, x - rx, y);
+    pb.cubic(x - rx, y - ry * k, x - rx * k, y - ry, x, y - ry);
+    pb.cubic(x + rx * k, y - ry, x + rx, y - ry * k, x + rx, y);
+    pb.close();
+    pb.end()
+}
+
+/// Create a regular n-gon with rounded corners.
+pub fn rounded_ngon(x: f32, y: f32, n: usize, size: f32, radius: f32) -> Path {
+    assert!(3 <= n);
+    let half = PI / n as f32;
+    // Corners are limited not to exceed the half of the edges.
+    let radius = radius.min(size * half.cos()).max(0.0);
+    let d = size - radius / half.cos();
+    let mut pb = PathBuilder::new();
+    for i in 0..n {
+        let angle = i as f32 / n as f32 * TAU - FRAC_PI_2;
+        let c = Point([x, y]) + Point::from_angle(angle) * d;
+        pb.arc(c.x(), c.y(), radius, angle - half, angle + half);
+    }
+    pb.close();
+    pb.end()
+}
+
+/// Create an arrow from (`x1`, `y1`) to (`x2`, `y2`).
+///
+/// Returns an empty path if the start and the end are the same point.
+pub fn arrow(
+    x1: f32,
+    y1: f32,
+    x2: f32,
+    y2: f32,
+    shaft_width: f32,
+    head_width: f32,
+    head_length: f32,
+) -> Path {
+    let start = Point([x1, y1]);
+    let end = Point([x2, y2]);
+    let length = (end - start).norm();
+    if length == 0.0 {
+        return Path::new(vec![]);
+    }
+    let head_length = head_length.min(length);
+    let u = (end - start) / length;
+    let v = Point([-u.y(), u.x()]);
+    let neck = end - u * head_length;
+    Path::from_points(
+        &vec![
+            start + v * (shaft_width / 2.0),
+            neck + v * (shaft_width / 2.0),
+            neck + v * (head_width / 2.0),
+            end,
+            neck - v * (head_width / 2.0),
+            neck - v * (shaft_width / 2.0),
+            start - v * (shaft_width / 2.0),
+            start + v * (shaft_width / 2.0),
+        ],
+        true,
+    )
+}
+
+#[test]
+fn test() {
+    use crate::fill_rule::NonZero;
+    use crate::path_hit_test::path_contains;
+
+    let close = |path: &Path, expected: [f32; 4]| {
+        let b = path.bounds().unwrap();
+        assert!(
+            b.iter().zip(expected).all(|(a, b)| (a - b).abs() < 1e-3),
+            "{:?} != {:?}",
+            b,
+            expected
+        );
+    };
+    let contains = |path: &Path, x: f32, y: f32| path_contains(path, Point([x, y]), NonZero);
+
+    close(&ellipse(10.0, 20.0, 5.0, 3.0), [5.0, 15.0, 17.0, 23.0]);
+    let rr = rounded_rect(0.0, 0.0, 10.0, 20.0, [2.0, 8.0, 8.0, 2.0]);
+    close(&rr, [0.0, 10.0, 0.0, 20.0]);
+    assert!(contains(&rr, 0.5, 10.0) && !contains(&rr, 9.9, 0.1));
+    let p = pie(0.0, 0.0, 10.0, 0.0, FRAC_PI_2);
+    close(&p, [0.0, 10.0, 0.0, 10.0]);
+    assert!(contains(&p, 5.0, 5.0) && !contains(&p, -5.0, 5.0));
+    let d = donut(0.0, 0.0, 5.0, 10.0, 0.0, TAU);
+    close(&d, [-10.0, 10.0, -10.0, 10.0]);
+    assert!(contains(&d, 7.5, 0.0) && !contains(&d, 0.0, 0.0));
+    let d = donut(0.0, 0.0, 5.0, 10.0, 0.0, PI);
+    assert!(contains(&d, 0.0, 7.5) && !contains(&d, 0.0, -7.5));
+    let s = star(0.0, 0.0, 5, 10.0, 4.0);
+    close(
+        &s,
+        [
+            -10.0 * (0.4 * PI).sin(),
+            10.0 * (0.4 * PI).sin(),
+            -10.0,
+            10.0 * (0.2 * PI).cos(),
+        ],
+    );
+    assert!(contains(&s, 0.0, -9.0) && !contains(&s, 0.0, 9.0));
+    let se = superellipse(0.0, 0.0, 10.0, 5.0, 4.0);
+    close(&se, [-10.0, 10.0, -5.0, 5.0]);
+    let k = 2.0f32.powf(-0.25);
+    assert!(
+        contains(&se, 10.0 * k - 0.01, 5.0 * k - 0.01)
+            && !contains(&se, 10.0 * k + 0.01, 5.0 * k + 0.01)
+    );
+    let a = arrow(0.0, 0.0, 20.0, 0.0, 2.0, 6.0, 5.0);
+    close(&a, [0.0, 20.0, -3.0, 3.0]);
+    assert!(contains(&a, 10.0, 0.9) && !contains(&a, 10.0, 1.1) && contains(&a, 16.0, 2.0));
+    assert!(arrow(5.0, 5.0, 5.0, 5.0, 2.0, 6.0, 5.0).0.is_empty());
+
+    // Rounded corners fit within the half of the edges.
+    // The largest radius of a triangle makes its incircle.
+    close(
+        &rounded_ngon(0.0, 0.0, 3, 10.0, 20.0),
+        [-5.0, 5.0, -5.0, 5.0],
+    );
+    let apothem = 10.0 * (PI / 6.0).cos();
+    let d = 10.0 - 8.0 / (PI / 6.0).cos();
+    close(
+        &rounded_ngon(0.0, 0.0, 6, 10.0, 8.0),
+        [-apothem, apothem, -d - 8.0, d + 8.0],
+    );
+    close(
+        &rounded_ngon(0.0, 0.0, 6, 10.0, 0.0),
+        [-apothem, apothem, -10.0, 10.0],
+    );
+}
@@ -0,0 +1,209 @@
+//! [`Shadow`] effects drawn with the blurred coverage of shapes.
+//!
+//! Set [`Context::shadows`](crate::context::Context::shadows) to apply them to fills, strokes and images.
+//! Shadows are cast by the coverage of the shape, not by the colors drawn in it.
+//! Images cast rectangular shadows of their destination quads, even where they are transparent.
+//! Use [`Context::filter_layer`](crate::context::Context::filter_layer) with a filter on
+//! [`FilterInput::SourceAlpha`](crate::filter::FilterInput::SourceAlpha) for shadows following the alpha.
+
+use crate::buffer::{Buffer, GenericBuffer};
+use crate::contrib::gauss_blur::{gauss_blur, Extrapolation};
+use crate::fill_rule::FillRule;
+use crate::matrix::Matrix;
+use crate::path::Path;
+use crate::path_segments::Segments;
+use crate::path_transform::path_transform;
+use crate::pixel::Pixel;
+use crate::rasterize::RasterizeBuffer;
+
+/// A shadow effect. `blur` is twice the standard deviation of the gaussian blur like Canvas2D `shadowBlur`.
+#[derive(Debug, Clone)]
+pub enum Shadow<P: Pixel> {
+    /// Shadow under the shape like Canvas2D `shadowColor`, `shadowBlur` and `shadowOffsetX/Y`.
+    /// The offset is in the device coordinates and is not transformed.
+    Drop {
+        color: P,
+        blur: f32,
+        offset_x: f32,
+        offset_y: f32,
+    },
+    /// Glow around the shape. `spread` from 0 to 1 makes the glow denser.
+    Glow { color: P, blur: f32, spread: f32 },
+    /// Shadow inside the shape cast by its edges. It is drawn over the shape.
+    Inner {
+        color: P,
+        blur: f32,
+        offset_x: f32,
+        offset_y: f32,
+    },
+}
+
+impl<P: Pixel> Shadow<P> {
+    pub fn color(&self) -> &P {
+        match self {
+            Shadow::Drop { color, .. }
+            | Shadow::Glow { color, .. }
+            | Shadow::Inner { color, .. } => color,
+        }
+    }
+
+    /// Whether the effect is drawn over the shape.
+    pub fn is_inner(&self) -> bool {
+        matches!(self, Shadow::Inner { .. })
+    }
+
+    /// Coverage of the effect for the flattened `path` in the device coordinates.
+    ///
+    /// Returns the position of the top left corner of the coverage buffer, which is confined to
+    /// the bounding box of the shape with the margin of the blur.
+    pub(crate) fn coverage(
+        &self,
+        path: &Path,
+        fill_rule: impl FillRule,
+        width: u32,
+        height: u32,
+    ) -> Option<([i32; 2], GenericBuffer<f32>)> {
+        let (blur, offset, spread) = match self {
+            Shadow::Drop {
+                blur,
+                offset_x,
+                offset_y,
+                ..
+            }
+            | Shadow::Inner {
+                blur,
+                offset_x,
+                offset_y,
+                ..
+            } => (*blur, [*offset_x, *offset_y], 0.0),
+            Shadow::Glow { blur, spread, .. } => (*blur, [0.0, 0.0], *spread),
+        };
+        let sigma = blur.max(0.0) / 2.0;
+        let pad = (sigma * 3.0).ceil() + 1.0;
+        let bounds = if fill_rule.is_inverse() {
+            [0.0, width as f32, 0.0, height as f32]
+        } else {
+            let b = path.control_bounds()?;
+            if self.is_inner() {
+                b
+            } else {
+                [
+                    b[0] + offset[0],
+                    b[1] + offset[0],
+                    b[2] + offset[1],
+                    b[3] + offset[1],
+                ]
+            }
+        };
+        let x0 = (bounds[0] - pad).floor().max(-pad);
+        let x1 = (bounds[1] + pad).ceil().min(width as f32 + pad);
+        let y0 = (bounds[2] - pad).floor().max(-pad);
+        let y1 = (bounds[3] + pad).ceil().min(height as f32 + pad);
+        if x1 <= x0 || y1 <= y0 {
+            return None;
+        }
+        let (w, h) = ((x1 - x0) as u32, (y1 - y0) as u32);
+
+        let mut buf = rasterize_coverage(path, fill_rule, offset[0] - x0, offset[1] - y0, w, h);
+        let extrapolation = if self.is_inner() {
+            for v in buf.buffer.iter_mut() {
+                *v = 1.0 - *v;
+            }
+            Extrapolation::Constant(1.0)
+        } else {
+            Extrapolation::Constant(0.0)
+        };
+        if 0.0 < sigma {
+            let mut tmp = GenericBuffer::from_pixel(w, h, 0.0);
+            gauss_blur(&mut buf, &mut tmp, sigma as f64, &extrapolation, 3);
+        }
+        match self {
+            Shadow::Inner { .. } => {
+                let shape = rasterize_coverage(path, fill_rule, -x0, -y0, w, h);
+                for (v, s) in buf.buffer.iter_mut().zip(shape.buffer.iter()) {
+                    *v *= s;
+                }
+            }
+            Shadow::Glow { .. } if 0.0 < spread => {
+                let k = 1.0 / (1.0 - spread.min(0.999));
+                for v in buf.buffer.iter_mut() {
+                    *v = (*v * k).min(1.0);
+                }
+            }
+            _ => {}
+        }
+        Some(([x0 as i32, y0 as i32], buf))
+    }
+}
+
+fn rasterize_coverage(
+    path: &Path,
+    fill_rule: impl FillRule,
+    dx: f32,
+    dy: f32,
+    width: u32,
+    height: u32,
+) -> GenericBuffer<f32> {
+    let path = path_transform(path, &Matrix::new().translate(dx, dy));
+    let mut buf = GenericBuffer::from_pixel(width, height, 0.0);
+    let mut rasterizer = RasterizeBuffer::new(width, height);
+    rasterizer.rasterize(
+        Segments::new(path.0.iter().cloned()),
+        fill_rule,
+        &mut |x, y, v| buf.put_pixel(x, y, v),
+        fill_rule.is_inverse(),
+    );
+    buf
+}
+
+#[test]
+fn test() {
+    use crate::compositor::SrcOver;
+    use crate::context::{Context, FillStyle};
+    use crate::fill_color::Solid;
+    use crate::fill_rule::NonZero;
+    use crate::pixel::Rgba;
+    use crate::primitives::rect;
+
+    let path = rect(20.0, 20.0, 20.0, 20.0);
+    let drop = Shadow::Drop {
+        color: 0.0f32,
+        blur: 4.0,
+        offset_x: 10.0,
+        offset_y: 0.0,
+    };
+    let ([x0, y0], buf) = drop.coverage(&path, NonZero, 100, 100).unwrap();
+    let at = |x: i32, y: i32| *buf.get_pixel((x - x0) as u32, (y - y0) as u32);
+    assert!((at(40, 30) - 1.0).abs() < 1e-3);
+    assert!(at(29, 30) < 0.5 && 0.5 < at(31, 30));
+    assert!(at(49, 30) > 0.5 && 0.5 > at(51, 30));
+
+    let inner = Shadow::Inner {
+        color: 0.0f32,
+        blur: 4.0,
+        offset_x: 4.0,
+        offset_y: 0.0,
+    };
+    let ([x0, y0], buf) = inner.coverage(&path, NonZero, 100, 100).unwrap();
+    let at = |x: i32, y: i32| *buf.get_pixel((x - x0) as u32, (y - y0) as u32);
+    assert!(at(15, 30) == 0.0);
+    assert!(at(21, 30) > 0.5 && at(38, 30) < 0.1);
+
+    // A glow spreads to all sides and is covered by the shape itself.
+    let background = Rgba([0.0, 0.0, 0.0, 1.0]);
+    let mut context = Context::from_pixel(60, 60, background);
+    context.shadows = vec![Shadow::Glow {
+        color: Rgba([0.0, 1.0, 0.0, 1.0]),
+        blur: 4.0,
+        spread: 0.5,
+    }];
+    let red = Rgba([1.0, 0.0, 0.0, 1.0]);
+    context.fill(&path, &FillStyle::new(Solid::new(red), SrcOver, NonZero));
+    for (x, y) in [(30, 18), (30, 41), (18, 30), (41, 30)] {
+        assert!(0.2 < context.image.get_pixel(x, y).0[1], "{x} {y}");
+    }
+    for (x, y) in [(30, 30), (21, 21), (38, 38)] {
+        assert_eq!(context.image.get_pixel(x, y).0, red.0);
+    }
+    assert_eq!(context.image.get_pixel(2, 2).0, background.0);
+}
@@ -0,0 +1,508 @@
+//! Convert glyph outlines of TrueType/OpenType fonts to [`Path`].
+//!
+//! This module requires `text` feature.
+
+use crate::{
+    matrix::Matrix, path::Path, path_builder::PathBuilder, path_measure::PathMeasure,
+    path_transform::path_transform, point::Point,
+};
+use std::fmt;
+use ttf_parser::{Face, FaceParsingError, GlyphId, OutlineBuilder};
+
+/// Error of loading a font.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FontError {
+    /// The data is not a TrueType/OpenType font or collection.
+    UnknownFormat,
+    /// The font collection has no face of the index.
+    FaceIndexOutOfBounds,
+    /// The data is truncated or a required table is missing.
+    Malformed,
+}
+
+impl From<FaceParsingError> for FontError {
+    fn from(e: FaceParsingError) -> FontError {
+        match e {
+            FaceParsingError::UnknownMagic => FontError::UnknownFormat,
+            FaceParsingError::FaceIndexOutOfBounds => FontError::FaceIndexOutOfBounds,
+            _ => FontError::Malformed,
+        }
+    }
+}
+
+impl fmt::Display for FontError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FontError::UnknownFormat => write!(f, "unknown font format"),
+            FontError::FaceIndexOutOfBounds => write!(f, "face index out of bounds"),
+            FontError::Malformed => write!(f, "malformed font"),
+        }
+    }
+}
+
+impl std::error::Error for FontError {}
+
+/// A font face.
+pub struct Font<'a> {
+    face: Face<'a>,
+}
+
+impl<'a> Font<'a> {
+    /// Load the first font face from TrueType/OpenType font data.
+    pub fn from_bytes(data: &'a [u8]) -> Result<Font<'a>, FontError> {
+        Font::from_bytes_with_index(data, 0)
+    }
+
+    /// Load the `index`th font face from font data.
+    pub fn from_bytes_with_index(data: &'a [u8], index: u32) -> Result<Font<'a>, FontError> {
+        Ok(Font {
+            face: Face::from_slice(data, index)?,
+        })
+    }
+
+    fn scale(&self, size: f32) -> f32 {
+        size / self.face.units_per_em() as f32
+    }
+
+    /// Distance from the baseline to the top of glyphs.
+    pub fn ascent(&self, size: f32) -> f32 {
+        self.face.ascender() as f32 * self.scale(size)
+    }
+
+    /// Distance from the baseline to the bottom of glyphs. It is usually negative.
+    pub fn descent(&self, size: f32) -> f32 {
+        self.face.descender() as f32 * self.scale(size)
+    }
+
+    /// Distance between the baselines of two lines.
+    pub fn line_height(&self, size: f32) -> f32 {
+        (self.face.ascender() - self.face.descender() + self.face.line_gap()) as f32
+            * self.scale(size)
+    }
+
+    /// Horizontal advance of the character.
+    pub fn advance(&self, c: char, size: f32) -> f32 {
+        self.face
+            .glyph_index(c)
+            .and_then(|id| self.face.glyph_hor_advance(id))
+            .unwrap_or(0) as f32
+            * self.scale(size)
+    }
+
+    /// Kerning between two characters.
+    ///
+    /// Only the legacy `kern` table is read; kerning in the `GPOS` table is ignored.
+    pub fn kerning(&self, left: char, right: char, size: f32) -> f32 {
+        let (left, right) = match (self.face.glyph_index(left), self.face.glyph_index(right)) {
+            (Some(left), Some(right)) => (left, right),
+            _ => return 0.0,
+        };
+        self.glyph_kerning(left, right) as f32 * self.scale(size)
+    }
+
+    fn glyph_kerning(&self, left: GlyphId, right: GlyphId) -> i32 {
+        self.face
+            .tables()
+            .kern
+            .map(|kern| {
+                kern.subtables
+                    .into_iter()
+                    .filter(|st| st.horizontal && !st.variable && !st.has_cross_stream)
+                    .filter_map(|st| st.glyphs_kerning(left, right))
+                    .map(i32::from)
+                    .sum()
+            })
+            .unwrap_or(0)
+    }
+
+    /// Get the outline of the character. The origin is on the baseline and y axis points downward.
+    ///
+    /// Returns `None` if the font has no outline for the character, e.g. a space.
+    pub fn glyph_path(&self, c: char, size: f32) -> Option<Path> {
+        let id = self.face.glyph_index(c)?;
+        let mut builder = GlyphBuilder {
+            builder: PathBuilder::new(),
+            scale: self.scale(size),
+        };
+        self.face.outline_glyph(id, &mut builder)?;
+        Some(builder.builder.end())
+    }
+}
+
+struct GlyphBuilder {
+    builder: PathBuilder,
+    scale: f32,
+}
+
+impl OutlineBuilder for GlyphBuilder {
+    fn move_to(&mut self, x: f32, y: f32) {
+        self.builder.move_to(x * self.scale, -y * self.scale);
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        self.builder.line_to(x * self.scale, -y * self.scale);
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        let s = self.scale;
+        self.builder.quad(x1 * s, -y1 * s, x * s, -y * s);
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        let s = self.scale;
+        self.builder
+            .cubic(x1 * s, -y1 * s, x2 * s, -y2 * s, x * s, -y * s);
+    }
+
+    fn close(&mut self) {
+        self.builder.close();
+    }
+}
+
+/// Horizontal alignment of lines.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Align {
+    Left,
+    Center,
+    Right,
+}
+
+/// Font and layout parameters of text.
+#[derive(Clone)]
+pub struct TextStyle<'a> {
+    pub font: &'a Font<'a>,
+    /// Font size in pixels.
+    pub size: f32,
+    pub align: Align,
+    /// Multiplier of the font's line height.
+    pub line_height: f32,
+}
+
+impl<'a> TextStyle<'a> {
+    pub fn new(font: &'a Font<'a>, size: f32) -> Self {
+        TextStyle {
+            font,
+            size,
+            align: Align::Left,
+            line_height: 1.0,
+        }
+    }
+}
+
+/// A glyph placed by [`layout`].
+#[derive(Debug, Clone)]
+pub struct Glyph {
+    pub c: char,
+    /// Outline of the glyph relative to `position`.
+    pub path: Option<Path>,
+    /// Origin of the glyph on the baseline.
+    pub position: Point,
+    pub advance: f32,
+}
+
+/// Lay out the text from left to right.
+///
+/// The first baseline is at `y = 0` and lines are aligned to `x = 0` according to [`TextStyle::align`].
+/// Lines are separated by `'\n'`.
+pub fn layout(text: &str, style: &TextStyle) -> Vec<Glyph> {
+    let font = style.font;
+    let line_height = font.line_height(style.size) * style.line_height;
+    let mut glyphs = Vec::with_capacity(text.len());
+    for (i, line) in text.lines().enumerate() {
+        let start = glyphs.len();
+        let mut x = 0.0;
+        let mut last = None;
+        for c in line.chars() {
+            if let Some(last) = last {
+                x += font.kerning(last, c, style.size);
+            }
+            let advance = font.advance(c, style.size);
+            glyphs.push(Glyph {
+                c,
+                path: font.glyph_path(c, style.size),
+                position: Point::from((x, i as f32 * line_height)),
+                advance,
+            });
+            x += advance;
+            last = Some(c);
+        }
+        let offset = match style.align {
+            Align::Left => 0.0,
+            Align::Center => -x / 2.0,
+            Align::Right => -x,
+        };
+        for glyph in &mut glyphs[start..] {
+            glyph.position = glyph.position + Point::from((offset, 0.0));
+        }
+    }
+    glyphs
+}
+
+/// Create a [`Path`] of the text whose first baseline starts at (`x`, `y`).
+pub fn text_path(text: &str, x: f32, y: f32, style: &TextStyle) -> Path {
+    let mut path = Path::new(Vec::new());
+    for glyph in layout(text, style) {
+        if let Some(glyph_path) = &glyph.path {
+            let p = glyph.position;
+            path.merge(&path_transform(
+                glyph_path,
+                &Matrix::new().translate(x + p.x(), y + p.y()),
+            ));
+        }
+    }
+    path
+}
+
+/// Which side of the path glyphs are placed on, for [`text_along_path`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Side {
+    /// Baselines lie on the path.
+    Above,
+    /// Glyphs hang below the path.
+    Below,
+}
+
+/// Placement options for [`text_along_path`].
+#[derive(Debug, Clone)]
+pub struct PathTextOptions {
+    /// Distance along the path where the text is anchored. [`TextStyle::align`] is applied at this point.
+    pub offset: f32,
+    pub side: Side,
+    /// Tolerance for flattening the path.
+    pub tolerance: f32,
+}
+
+impl Default for PathTextOptions {
+    fn default() -> Self {
+        PathTextOptions {
+            offset: 0.0,
+            side: Side::Above,
+            tolerance: 0.1,
+        }
+    }
+}
+
+/// Create a [`Path`] of the text laid out along `path`.
+///
+/// Each glyph is rotated to the tangent at the arc-length position of its center.
+/// Glyphs that do not fit on the path are dropped.
+pub fn text_along_path(
+    text: &str,
+    path: &Path,
+    style: &TextStyle,
+    options: &PathTextOptions,
+) -> Path {
+    let measure = PathMeasure::new(path, options.tolerance);
+    let shift = match options.side {
+        Side::Above => 0.0,
+        Side::Below => style.font.ascent(style.size),
+    };
+
+    let mut res = Path::new(Vec::new());
+    for glyph in layout(text, style) {
+        let glyph_path = match &glyph.path {
+            Some(path) => path,
+            None => continue,
+        };
+        let distance = options.offset + glyph.position.x() + glyph.advance / 2.0;
+        let (p, tangent) = match measure.position_and_tangent_at(distance) {
+            Some(pt) => pt,
+            None => continue,
+        };
+        let matrix = Matrix::new()
+            .translate(-glyph.advance / 2.0, glyph.position.y() + shift)
+            .rotate(tangent.atan2())
+            .translate(p.x(), p.y());
+        res.merge(&path_transform(glyph_path, &matrix));
+    }
+    res
+}
+
+#[test]
+fn test() {
+    // A font with 1000 units per em, where 'A' is a 300x700 rectangle with the advance 500
+    // and ' ' has no outline with the advance 250. The pair "AA" is kerned by -100.
+    fn table(data: &mut Vec<u8>, values: &[(i64, usize)]) {
+        for &(v, n) in values {
+            data.extend_from_slice(&v.to_be_bytes()[8 - n..]);
+        }
+    }
+    let mut head = Vec::new();
+    table(
+        &mut head,
+        &[(0x10000, 4), (0, 4), (0, 4), (0x5F0F3CF5, 4), (0, 2)],
+    );
+    table(&mut head, &[(1000, 2), (0, 8), (0, 8)]);
+    table(
+        &mut head,
+        &[
+            (0, 2),
+            (0, 2),
+            (400, 2),
+            (700, 2),
+            (0, 2),
+            (0, 2),
+            (0, 2),
+            (0, 2),
+            (0, 2),
+        ],
+    );
+    let mut hhea = Vec::new();
+    table(&mut hhea, &[(0x10000, 4), (800, 2), (-200, 2), (0, 2)]);
+    table(&mut hhea, &[(0, 2); 12]);
+    table(&mut hhea, &[(3, 2)]);
+    let mut maxp = Vec::new();
+    table(&mut maxp, &[(0x5000, 4), (3, 2)]);
+    let mut hmtx = Vec::new();
+    table(
+        &mut hmtx,
+        &[(500, 2), (0, 2), (500, 2), (100, 2), (250, 2), (0, 2)],
+    );
+    let mut cmap = Vec::new();
+    table(&mut cmap, &[(0, 2), (1, 2), (0, 2), (4, 2), (12, 4)]);
+    table(&mut cmap, &[(12, 2), (0, 2), (40, 4), (0, 4), (2, 4)]);
+    table(
+        &mut cmap,
+        &[(32, 4), (32, 4), (2, 4), (65, 4), (65, 4), (1, 4)],
+    );
+    let mut kern = Vec::new();
+    table(&mut kern, &[(0, 2), (1, 2), (0, 2), (20, 2), (1, 2)]);
+    table(&mut kern, &[(1, 2), (6, 2), (0, 2), (0, 2)]);
+    table(&mut kern, &[(1, 2), (1, 2), (-100, 2)]);
+    let mut glyf = Vec::new();
+    table(
+        &mut glyf,
+        &[(1, 2), (100, 2), (0, 2), (400, 2), (700, 2), (3, 2), (0, 2)],
+    );
+    table(&mut glyf, &[(1, 1); 4]);
+    table(&mut glyf, &[(100, 2), (0, 2), (300, 2), (0, 2)]);
+    table(&mut glyf, &[(0, 2), (700, 2), (0, 2), (-700, 2)]);
+    let mut loca = Vec::new();
+    table(
+        &mut loca,
+        &[
+            (0, 2),
+            (0, 2),
+            (glyf.len() as i64 / 2, 2),
+            (glyf.len() as i64 / 2, 2),
+        ],
+    );
+    let tables: [(&[u8; 4], Vec<u8>); 8] = [
+        (b"cmap", cmap),
+        (b"glyf", glyf),
+        (b"head", head),
+        (b"hhea", hhea),
+        (b"hmtx", hmtx),
+        (b"kern", kern),
+        (b"loca", loca),
+        (b"maxp", maxp),
+    ];
+    let mut data = Vec::new();
+    table(
+        &mut data,
+        &[
+            (0x10000, 4),
+            (tables.len() as i64, 2),
+            (0, 2),
+            (0, 2),
+            (0, 2),
+        ],
+    );
+    let mut offset = 12 + 16 * tables.len();
+    for (tag, t) in &tables {
+        data.extend_from_slice(*tag);
+        table(
+            &mut data,
+            &[(0, 4), (offset as i64, 4), (t.len() as i64, 4)],
+        );
+        offset += t.len();
+    }
+    for (_, t) in &tables {
+        data.extend_from_slice(t);
+    }
+    let font = Font::from_bytes(&data).unwrap();
+    assert_eq!(
+        Font::from_bytes(&data[..20]).err(),
+        Some(FontError::Malformed)
+    );
+    assert_eq!(
+        Font::from_bytes(&[0; 12]).err(),
+        Some(FontError::UnknownFormat)
+    );
+
+    assert_eq!(font.ascent(100.0), 80.0);
+    assert_eq!(font.descent(100.0), -20.0);
+    assert_eq!(font.line_height(100.0), 100.0);
+    assert_eq!(font.advance('A', 100.0), 50.0);
+    assert_eq!(font.kerning('A', 'A', 100.0), -10.0);
+    assert_eq!(font.kerning('A', ' ', 100.0), 0.0);
+    assert!(font.glyph_path(' ', 100.0).is_none());
+    assert_eq!(
+        font.glyph_path('A', 100.0).unwrap().bounds(),
+        Some([10.0, 40.0, -70.0, 0.0])
+    );
+
+    // Glyphs are placed by their advances and kerning, and lines are moved by the line height.
+    let mut style = TextStyle::new(&font, 100.0);
+    let positions = |style: &TextStyle| -> Vec<[f32; 2]> {
+        layout("AA A\nA", style)
+            .iter()
+            .map(|g| [g.position.x(), g.position.y()])
+            .collect()
+    };
+    assert_eq!(
+        positions(&style),
+        [
+            [0.0, 0.0],
+            [40.0, 0.0],
+            [90.0, 0.0],
+            [115.0, 0.0],
+            [0.0, 100.0]
+        ]
+    );
+    style.align = Align::Center;
+    style.line_height = 1.5;
+    assert_eq!(
+        positions(&style),
+        [
+            [-82.5, 0.0],
+            [-42.5, 0.0],
+            [7.5, 0.0],
+            [32.5, 0.0],
+            [-25.0, 150.0]
+        ]
+    );
+    style.align = Align::Right;
+    assert_eq!(positions(&style)[4], [-50.0, 150.0]);
+
+    // The text path is the union of the glyph outlines from the baseline at (x, y).
+    let style = TextStyle::new(&font, 100.0);
+    assert_eq!(
+        text_path("A A", 5.0, 100.0, &style).bounds(),
+        Some([15.0, 120.0, 30.0, 100.0])
+    );
+
+    // Along a straight path, glyphs stand on it or hang below it,
+    // and glyphs beyond the end are dropped.
+    let line = Path::from_points(&vec![Point([0.0, 100.0]), Point([100.0, 100.0])], false);
+    let options = PathTextOptions {
+        offset: 5.0,
+        ..Default::default()
+    };
+    let close = |a: Option<[f32; 4]>, b: [f32; 4]| {
+        a.unwrap().iter().zip(b).all(|(a, b)| (a - b).abs() < 1e-3)
+    };
+    let along = text_along_path("AAA", &line, &style, &options);
+    assert!(close(along.bounds(), [15.0, 85.0, 30.0, 100.0]));
+    let options = PathTextOptions {
+        side: Side::Below,
+        ..options
+    };
+    let along = text_along_path("AAA", &line, &style, &options);
+    assert!(close(along.bounds(), [15.0, 85.0, 110.0, 180.0]));
+
+    // Glyphs are rotated to the tangent of the path.
+    let line = Path::from_points(&vec![Point([100.0, 0.0]), Point([100.0, 100.0])], false);
+    let along = text_along_path("A", &line, &style, &Default::default());
+    assert!(close(along.bounds(), [100.0, 170.0, 10.0, 40.0]));
+}
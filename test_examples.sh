@@ -1,13 +1,28 @@
 set -e
 
 cargo run --release --example basic
+cargo run --release --example text --features text
 cargo run --release --example composite_test
 cargo run --release --example conic_grad
 cargo run --release --example fast_gauss_blur
 cargo run --release --example pseudo_blur
 cargo run --release --example path
 cargo run --release --example k_curve
+cargo run --release --example simplify
+cargo run --release --example path_effect
+cargo run --release --example variable_stroke
+cargo run --release --example stroke_align
+cargo run --release --example mask
+cargo run --release --example shadow
+cargo run --release --example filter
+cargo run --release --example morphology
+cargo run --release --example convolve
+cargo run --release --example color_adjust
+cargo run --release --example lighting
+cargo run --release --example warp
 cargo run --release --example context
+cargo run --release --example context_draw_image
+cargo run --release --example perspective
 cargo run --release --example path_data_notation
 cargo run --release --example nanachi
 